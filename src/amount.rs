@@ -0,0 +1,96 @@
+//! Hex-or-decimal `U256` parsing, adapted from CoW Protocol's
+//! `HexOrDecimalU256` serde helper: Polymarket/EVM APIs return big-integer
+//! fields (`tokenId`, `makerAmount`, `takerAmount`) as plain decimal
+//! strings, quoted integers, or `0x`-prefixed hex depending on the
+//! endpoint. The old code parsed these with `parse::<f64>()` or
+//! `U256::from_str_radix(_, 10)`, which either loses precision above 2^53
+//! or rejects hex outright — both silently wrong for a 78-digit token id.
+//! `parse` below handles either format losslessly; `deserialize`/
+//! `deserialize_opt` plug it into `#[serde(with = "amount")]` fields.
+//!
+//! Raw amounts stay `u128`/`U256` (6-decimal USDC units) everywhere along
+//! the signing and wire path; `usdc_to_raw`/`raw_to_usdc` are the only
+//! places an `f64` enters or leaves, kept to the edges (config/CLI input,
+//! log/display output) as the request asked.
+
+use alloy::primitives::U256;
+use anyhow::{Context, Result};
+use serde::{de::Error as _, Deserialize, Deserializer};
+
+/// Parses a `0x`-prefixed hex string or a plain decimal string into a `U256`.
+pub fn parse(s: &str) -> Result<U256> {
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => U256::from_str_radix(hex, 16).with_context(|| format!("invalid hex U256 {s:?}")),
+        None => U256::from_str_radix(s, 10).with_context(|| format!("invalid decimal U256 {s:?}")),
+    }
+}
+
+/// `#[serde(deserialize_with = "amount::deserialize")]` for a required
+/// `U256` field that may arrive as hex or decimal.
+pub fn deserialize<'de, D>(deserializer: D) -> Result<U256, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    parse(&s).map_err(D::Error::custom)
+}
+
+/// Same as `deserialize`, but a missing/null field becomes `None` instead
+/// of an error — for optional fill-amount fields that aren't present until
+/// an order partially matches.
+pub fn deserialize_opt<'de, D>(deserializer: D) -> Result<Option<U256>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s: Option<String> = Option::deserialize(deserializer)?;
+    s.as_deref().map(parse).transpose().map_err(D::Error::custom)
+}
+
+/// Converts a human-readable USDC amount to raw 6-decimal on-chain units.
+/// `.round()` is unavoidable here — the input is already an `f64` computed
+/// upstream (bet sizing, strategy config) — but this is the only place that
+/// conversion happens, rather than being repeated at every call site.
+pub fn usdc_to_raw(usdc: f64) -> u128 {
+    (usdc * 1e6).round() as u128
+}
+
+/// Inverse of `usdc_to_raw`, for display/logging only.
+pub fn raw_to_usdc(raw: u128) -> f64 {
+    raw as f64 / 1e6
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_decimal_strings() {
+        assert_eq!(parse("1000").unwrap(), U256::from(1000u64));
+        assert_eq!(parse("0").unwrap(), U256::ZERO);
+    }
+
+    #[test]
+    fn parses_hex_strings_case_insensitively() {
+        assert_eq!(parse("0x3e8").unwrap(), U256::from(1000u64));
+        assert_eq!(parse("0X3E8").unwrap(), U256::from(1000u64));
+    }
+
+    #[test]
+    fn parses_78_digit_token_ids_losslessly() {
+        let big = "1".repeat(78);
+        let parsed = parse(&big).unwrap();
+        assert_eq!(parsed.to_string(), big);
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert!(parse("not-a-number").is_err());
+        assert!(parse("0xnothex").is_err());
+    }
+
+    #[test]
+    fn usdc_raw_round_trip() {
+        assert_eq!(usdc_to_raw(1.5), 1_500_000);
+        assert_eq!(raw_to_usdc(1_500_000), 1.5);
+    }
+}
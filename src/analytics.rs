@@ -0,0 +1,184 @@
+//! Offline analytics over logged ticks (`TickLogger`'s CSV or
+//! `BinaryTickLogger`'s `.bin` format): rolling weighted-mean price,
+//! realized volatility, and tick rate, computed after the fact so users can
+//! study microstructure without re-running the live bot.
+//! `src/bin/tick_analytics.rs` streams a tick log through `WeightedMeanWindow`
+//! and emits one enriched row per tick, keyed by the original `window` so it
+//! can be joined back to `OutcomeLogger` rows.
+
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    timestamp_ms: u64,
+    price: f64,
+    weight: f64,
+}
+
+/// Rolling weighted-mean price over a fixed time span (e.g. 5s/30s/60s).
+/// Maintains running `sum_wp`/`sum_w` accumulators so `mean()` is O(1)
+/// instead of re-summing the whole window on every tick: `push` adds the new
+/// sample to the accumulators, then pops expired samples off the front,
+/// subtracting their contribution back out.
+///
+/// `weight` is supplied by the caller at push time — pass `1.0` for a
+/// uniform/count-based mean, or `exp_decay_weight` (or any other
+/// recency/confidence heuristic) for a weighted one.
+#[derive(Debug)]
+pub struct WeightedMeanWindow {
+    window_span_ms: u64,
+    samples: VecDeque<Sample>,
+    sum_wp: f64,
+    sum_w: f64,
+    last_timestamp_ms: Option<u64>,
+}
+
+impl WeightedMeanWindow {
+    pub fn new(window_span_ms: u64) -> Self {
+        Self { window_span_ms, samples: VecDeque::new(), sum_wp: 0.0, sum_w: 0.0, last_timestamp_ms: None }
+    }
+
+    /// Pushes a new tick. Ticks whose timestamp doesn't strictly advance past
+    /// the last one seen are dropped — out-of-order or duplicate timestamps
+    /// would otherwise corrupt the pop-from-front eviction below, which
+    /// assumes `samples` is sorted ascending by `timestamp_ms`.
+    pub fn push(&mut self, timestamp_ms: u64, price: f64, weight: f64) {
+        if let Some(last) = self.last_timestamp_ms {
+            if timestamp_ms <= last {
+                return;
+            }
+        }
+        self.last_timestamp_ms = Some(timestamp_ms);
+        self.samples.push_back(Sample { timestamp_ms, price, weight });
+        self.sum_wp += price * weight;
+        self.sum_w += weight;
+        while let Some(front) = self.samples.front() {
+            if front.timestamp_ms + self.window_span_ms < timestamp_ms {
+                self.sum_wp -= front.price * front.weight;
+                self.sum_w -= front.weight;
+                self.samples.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Weighted mean over the current window. `0.0` if empty or if every
+    /// buffered sample somehow carries zero weight — never divides by zero.
+    pub fn mean(&self) -> f64 {
+        if self.sum_w <= 0.0 {
+            0.0
+        } else {
+            self.sum_wp / self.sum_w
+        }
+    }
+
+    /// Realized volatility: std dev of tick-to-tick log returns (%) within
+    /// the current window. `0.0` with fewer than 3 samples, matching
+    /// `WindowTicks::micro_vol`'s threshold (need at least 2 returns for a
+    /// sample variance).
+    pub fn realized_vol(&self) -> f64 {
+        if self.samples.len() < 3 {
+            return 0.0;
+        }
+        let returns: Vec<f64> = self.samples.iter()
+            .zip(self.samples.iter().skip(1))
+            .map(|(a, b)| (b.price / a.price).ln() * 100.0)
+            .collect();
+        let n = returns.len() as f64;
+        let mean = returns.iter().sum::<f64>() / n;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        variance.sqrt()
+    }
+
+    /// Ticks per second implied by the current window's occupancy.
+    pub fn tick_rate(&self) -> f64 {
+        if self.samples.is_empty() {
+            0.0
+        } else {
+            self.samples.len() as f64 / (self.window_span_ms as f64 / 1000.0)
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
+
+/// `0.5.powf(age_ms / half_life_ms)` exponential decay: `1.0` at `age_ms ==
+/// 0`, halved every `half_life_ms`. A convenience `weight` for
+/// `WeightedMeanWindow::push` callers that want recency weighting instead of
+/// the uniform `1.0`.
+pub fn exp_decay_weight(age_ms: u64, half_life_ms: u64) -> f64 {
+    if half_life_ms == 0 {
+        return 1.0;
+    }
+    0.5_f64.powf(age_ms as f64 / half_life_ms as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mean_is_zero_on_empty_window() {
+        let w = WeightedMeanWindow::new(5_000);
+        assert_eq!(w.mean(), 0.0);
+        assert_eq!(w.tick_rate(), 0.0);
+        assert_eq!(w.realized_vol(), 0.0);
+    }
+
+    #[test]
+    fn uniform_mean_evicts_samples_outside_the_span() {
+        let mut w = WeightedMeanWindow::new(5_000);
+        w.push(0, 100.0, 1.0);
+        w.push(1_000, 102.0, 1.0);
+        assert_eq!(w.mean(), 101.0);
+
+        w.push(6_000, 104.0, 1.0); // evicts the t=0 sample (6000 - 5000 > 0)
+        assert_eq!(w.len(), 2);
+        assert_eq!(w.mean(), 103.0);
+    }
+
+    #[test]
+    fn non_advancing_timestamps_are_dropped() {
+        let mut w = WeightedMeanWindow::new(5_000);
+        w.push(1_000, 100.0, 1.0);
+        w.push(1_000, 999.0, 1.0); // duplicate timestamp, ignored
+        w.push(500, 999.0, 1.0); // goes backwards, ignored
+        assert_eq!(w.len(), 1);
+        assert_eq!(w.mean(), 100.0);
+    }
+
+    #[test]
+    fn recency_weighted_mean_favors_newer_samples() {
+        let mut w = WeightedMeanWindow::new(60_000);
+        w.push(0, 100.0, exp_decay_weight(0, 10_000));
+        w.push(30_000, 200.0, exp_decay_weight(0, 10_000));
+        // Both pushed with weight 1.0 (age 0 at push time) — caller would
+        // normally recompute ages against "now", but even with equal
+        // weights the window itself behaves like a plain mean.
+        assert_eq!(w.mean(), 150.0);
+    }
+
+    #[test]
+    fn exp_decay_weight_halves_at_the_half_life() {
+        assert!((exp_decay_weight(10_000, 10_000) - 0.5).abs() < 1e-9);
+        assert_eq!(exp_decay_weight(0, 10_000), 1.0);
+        assert_eq!(exp_decay_weight(5_000, 0), 1.0);
+    }
+
+    #[test]
+    fn realized_vol_needs_at_least_three_samples() {
+        let mut w = WeightedMeanWindow::new(10_000);
+        w.push(0, 100.0, 1.0);
+        w.push(1_000, 101.0, 1.0);
+        assert_eq!(w.realized_vol(), 0.0);
+        w.push(2_000, 99.0, 1.0);
+        assert!(w.realized_vol() > 0.0);
+    }
+}
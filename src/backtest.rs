@@ -0,0 +1,699 @@
+//! Offline replay: feed a recorded sequence of `TradeContext` snapshots
+//! (each paired with its eventual settlement) through `strategy::evaluate`
+//! and track the resulting account as if the signals had been traded
+//! live. This is what lets a `StrategyConfig` be swept and scored without
+//! a live feed, and is the natural backing for the circuit-breaker and
+//! consecutive-loss fields already on `StrategyConfig`/`Session` — they
+//! only do anything useful once something is actually replaying trades
+//! through them.
+
+use crate::polymarket::Side;
+use crate::strategy::{evaluate, Session, Signal, CalibrationMode, StrategyConfig, TradeContext, VolTracker, WindowTicks};
+
+/// One replayed round: the context `evaluate` would have seen, plus
+/// whether BTC actually settled UP by the end of the window.
+#[derive(Debug, Clone)]
+pub struct ReplayRound {
+    pub ctx: TradeContext,
+    pub settled_up: bool,
+    /// Unix timestamp (seconds) this round closed at, for the circuit
+    /// breaker's cooldown window.
+    pub closed_at: u64,
+}
+
+/// Record of one filled trade during a replay, for post-run inspection.
+#[derive(Debug, Clone)]
+pub struct Fill {
+    pub signal: Signal,
+    pub won: bool,
+    pub pnl_usdc: f64,
+}
+
+/// Simulated account driving a `Session` through a `ReplayRound` sequence.
+/// Mirrors the live loop's settlement formula (taker fee paid at entry
+/// regardless of outcome; a winner receives `size/price`, a loser forfeits
+/// the stake) without needing the live WS/Polymarket plumbing.
+#[derive(Debug)]
+pub struct Account {
+    pub session: Session,
+    config: StrategyConfig,
+    pub fills: Vec<Fill>,
+    /// Cumulative taker fees paid across all fills, for fee-drag reporting.
+    pub total_fees_usdc: f64,
+}
+
+impl Account {
+    pub fn new(config: StrategyConfig, initial_bankroll: f64) -> Self {
+        Self {
+            session: Session::new(initial_bankroll),
+            config,
+            fills: Vec::new(),
+            total_fees_usdc: 0.0,
+        }
+    }
+
+    /// Settles a single round: checks the circuit breaker, asks `evaluate`
+    /// whether it would trade, and if so applies the fee/payout and
+    /// records the fill.
+    pub fn process_round(&mut self, round: &ReplayRound) {
+        self.session.check_circuit_breaker(
+            self.config.circuit_breaker_window,
+            self.config.circuit_breaker_min_wr,
+            self.config.circuit_breaker_cooldown_s,
+            round.closed_at,
+        );
+        if self.session.is_circuit_broken(round.closed_at) {
+            return;
+        }
+        let Some(signal) = evaluate(&round.ctx, &self.session, &self.config) else {
+            return;
+        };
+        let won = (round.settled_up && signal.side == Side::Buy)
+            || (!round.settled_up && signal.side == Side::Sell);
+        self.settle_fill(signal, won);
+    }
+
+    /// Applies the fee/payout formula for a signal whose outcome is already
+    /// known and records the resulting fill — the settlement half of
+    /// `process_round`, split out so a tick-driven caller (`Backtest`) can
+    /// evaluate a signal the instant it fires but only settle it once the
+    /// round's realized outcome is known.
+    fn settle_fill(&mut self, signal: Signal, won: bool) {
+        let fee_cost = signal.size_usdc * signal.fee_pct / 100.0;
+        let pnl = if won {
+            signal.size_usdc * (1.0 / signal.price - 1.0) - fee_cost
+        } else {
+            -signal.size_usdc - fee_cost
+        };
+        self.total_fees_usdc += fee_cost;
+        self.session.record_trade(pnl);
+        self.fills.push(Fill { signal, won, pnl_usdc: pnl });
+    }
+
+    /// Replays an ordered sequence of rounds, in order.
+    pub fn replay(&mut self, rounds: &[ReplayRound]) {
+        for round in rounds {
+            self.process_round(round);
+        }
+    }
+}
+
+/// Post-run performance summary for a replayed `Account`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AccTracker {
+    pub trades: u32,
+    pub win_rate: f64,
+    pub max_drawdown_pct: f64,
+    pub sharpe: f64,
+    pub profit_factor: f64,
+    /// Total fees paid as a fraction of gross trading volume (Σ size_usdc),
+    /// i.e. how much of the traded notional was handed to fees.
+    pub fee_drag_pct: f64,
+}
+
+impl AccTracker {
+    pub fn summarize(account: &Account) -> Self {
+        let gross_volume: f64 = account.fills.iter().map(|f| f.signal.size_usdc).sum();
+        let fee_drag_pct = if gross_volume > 0.0 {
+            account.total_fees_usdc / gross_volume * 100.0
+        } else {
+            0.0
+        };
+        Self {
+            trades: account.session.trades,
+            win_rate: account.session.win_rate(),
+            max_drawdown_pct: account.session.session_drawdown_pct(),
+            sharpe: account.session.sharpe(),
+            profit_factor: account.session.profit_factor(),
+            fee_drag_pct,
+        }
+    }
+}
+
+/// One raw market tick from a recorded price/quote stream — what a tick
+/// database or exported WS log would actually hand you, before any of it
+/// has been folded into a `TradeContext`.
+#[derive(Debug, Clone, Copy)]
+pub struct Tick {
+    pub timestamp_ms: u64,
+    pub chainlink_price: f64,
+    pub market_up_price: f64,
+    pub book_imbalance: f64,
+    pub spread: f64,
+    pub num_ws_sources: u32,
+}
+
+/// Tick-level backtest engine: folds a raw `Tick` stream into 5-minute
+/// rounds (bucketed on `round_duration_secs`), feeding prices through a
+/// `VolTracker`/`WindowTicks` pair exactly as the live loop does, calling
+/// `evaluate()` once a round enters its entry window, and settling the
+/// resulting signal against the round's realized outcome once the next
+/// round begins. This is the tick-stream front end to `Account`/`ReplayRound`
+/// above — callers with pre-built `TradeContext`s should use those directly.
+#[derive(Debug)]
+pub struct Backtest {
+    pub account: Account,
+    vol_tracker: VolTracker,
+    window_ticks: WindowTicks,
+    round_duration_secs: u64,
+    current_round_start_ms: Option<u64>,
+    start_price: f64,
+    pending_signal: Option<Signal>,
+}
+
+impl Backtest {
+    pub fn new(
+        config: StrategyConfig,
+        initial_bankroll: f64,
+        round_duration_secs: u64,
+        vol_lookback: usize,
+        default_vol: f64,
+    ) -> Self {
+        Self {
+            account: Account::new(config, initial_bankroll),
+            vol_tracker: VolTracker::new(vol_lookback, default_vol),
+            window_ticks: WindowTicks::new(),
+            round_duration_secs,
+            current_round_start_ms: None,
+            start_price: 0.0,
+            pending_signal: None,
+        }
+    }
+
+    /// Feeds one tick through the pipeline. Rolls over to a new round (and
+    /// settles the previous one, if a signal fired during it) whenever the
+    /// tick's timestamp lands in a later `round_duration_secs` bucket than
+    /// the round currently open.
+    pub fn on_tick(&mut self, tick: &Tick) {
+        let round_duration_ms = self.round_duration_secs.max(1) * 1000;
+        let round_start_ms = (tick.timestamp_ms / round_duration_ms) * round_duration_ms;
+
+        if self.current_round_start_ms != Some(round_start_ms) {
+            if self.current_round_start_ms.is_some() {
+                self.settle_round(tick.chainlink_price);
+            }
+            self.current_round_start_ms = Some(round_start_ms);
+            self.start_price = tick.chainlink_price;
+            self.window_ticks.clear();
+        }
+
+        self.window_ticks.tick(tick.chainlink_price, tick.timestamp_ms);
+
+        if self.pending_signal.is_some() {
+            return;
+        }
+        let elapsed_ms = tick.timestamp_ms - round_start_ms;
+        let seconds_remaining = (round_duration_ms.saturating_sub(elapsed_ms)) / 1000;
+        if seconds_remaining > self.account.config.entry_seconds_before_end {
+            return;
+        }
+
+        let round_closed_at = round_start_ms / 1000 + self.round_duration_secs;
+        self.account.session.check_circuit_breaker(
+            self.account.config.circuit_breaker_window,
+            self.account.config.circuit_breaker_min_wr,
+            self.account.config.circuit_breaker_cooldown_s,
+            round_closed_at,
+        );
+        if self.account.session.is_circuit_broken(round_closed_at) {
+            return;
+        }
+
+        let ctx = TradeContext {
+            start_price: self.start_price,
+            chainlink_price: tick.chainlink_price,
+            exchange_price: None,
+            rtds_price: None,
+            market_up_price: tick.market_up_price,
+            seconds_remaining,
+            fee_rate: self.account.config.fee_rate,
+            vol_5min_pct: self.vol_tracker.current_vol(),
+            spread: tick.spread,
+            book_imbalance: tick.book_imbalance,
+            num_ws_sources: tick.num_ws_sources,
+            micro_vol: self.window_ticks.micro_vol(),
+            momentum_ratio: self.window_ticks.momentum_ratio(),
+            fisher: self.window_ticks.fisher(self.account.config.fisher_window),
+            fisher_prev: self.window_ticks.fisher_prev(),
+            max_drawdown_bps: self.window_ticks.max_drawdown_bps(),
+        };
+        self.pending_signal = evaluate(&ctx, &self.account.session, &self.account.config);
+    }
+
+    /// Closes out the round that just ended: records its realized move/range
+    /// into the vol tracker and settles any signal taken during it against
+    /// whether BTC actually closed UP (Polymarket's own tie-break: equal
+    /// counts as UP).
+    fn settle_round(&mut self, settle_price: f64) {
+        self.vol_tracker.record_move(self.start_price, settle_price);
+        if let Some((high, low)) = self.window_ticks.high_low() {
+            self.vol_tracker.record_range(high, low);
+        }
+        if let Some(signal) = self.pending_signal.take() {
+            let settled_up = settle_price >= self.start_price;
+            let won = (settled_up && signal.side == Side::Buy)
+                || (!settled_up && signal.side == Side::Sell);
+            self.account.settle_fill(signal, won);
+        }
+    }
+
+    /// Feeds an ordered tick stream through `on_tick`, in order.
+    pub fn run(&mut self, ticks: &[Tick]) {
+        for tick in ticks {
+            self.on_tick(tick);
+        }
+    }
+}
+
+/// Richer post-run performance summary than `AccTracker`, for a full
+/// tick-level `Backtest` run: adds PnL, the equity curve, Sortino, average
+/// win/loss, Brier score and turnover on top of `AccTracker`'s fields. It
+/// carries no fee-drag figure.
+#[derive(Debug, Clone)]
+pub struct BacktestReport {
+    pub trades: u32,
+    pub win_rate: f64,
+    pub pnl_usdc: f64,
+    pub max_drawdown_pct: f64,
+    pub sharpe: f64,
+    pub sortino: f64,
+    pub profit_factor: f64,
+    pub avg_win_usdc: f64,
+    pub avg_loss_usdc: f64,
+    /// Mean squared error between each fill's `implied_p_up` (on the side
+    /// actually taken) and its realized 0/1 outcome — lower is better
+    /// calibrated. 0.0 with no fills.
+    pub brier_score: f64,
+    /// Cumulative session PnL after each fill, in fill order.
+    pub equity_curve: Vec<f64>,
+    /// Gross traded notional (Σ size_usdc across all fills), i.e. how much
+    /// capital was put to work regardless of win/loss.
+    pub turnover_usdc: f64,
+}
+
+impl BacktestReport {
+    pub fn summarize(account: &Account) -> Self {
+        let wins: Vec<f64> = account.fills.iter().filter(|f| f.won).map(|f| f.pnl_usdc).collect();
+        let losses: Vec<f64> = account.fills.iter().filter(|f| !f.won).map(|f| f.pnl_usdc).collect();
+        let avg_win_usdc = if wins.is_empty() { 0.0 } else { wins.iter().sum::<f64>() / wins.len() as f64 };
+        let avg_loss_usdc = if losses.is_empty() { 0.0 } else { losses.iter().sum::<f64>() / losses.len() as f64 };
+        let turnover_usdc: f64 = account.fills.iter().map(|f| f.signal.size_usdc).sum();
+
+        let mut equity = 0.0;
+        let equity_curve: Vec<f64> = account.fills.iter().map(|f| { equity += f.pnl_usdc; equity }).collect();
+
+        let brier_score = if account.fills.is_empty() {
+            0.0
+        } else {
+            let sum_sq: f64 = account.fills.iter().map(|f| {
+                let predicted = if f.signal.side == Side::Buy { f.signal.implied_p_up } else { 1.0 - f.signal.implied_p_up };
+                let actual = if f.won { 1.0 } else { 0.0 };
+                (predicted - actual).powi(2)
+            }).sum();
+            sum_sq / account.fills.len() as f64
+        };
+
+        Self {
+            trades: account.session.trades,
+            win_rate: account.session.win_rate(),
+            pnl_usdc: account.session.pnl_usdc,
+            max_drawdown_pct: account.session.session_drawdown_pct(),
+            sharpe: account.session.sharpe(),
+            sortino: account.session.sortino(),
+            profit_factor: account.session.profit_factor(),
+            avg_win_usdc,
+            avg_loss_usdc,
+            brier_score,
+            equity_curve,
+            turnover_usdc,
+        }
+    }
+}
+
+/// Replays every `ticks_*.csv` file in `dir` (the format `logger::TickLogger`
+/// writes: `timestamp_ms,source,price,window`) through `Backtest`, in
+/// filename order — lets `min_edge_pct`, `kelly_fraction`, and the `extreme`
+/// thresholds be tuned against recorded history without a live feed or
+/// sockets, driven by `--backtest <dir>` in `main`.
+///
+/// Logged ticks carry only a price and its source, not order-book state, so
+/// every replayed round sees an uninformed `market_up_price` of 0.5 and a
+/// flat spread/imbalance/`num_ws_sources`. This replays the underlying-price
+/// side of the strategy (edge, vol, calibration) faithfully; it can't
+/// re-derive historical order-book-dependent skips.
+pub fn run_csv_replay(
+    dir: &std::path::Path,
+    config: StrategyConfig,
+    initial_bankroll: f64,
+    round_duration_secs: u64,
+    vol_lookback: usize,
+    default_vol: f64,
+) -> anyhow::Result<BacktestReport> {
+    use anyhow::Context;
+
+    let mut paths: Vec<_> = std::fs::read_dir(dir)
+        .with_context(|| format!("Cannot read backtest directory {}", dir.display()))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("ticks_") && n.ends_with(".csv"))
+        })
+        .collect();
+    paths.sort();
+    anyhow::ensure!(!paths.is_empty(), "No ticks_*.csv files found in {}", dir.display());
+
+    let mut backtest = Backtest::new(config, initial_bankroll, round_duration_secs, vol_lookback, default_vol);
+    for path in &paths {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Cannot read {}", path.display()))?;
+        for line in content.lines().skip(1) {
+            let mut fields = line.split(',');
+            let (Some(ts), Some(_source), Some(price), Some(_window)) =
+                (fields.next(), fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            let (Ok(timestamp_ms), Ok(chainlink_price)) = (ts.parse::<u64>(), price.parse::<f64>()) else {
+                continue;
+            };
+            backtest.on_tick(&Tick {
+                timestamp_ms,
+                chainlink_price,
+                market_up_price: 0.5,
+                book_imbalance: 0.5,
+                spread: 0.0,
+                num_ws_sources: 1,
+            });
+        }
+    }
+
+    Ok(BacktestReport::summarize(&backtest.account))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> StrategyConfig {
+        StrategyConfig {
+            max_bet_usdc: 5.0,
+            min_bet_usdc: 1.0,
+            min_shares: 5,
+            min_edge_pct: 1.0,
+            entry_seconds_before_end: 10,
+            session_profit_target_usdc: 1000.0,
+            session_loss_limit_usdc: 1000.0,
+            fee_rate: 0.25,
+            min_market_price: 0.05,
+            max_market_price: 0.95,
+            min_delta_pct: 0.0,
+            max_spread: 1.0,
+            kelly_fraction: 0.2,
+            initial_bankroll_usdc: 40.0,
+            always_trade: true,
+            vol_confidence_multiplier: 4.0,
+            min_payout_ratio: 0.0,
+            min_book_imbalance: 0.0,
+            max_vol_5min_pct: 0.0,
+            min_ws_sources: 0,
+            circuit_breaker_window: 0,
+            circuit_breaker_min_wr: 0.0,
+            circuit_breaker_cooldown_s: 0,
+            min_implied_prob: 0.0,
+            max_consecutive_losses: 0,
+            student_t_df: 0.0,
+            min_z_score: 0.0,
+            max_model_divergence: 0.0,
+            quote_spread_pct: 0.0,
+            atr_window: 10,
+            exit_stop_atr_mult: 0.0,
+            exit_tp_atr_mult: 0.0,
+            exit_tp_window: 10,
+            fisher_window: 20,
+            fisher_extreme_threshold: 0.0,
+            min_vol_edge: 0.0,
+            roi_table: Vec::new(),
+            trailing_stop_pct: 0.0,
+            trailing_stop_bps: 0.0,
+            hard_stop_bps: 0.0,
+            min_momentum_exit: 0.0,
+            calibration_breakpoints: Vec::new(),
+            calibration_mode: CalibrationMode::Multiplier,
+            safety_spread_pct: 0.0,
+            trailing_stages: Vec::new(),
+            daily_fee_budget: 0.0,
+            daily_max_volume: 0.0,
+            feed_spread_pct: 0.0,
+            feed_skew_pct: 0.0,
+            symmetric_fee_model: false,
+            symmetric_fee_base_rate: 0.0,
+            consensus_max_deviation_pct: 0.0,
+        }
+    }
+
+    fn test_ctx(start_price: f64, chainlink_price: f64, market_up_price: f64) -> TradeContext {
+        TradeContext {
+            start_price,
+            chainlink_price,
+            exchange_price: None,
+            rtds_price: None,
+            market_up_price,
+            seconds_remaining: 5,
+            fee_rate: 0.25,
+            vol_5min_pct: 0.1,
+            spread: 0.01,
+            book_imbalance: 0.0,
+            num_ws_sources: 0,
+            micro_vol: 0.0,
+            momentum_ratio: 1.0,
+            fisher: 0.0,
+            fisher_prev: 0.0,
+            max_drawdown_bps: 0.0,
+        }
+    }
+
+    #[test]
+    fn account_records_a_win_and_updates_bankroll() {
+        let mut account = Account::new(test_config(), 40.0);
+        let round = ReplayRound {
+            ctx: test_ctx(100_000.0, 100_200.0, 0.5),
+            settled_up: true,
+            closed_at: 1_000,
+        };
+        account.process_round(&round);
+        assert_eq!(account.session.trades, 1);
+        assert_eq!(account.fills.len(), 1);
+        assert!(account.fills[0].won);
+        assert!(account.session.pnl_usdc > 0.0);
+    }
+
+    #[test]
+    fn account_records_a_loss_and_forfeits_stake() {
+        let mut account = Account::new(test_config(), 40.0);
+        let round = ReplayRound {
+            ctx: test_ctx(100_000.0, 100_200.0, 0.5),
+            settled_up: false,
+            closed_at: 1_000,
+        };
+        account.process_round(&round);
+        assert_eq!(account.session.trades, 1);
+        assert!(!account.fills[0].won);
+        assert!(account.session.pnl_usdc < 0.0);
+    }
+
+    #[test]
+    fn account_skips_rounds_evaluate_rejects() {
+        let mut config = test_config();
+        config.always_trade = false;
+        config.min_edge_pct = 99.0; // unreachable edge requirement
+        let mut account = Account::new(config, 40.0);
+        let round = ReplayRound {
+            ctx: test_ctx(100_000.0, 100_050.0, 0.5),
+            settled_up: true,
+            closed_at: 1_000,
+        };
+        account.process_round(&round);
+        assert_eq!(account.session.trades, 0);
+        assert!(account.fills.is_empty());
+    }
+
+    #[test]
+    fn account_replays_an_ordered_sequence() {
+        let mut account = Account::new(test_config(), 40.0);
+        let rounds: Vec<ReplayRound> = (0..5)
+            .map(|i| ReplayRound {
+                ctx: test_ctx(100_000.0, 100_200.0, 0.5),
+                settled_up: i % 2 == 0,
+                closed_at: 1_000 + i,
+            })
+            .collect();
+        account.replay(&rounds);
+        assert_eq!(account.session.trades, 5);
+        assert_eq!(account.fills.len(), 5);
+    }
+
+    #[test]
+    fn account_respects_circuit_breaker_after_losing_streak() {
+        let mut config = test_config();
+        config.circuit_breaker_window = 3;
+        config.circuit_breaker_min_wr = 0.5;
+        config.circuit_breaker_cooldown_s = 3_600;
+        let mut account = Account::new(config, 40.0);
+        let losing_rounds: Vec<ReplayRound> = (0..3)
+            .map(|i| ReplayRound {
+                ctx: test_ctx(100_000.0, 100_200.0, 0.5),
+                settled_up: false, // always_trade bets UP, so these all lose
+                closed_at: 1_000 + i,
+            })
+            .collect();
+        account.replay(&losing_rounds);
+        assert_eq!(account.session.trades, 3);
+
+        let next_round = ReplayRound {
+            ctx: test_ctx(100_000.0, 100_200.0, 0.5),
+            settled_up: true,
+            closed_at: 1_001,
+        };
+        account.process_round(&next_round);
+        assert_eq!(account.session.trades, 3, "circuit breaker should have blocked the 4th trade");
+    }
+
+    #[test]
+    fn acc_tracker_summarizes_win_rate_and_fee_drag() {
+        let mut account = Account::new(test_config(), 40.0);
+        let rounds = vec![
+            ReplayRound { ctx: test_ctx(100_000.0, 100_200.0, 0.5), settled_up: true, closed_at: 1_000 },
+            ReplayRound { ctx: test_ctx(100_000.0, 100_200.0, 0.5), settled_up: false, closed_at: 1_001 },
+        ];
+        account.replay(&rounds);
+        let summary = AccTracker::summarize(&account);
+        assert_eq!(summary.trades, 2);
+        assert!((summary.win_rate - 0.5).abs() < 1e-9);
+        assert!(summary.fee_drag_pct > 0.0);
+    }
+
+    #[test]
+    fn acc_tracker_reports_zero_fee_drag_with_no_trades() {
+        let account = Account::new(test_config(), 40.0);
+        let summary = AccTracker::summarize(&account);
+        assert_eq!(summary.trades, 0);
+        assert_eq!(summary.fee_drag_pct, 0.0);
+    }
+
+    // --- tick-level Backtest / BacktestReport ---
+
+    fn tick(timestamp_ms: u64, chainlink_price: f64) -> Tick {
+        Tick {
+            timestamp_ms,
+            chainlink_price,
+            market_up_price: 0.5,
+            book_imbalance: 0.0,
+            spread: 0.01,
+            num_ws_sources: 1,
+        }
+    }
+
+    #[test]
+    fn backtest_settles_a_win_once_the_next_round_begins() {
+        let mut backtest = Backtest::new(test_config(), 40.0, 300, 10, 0.1);
+        // Round 1: opens at 100_000, ticks up 10% into the entry window -- a
+        // move large enough to clear min_edge_pct/Kelly regardless of vol.
+        backtest.run(&[
+            tick(0, 100_000.0),
+            tick(100_000, 100_000.0),
+            tick(200_000, 105_000.0),
+            tick(295_000, 110_000.0), // inside the 10s entry window -> evaluate() fires
+        ]);
+        assert_eq!(backtest.account.fills.len(), 0, "not settled until the round rolls over");
+
+        // Round 2 begins; BTC closed >= the round-1 open, so the BUY UP fill wins.
+        backtest.on_tick(&tick(300_000, 112_000.0));
+        assert_eq!(backtest.account.fills.len(), 1);
+        assert!(backtest.account.fills[0].won);
+        assert_eq!(backtest.account.fills[0].signal.side, Side::Buy);
+    }
+
+    #[test]
+    fn backtest_settles_a_loss_when_price_closes_below_open() {
+        let mut backtest = Backtest::new(test_config(), 40.0, 300, 10, 0.1);
+        backtest.run(&[
+            tick(0, 100_000.0),
+            tick(295_000, 110_000.0), // still ticks UP intra-round -> BUY UP signal
+        ]);
+        // ...but BTC closes back below the round-1 open.
+        backtest.on_tick(&tick(300_000, 95_000.0));
+        assert_eq!(backtest.account.fills.len(), 1);
+        assert!(!backtest.account.fills[0].won);
+    }
+
+    #[test]
+    fn backtest_report_summarizes_equity_curve_and_turnover() {
+        let mut backtest = Backtest::new(test_config(), 40.0, 300, 10, 0.1);
+        backtest.run(&[
+            tick(0, 100_000.0),
+            tick(295_000, 110_000.0),
+            tick(300_000, 112_000.0), // settles round 1 as a win
+            tick(595_000, 123_200.0),
+            tick(600_000, 100_000.0), // settles round 2 as a loss
+        ]);
+        let report = BacktestReport::summarize(&backtest.account);
+        assert_eq!(report.trades, 2);
+        assert_eq!(report.pnl_usdc, backtest.account.session.pnl_usdc);
+        assert_eq!(report.equity_curve.len(), 2);
+        assert!(report.turnover_usdc > 0.0);
+        assert!(report.avg_win_usdc > 0.0);
+        assert!(report.avg_loss_usdc < 0.0);
+        assert!(report.brier_score > 0.0);
+    }
+
+    #[test]
+    fn backtest_report_brier_score_is_zero_with_no_fills() {
+        let backtest = Backtest::new(test_config(), 40.0, 300, 10, 0.1);
+        let report = BacktestReport::summarize(&backtest.account);
+        assert_eq!(report.trades, 0);
+        assert_eq!(report.brier_score, 0.0);
+    }
+
+    #[test]
+    fn run_csv_replay_reads_ticks_csv_files_in_order() {
+        let dir = std::env::temp_dir().join("poly5m_test_csv_replay");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("ticks_20260101.csv"),
+            "timestamp_ms,source,price,window\n\
+             0,CL,100000.00,0\n\
+             295000,CL,110000.00,0\n\
+             300000,CL,112000.00,0\n",
+        ).unwrap();
+
+        let report = run_csv_replay(&dir, test_config(), 40.0, 300, 10, 0.1).unwrap();
+        assert_eq!(report.trades, 1);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn run_csv_replay_errors_with_no_matching_files() {
+        let dir = std::env::temp_dir().join("poly5m_test_csv_replay_empty");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        assert!(run_csv_replay(&dir, test_config(), 40.0, 300, 10, 0.1).is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn backtest_does_not_settle_until_a_round_actually_rolls_over() {
+        let mut backtest = Backtest::new(test_config(), 40.0, 300, 10, 0.1);
+        backtest.run(&[
+            tick(0, 100_000.0),
+            tick(150_000, 100_010.0),
+            tick(295_000, 100_020.0),
+            tick(298_000, 100_030.0),
+        ]);
+        assert_eq!(backtest.account.fills.len(), 0);
+        assert_eq!(backtest.account.session.trades, 0);
+    }
+}
@@ -0,0 +1,175 @@
+//! Standalone tool to backfill the Postgres schema (`db::DbLogger`) from
+//! pre-existing CSV logs, for deployments that ran with `[database].enabled =
+//! false` for a while and don't want to lose that history once it's turned
+//! on. Ingests the unified trade/resolution/skip CSV (`CsvLogger`), the
+//! per-window outcomes CSV (`OutcomeLogger`), and the daily `ticks_*.csv`
+//! files (`TickLogger`) the same way the live loop would have logged them,
+//! via the same `DbLogger` the main binary uses — so backfilled rows land in
+//! identical tables and candles get rolled up identically. Historical tick
+//! rows don't carry `micro_vol`/`momentum_ratio`/`sign_changes` (those only
+//! exist in-memory on the live `WindowTicks`), so backfilled candles get
+//! zeros for those three columns; price/OHLC are unaffected.
+//!
+//! Usage: `backfill <connection_string> <ssl: true|false> <table_prefix> <trade_csv> <outcomes_csv> <ticks_dir>`
+//! Any of the three CSV/dir arguments may be `-` to skip that source.
+//!
+//! There's no `[lib]` target in this crate, so the db module is pulled in by
+//! path rather than depended on like a normal crate.
+#[path = "../db.rs"]
+mod db;
+
+use anyhow::{Context, Result};
+use std::io::BufRead;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let args: Vec<String> = std::env::args().collect();
+    anyhow::ensure!(
+        args.len() == 7,
+        "Usage: backfill <connection_string> <ssl: true|false> <table_prefix> <trade_csv|-> <outcomes_csv|-> <ticks_dir|->"
+    );
+    let connection_string = &args[1];
+    let ssl: bool = args[2].parse().context("ssl must be true or false")?;
+    let table_prefix = &args[3];
+    let trade_csv = &args[4];
+    let outcomes_csv = &args[5];
+    let ticks_dir = &args[6];
+
+    let logger = db::DbLogger::connect(connection_string, ssl, table_prefix, 4096).await?;
+
+    let mut trades = 0u64;
+    let mut skips = 0u64;
+    let mut resolutions = 0u64;
+    let mut outcomes = 0u64;
+    let mut ticks = 0u64;
+
+    if trade_csv != "-" {
+        let (t, s, r) = backfill_trade_csv(&logger, trade_csv)?;
+        trades += t;
+        skips += s;
+        resolutions += r;
+    }
+    if outcomes_csv != "-" {
+        outcomes += backfill_outcomes_csv(&logger, outcomes_csv)?;
+    }
+    if ticks_dir != "-" {
+        ticks += backfill_ticks_dir(&logger, ticks_dir)?;
+    }
+
+    // Give the background batch-writer time to flush the last partial batch
+    // before the process exits and drops the channel.
+    tokio::time::sleep(std::time::Duration::from_millis(1000)).await;
+
+    tracing::info!(
+        "Backfill complete: {trades} trades, {skips} skips, {resolutions} resolutions, \
+         {outcomes} window outcomes, {ticks} ticks"
+    );
+    Ok(())
+}
+
+/// Returns `(header -> column index)` so each CSV can be read without
+/// hardcoding 50-odd positional offsets.
+fn header_index(header: &str) -> std::collections::HashMap<&str, usize> {
+    header.split(',').enumerate().map(|(i, name)| (name, i)).collect()
+}
+
+fn field<'a>(fields: &[&'a str], idx: &std::collections::HashMap<&str, usize>, name: &str) -> Option<&'a str> {
+    idx.get(name).and_then(|&i| fields.get(i)).copied().filter(|s| !s.is_empty())
+}
+
+/// Ingests the unified trade/resolution/skip CSV written by `logger::CsvLogger`.
+fn backfill_trade_csv(logger: &db::DbLogger, path: &str) -> Result<(u64, u64, u64)> {
+    let file = std::fs::File::open(path).with_context(|| format!("Cannot open {path}"))?;
+    let mut lines = std::io::BufReader::new(file).lines();
+    let header = lines.next().context("Empty trade CSV")??;
+    let idx = header_index(&header);
+
+    let (mut trades, mut skips, mut resolutions) = (0u64, 0u64, 0u64);
+    for line in lines {
+        let line = line?;
+        let fields: Vec<&str> = line.split(',').collect();
+        let Some(timestamp) = field(&fields, &idx, "timestamp").and_then(|v| v.parse::<u64>().ok()) else { continue };
+        let Some(window) = field(&fields, &idx, "window").and_then(|v| v.parse::<u64>().ok()) else { continue };
+        let Some(event) = field(&fields, &idx, "event") else { continue };
+        let price_source = field(&fields, &idx, "price_source").unwrap_or("");
+
+        match event {
+            "trade" => {
+                let side = field(&fields, &idx, "side").unwrap_or("");
+                let edge_pct = field(&fields, &idx, "edge_net_pct").and_then(|v| v.parse::<f64>().ok()).unwrap_or(0.0);
+                let size_usdc = field(&fields, &idx, "size_usdc").and_then(|v| v.parse::<f64>().ok()).unwrap_or(0.0);
+                let entry_price = field(&fields, &idx, "entry_price").and_then(|v| v.parse::<f64>().ok()).unwrap_or(0.0);
+                logger.log_trade(window, timestamp, side, edge_pct, size_usdc, entry_price, price_source);
+                trades += 1;
+            }
+            "skip" => {
+                let reason = field(&fields, &idx, "skip_reason").unwrap_or("");
+                logger.log_skip(window, timestamp, reason, price_source);
+                skips += 1;
+            }
+            "resolution" => {
+                let result = field(&fields, &idx, "result").unwrap_or("");
+                let pnl = field(&fields, &idx, "pnl").and_then(|v| v.parse::<f64>().ok()).unwrap_or(0.0);
+                logger.log_resolution(window, timestamp, result, pnl, price_source);
+                resolutions += 1;
+            }
+            other => tracing::warn!("Unknown event '{other}' in {path}, skipping row"),
+        }
+    }
+    Ok((trades, skips, resolutions))
+}
+
+/// Ingests the per-window outcomes CSV written by `logger::OutcomeLogger`.
+fn backfill_outcomes_csv(logger: &db::DbLogger, path: &str) -> Result<u64> {
+    let file = std::fs::File::open(path).with_context(|| format!("Cannot open {path}"))?;
+    let mut lines = std::io::BufReader::new(file).lines();
+    let header = lines.next().context("Empty outcomes CSV")??;
+    let idx = header_index(&header);
+
+    let mut count = 0u64;
+    for line in lines {
+        let line = line?;
+        let fields: Vec<&str> = line.split(',').collect();
+        let Some(window) = field(&fields, &idx, "window").and_then(|v| v.parse::<u64>().ok()) else { continue };
+        let Some(btc_start) = field(&fields, &idx, "btc_start").and_then(|v| v.parse::<f64>().ok()) else { continue };
+        let Some(btc_end) = field(&fields, &idx, "btc_end").and_then(|v| v.parse::<f64>().ok()) else { continue };
+        logger.log_outcome(window, btc_start, btc_end, "");
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Ingests every `ticks_*.csv` file written by `logger::TickLogger` in `dir`.
+fn backfill_ticks_dir(logger: &db::DbLogger, dir: &str) -> Result<u64> {
+    let mut paths: Vec<_> = std::fs::read_dir(dir)
+        .with_context(|| format!("Cannot read ticks directory {dir}"))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with("ticks_") && n.ends_with(".csv")))
+        .collect();
+    paths.sort();
+
+    let mut count = 0u64;
+    for path in &paths {
+        let file = std::fs::File::open(path).with_context(|| format!("Cannot open {}", path.display()))?;
+        let mut lines = std::io::BufReader::new(file).lines();
+        let Some(Ok(_header)) = lines.next() else { continue };
+        for line in lines {
+            let line = line?;
+            let mut fields = line.split(',');
+            let (Some(ts_ms), Some(source), Some(price), Some(window)) =
+                (fields.next(), fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            let (Ok(ts_ms), Ok(price), Ok(window)) = (ts_ms.parse::<u64>(), price.parse::<f64>(), window.parse::<u64>()) else {
+                continue;
+            };
+            logger.log_tick(ts_ms, source, price, window, 0.0, 0.0, 0);
+            count += 1;
+        }
+    }
+    Ok(count)
+}
@@ -0,0 +1,86 @@
+//! Standalone tool to price a multi-bucket price-range market
+//! (`buckets::evaluate_buckets`) from the command line, since nothing in
+//! `main.rs`'s live loop trades anything but a single binary UP/DOWN window
+//! yet. Reads bucket definitions from a CSV of `lower,upper,market_price`
+//! rows (the last row's `upper` must be the literal `open` for the
+//! required open-ended top bucket) and prints every bucket whose edge
+//! clears the chosen preset's `min_edge_pct`.
+//!
+//! Usage: `bucket_price <sniper|conviction|scalper|farm> <start_price> <pct_change_pct> <seconds_remaining> <vol_5min_pct> <buckets.csv>`
+//!
+//! There's no `[lib]` target in this crate, so the strategy/bucket modules
+//! are pulled in by path rather than depended on like a normal crate (see
+//! `src/bin/backfill.rs` for the same pattern).
+#[path = "../amount.rs"]
+mod amount;
+#[path = "../fixedpoint.rs"]
+mod fixedpoint;
+#[path = "../polymarket.rs"]
+mod polymarket;
+#[path = "../digital_option.rs"]
+mod digital_option;
+#[path = "../strategy.rs"]
+mod strategy;
+#[path = "../buckets.rs"]
+mod buckets;
+#[path = "../presets.rs"]
+mod presets;
+
+use anyhow::{Context, Result};
+use buckets::Bucket;
+use std::io::BufRead;
+
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    anyhow::ensure!(
+        args.len() == 7,
+        "Usage: bucket_price <sniper|conviction|scalper|farm> <start_price> <pct_change_pct> <seconds_remaining> <vol_5min_pct> <buckets.csv>"
+    );
+    let config = presets::get(&args[1]).with_context(|| format!("Unknown preset {:?}", args[1]))?;
+    let start_price: f64 = args[2].parse().context("bad start_price")?;
+    let pct_change: f64 = args[3].parse().context("bad pct_change_pct")?;
+    let seconds_remaining: u64 = args[4].parse().context("bad seconds_remaining")?;
+    let vol_5min_pct: f64 = args[5].parse().context("bad vol_5min_pct")?;
+    let buckets = read_buckets(&args[6])?;
+
+    let session = strategy::Session::new(config.initial_bankroll_usdc);
+    let signals = buckets::evaluate_buckets(&buckets, start_price, pct_change, seconds_remaining, vol_5min_pct, &session, &config)
+        .map_err(|e| anyhow::anyhow!("invalid bucket partition: {e}"))?;
+
+    if signals.is_empty() {
+        println!("No bucket clears min_edge_pct={:.2}%", config.min_edge_pct);
+        return Ok(());
+    }
+    println!("bucket_index,lower,upper,market_price,edge_pct,size_usdc");
+    for s in &signals {
+        let b = &buckets[s.bucket_index];
+        let upper = b.upper.map(|u| u.to_string()).unwrap_or_else(|| "open".to_string());
+        println!("{},{},{},{:.4},{:.3},{:.2}", s.bucket_index, b.lower, upper, b.market_price, s.signal.edge_pct, s.signal.size_usdc);
+    }
+    Ok(())
+}
+
+/// Parses `lower,upper,market_price` rows, where `upper` is the literal
+/// `open` on the last (and only the last) bucket — mirrors
+/// `buckets::validate_partition`'s open-ended-last-bucket requirement.
+fn read_buckets(path: &str) -> Result<Vec<Bucket>> {
+    let file = std::fs::File::open(path).with_context(|| format!("Cannot open {path}"))?;
+    let mut out = Vec::new();
+    for (i, line) in std::io::BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() || line.starts_with("lower,") {
+            continue; // blank line or CSV header
+        }
+        let fields: Vec<&str> = line.split(',').collect();
+        anyhow::ensure!(fields.len() == 3, "row {i} must have 3 columns, got {line:?}");
+        let lower: f64 = fields[0].parse().with_context(|| format!("bad lower at row {i}: {line:?}"))?;
+        let upper = if fields[1].trim() == "open" {
+            None
+        } else {
+            Some(fields[1].parse().with_context(|| format!("bad upper at row {i}: {line:?}"))?)
+        };
+        let market_price: f64 = fields[2].parse().with_context(|| format!("bad market_price at row {i}: {line:?}"))?;
+        out.push(Bucket { lower, upper, market_price });
+    }
+    Ok(out)
+}
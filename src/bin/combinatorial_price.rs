@@ -0,0 +1,88 @@
+//! Standalone tool to price an N-outcome combinatorial market
+//! (`combinatorial::evaluate_combinatorial`) from the command line, since
+//! nothing in `main.rs`'s live loop trades anything but a single binary
+//! UP/DOWN window yet — mirrors `src/bin/bucket_price.rs`'s treatment of
+//! `buckets::evaluate_buckets` for the same reason.
+//!
+//! Reads outcome rows from a CSV of `price,model_prob,role` rows, where
+//! `role` is one of `buy`, `sell`, or `keep` and assigns that outcome's
+//! index (its row order) into the `combinatorial::Partition`.
+//!
+//! Usage: `combinatorial_price <sniper|conviction|scalper|farm> <outcomes.csv>`
+//!
+//! There's no `[lib]` target in this crate, so the strategy/combinatorial
+//! modules are pulled in by path rather than depended on like a normal
+//! crate (see `src/bin/backfill.rs` for the same pattern).
+#[path = "../amount.rs"]
+mod amount;
+#[path = "../fixedpoint.rs"]
+mod fixedpoint;
+#[path = "../polymarket.rs"]
+mod polymarket;
+#[path = "../digital_option.rs"]
+mod digital_option;
+#[path = "../strategy.rs"]
+mod strategy;
+#[path = "../combinatorial.rs"]
+mod combinatorial;
+#[path = "../presets.rs"]
+mod presets;
+
+use anyhow::{Context, Result};
+use combinatorial::Partition;
+use std::io::BufRead;
+
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    anyhow::ensure!(
+        args.len() == 3,
+        "Usage: combinatorial_price <sniper|conviction|scalper|farm> <outcomes.csv>"
+    );
+    let config = presets::get(&args[1]).with_context(|| format!("Unknown preset {:?}", args[1]))?;
+    let (prices, model_probs, partition) = read_outcomes(&args[2])?;
+
+    let session = strategy::Session::new(config.initial_bankroll_usdc);
+    let signal = combinatorial::evaluate_combinatorial(&prices, &model_probs, &partition, &session, &config)
+        .map_err(|e| anyhow::anyhow!("invalid partition: {e}"))?;
+
+    match signal {
+        None => println!("No signal clears min_edge_pct={:.2}%", config.min_edge_pct),
+        Some(s) => {
+            println!("side,price,edge_pct,size_usdc");
+            println!("{:?},{:.4},{:.3},{:.2}", s.side, s.price, s.edge_pct, s.size_usdc);
+        }
+    }
+    Ok(())
+}
+
+/// Parses `price,model_prob,role` rows, where `role` is `buy`, `sell`, or
+/// `keep` — each row's position becomes that outcome's index in the
+/// resulting `Partition`, mirroring `combinatorial::validate_partition`'s
+/// index-based buy/sell/keep sets.
+fn read_outcomes(path: &str) -> Result<(Vec<f64>, Vec<f64>, Partition)> {
+    let file = std::fs::File::open(path).with_context(|| format!("Cannot open {path}"))?;
+    let mut prices = Vec::new();
+    let mut model_probs = Vec::new();
+    let mut partition = Partition { buy: Vec::new(), sell: Vec::new(), keep: Vec::new() };
+    let mut index = 0usize;
+    for line in std::io::BufReader::new(file).lines() {
+        let line = line?;
+        if line.trim().is_empty() || line.starts_with("price,") {
+            continue; // blank line or CSV header
+        }
+        let fields: Vec<&str> = line.split(',').collect();
+        anyhow::ensure!(fields.len() == 3, "row {index} must have 3 columns, got {line:?}");
+        let price: f64 = fields[0].parse().with_context(|| format!("bad price at row {index}: {line:?}"))?;
+        let model_prob: f64 = fields[1].parse().with_context(|| format!("bad model_prob at row {index}: {line:?}"))?;
+        match fields[2].trim() {
+            "buy" => partition.buy.push(index),
+            "sell" => partition.sell.push(index),
+            "keep" => partition.keep.push(index),
+            other => anyhow::bail!("row {index}: unknown role {other:?}, expected buy/sell/keep"),
+        }
+        prices.push(price);
+        model_probs.push(model_prob);
+        index += 1;
+    }
+    Ok((prices, model_probs, partition))
+}
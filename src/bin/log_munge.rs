@@ -0,0 +1,164 @@
+//! Post-processes the CSVs written by `CsvLogger`/`TickLogger` into
+//! analysis-ready subsets, since the loggers themselves only ever append the
+//! full stream.
+//!
+//! Two modes:
+//! - `range`: copies only rows whose leading timestamp column falls within
+//!   an RFC3339 `[start, end)` bound. Assumes the input is in ascending time
+//!   order (true of every logger here) and stops reading as soon as a row
+//!   is past `end`, rather than scanning to EOF.
+//! - `copy-prep`: rewrites `CsvLogger`'s 51-column trade/resolution/skip CSV
+//!   for bulk loading via Postgres `COPY`, turning every blank field (e.g.
+//!   `btc_resolution` on trades, `side` on skips, `result`/`pnl` on trades)
+//!   into the `\N` NULL sentinel `COPY` expects, and validating every row —
+//!   including the header — still has exactly 51 columns (the invariant
+//!   `csv_all_events_same_field_count` asserts in `logger.rs`).
+//!
+//! Usage:
+//!   log_munge range <seconds|millis> <start_rfc3339> <end_rfc3339> <input.csv> <output.csv>
+//!   log_munge copy-prep <input.csv> <output.csv>
+
+use anyhow::{Context, Result};
+use std::io::{BufRead, BufWriter, Write};
+
+const TRADE_CSV_COLUMNS: usize = 51;
+
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    anyhow::ensure!(args.len() >= 2, "{}", usage());
+    match args[1].as_str() {
+        "range" => {
+            anyhow::ensure!(args.len() == 7, "{}", usage());
+            let unit = match args[2].as_str() {
+                "seconds" => TsUnit::Seconds,
+                "millis" => TsUnit::Millis,
+                other => anyhow::bail!("Unknown unit {other:?}, expected seconds or millis"),
+            };
+            let start = parse_rfc3339(&args[3])?;
+            let end = parse_rfc3339(&args[4])?;
+            range(unit, start, end, &args[5], &args[6])
+        }
+        "copy-prep" => {
+            anyhow::ensure!(args.len() == 4, "{}", usage());
+            copy_prep(&args[2], &args[3])
+        }
+        other => anyhow::bail!("Unknown mode {other:?}\n{}", usage()),
+    }
+}
+
+fn usage() -> &'static str {
+    "Usage:\n  \
+     log_munge range <seconds|millis> <start_rfc3339> <end_rfc3339> <input.csv> <output.csv>\n  \
+     log_munge copy-prep <input.csv> <output.csv>"
+}
+
+#[derive(Clone, Copy)]
+enum TsUnit {
+    Seconds,
+    Millis,
+}
+
+fn range(unit: TsUnit, start: u64, end: u64, input: &str, output: &str) -> Result<()> {
+    let (start, end) = match unit {
+        TsUnit::Seconds => (start, end),
+        TsUnit::Millis => (start * 1000, end * 1000),
+    };
+    let file = std::fs::File::open(input).with_context(|| format!("Cannot open {input}"))?;
+    let reader = std::io::BufReader::new(file);
+    let out = std::fs::File::create(output).with_context(|| format!("Cannot create {output}"))?;
+    let mut writer = BufWriter::new(out);
+    let mut kept = 0u64;
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if i == 0 {
+            writeln!(writer, "{line}")?;
+            continue;
+        }
+        let ts: u64 = line
+            .split(',')
+            .next()
+            .with_context(|| format!("empty line at row {i}"))?
+            .parse()
+            .with_context(|| format!("bad leading timestamp at row {i}: {line:?}"))?;
+        if ts >= end {
+            break; // ascending order assumed: nothing after this can be in range either
+        }
+        if ts >= start {
+            writeln!(writer, "{line}")?;
+            kept += 1;
+        }
+    }
+    writer.flush()?;
+    eprintln!("Wrote {kept} rows to {output}");
+    Ok(())
+}
+
+fn copy_prep(input: &str, output: &str) -> Result<()> {
+    let file = std::fs::File::open(input).with_context(|| format!("Cannot open {input}"))?;
+    let reader = std::io::BufReader::new(file);
+    let out = std::fs::File::create(output).with_context(|| format!("Cannot create {output}"))?;
+    let mut writer = BufWriter::new(out);
+    let mut rows = 0u64;
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let fields: Vec<&str> = line.split(',').collect();
+        anyhow::ensure!(
+            fields.len() == TRADE_CSV_COLUMNS,
+            "row {i} has {} columns instead of {TRADE_CSV_COLUMNS}: {line:?}",
+            fields.len()
+        );
+        let prepped: Vec<&str> = fields.into_iter().map(|f| if f.is_empty() { "\\N" } else { f }).collect();
+        writeln!(writer, "{}", prepped.join(","))?;
+        rows += 1;
+    }
+    writer.flush()?;
+    eprintln!("Wrote {rows} COPY-ready rows to {output}");
+    Ok(())
+}
+
+/// Parses a UTC `YYYY-MM-DDTHH:MM:SSZ` timestamp into epoch seconds.
+/// Hand-rolled, like `TickLogger::date_from_epoch`, rather than pulling in a
+/// date crate just for this.
+fn parse_rfc3339(s: &str) -> Result<u64> {
+    anyhow::ensure!(s.len() >= 19, "not an RFC3339 timestamp: {s:?}");
+    let y: u32 = s[0..4].parse().with_context(|| format!("bad year in {s:?}"))?;
+    let m: u32 = s[5..7].parse().with_context(|| format!("bad month in {s:?}"))?;
+    let d: u32 = s[8..10].parse().with_context(|| format!("bad day in {s:?}"))?;
+    let hh: u64 = s[11..13].parse().with_context(|| format!("bad hour in {s:?}"))?;
+    let mm: u64 = s[14..16].parse().with_context(|| format!("bad minute in {s:?}"))?;
+    let ss: u64 = s[17..19].parse().with_context(|| format!("bad second in {s:?}"))?;
+    anyhow::ensure!((1..=12).contains(&m), "month out of range in {s:?}");
+    anyhow::ensure!((1..=31).contains(&d), "day out of range in {s:?}");
+    Ok(days_since_epoch(y, m, d) * 86400 + hh * 3600 + mm * 60 + ss)
+}
+
+fn days_since_epoch(y: u32, m: u32, d: u32) -> u64 {
+    let mut days = 0u64;
+    for year in 1970..y {
+        let leap = year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+        days += if leap { 366 } else { 365 };
+    }
+    let leap = y.is_multiple_of(4) && (!y.is_multiple_of(100) || y.is_multiple_of(400));
+    let days_in_month = [31, if leap { 29 } else { 28 }, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+    for month in days_in_month.iter().take((m - 1) as usize) {
+        days += *month as u64;
+    }
+    days + (d - 1) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rfc3339_to_epoch_seconds() {
+        assert_eq!(parse_rfc3339("1970-01-01T00:00:00Z").unwrap(), 0);
+        assert_eq!(parse_rfc3339("2023-11-14T22:13:20Z").unwrap(), 1700000000);
+    }
+
+    #[test]
+    fn rejects_malformed_timestamp() {
+        assert!(parse_rfc3339("not-a-date").is_err());
+        assert!(parse_rfc3339("2023-13-01T00:00:00Z").is_err());
+    }
+}
@@ -0,0 +1,241 @@
+//! Standalone tool to run `simulator::simulate`/`optimal_params` from the
+//! command line, since nothing in `main.rs` calls into the Monte-Carlo
+//! simulator yet -- it's only ever exercised from its own tests.
+//!
+//! `ContextSampler` is implemented here by `CsvContextSampler`, which
+//! replays a CSV of historical `TradeContext` rows (one row per trade
+//! opportunity) cycling back to the first row once exhausted, so a
+//! sweep can draw more samples than the input has rows. The
+//! `*-synthetic` modes instead drive `market_path::MarketPathGenerator`,
+//! for stress-testing against fat-tailed/jumpy regimes no historical CSV
+//! is likely to contain.
+//!
+//! Usage:
+//!   strategy_sim run   <preset> <sessions> <trades_per_session> <seed> <contexts.csv>
+//!   strategy_sim sweep <preset> <sessions> <trades_per_session> <seed> <contexts.csv> \
+//!                      <min_edge_min> <min_edge_max> <min_edge_steps> \
+//!                      <vol_mult_min> <vol_mult_max> <vol_mult_steps> <max_acceptable_drawdown_pct>
+//!   strategy_sim run-synthetic   <preset> <sessions> <trades_per_session> <seed> <market-path args...>
+//!   strategy_sim sweep-synthetic <preset> <sessions> <trades_per_session> <seed> <market-path args...> \
+//!                      <min_edge_min> <min_edge_max> <min_edge_steps> \
+//!                      <vol_mult_min> <vol_mult_max> <vol_mult_steps> <max_acceptable_drawdown_pct>
+//!
+//! `<market-path args...>` is `<gaussian|cauchy> <drift> <scale> <jump_probability>
+//! <jump_multiplier> <max_abs_log_return> <window> <starting_price> <seconds_remaining>`,
+//! one positional argument per `market_path::MarketPathConfig` field.
+//!
+//! `contexts.csv` has a header row naming every `TradeContext` field in
+//! declaration order; `Option<f64>` fields (`exchange_price`, `rtds_price`)
+//! use an empty cell for `None`.
+//!
+//! There's no `[lib]` target in this crate, so the strategy/simulator
+//! modules are pulled in by path rather than depended on like a normal
+//! crate (see `src/bin/backfill.rs` for the same pattern).
+#[path = "../amount.rs"]
+mod amount;
+#[path = "../fixedpoint.rs"]
+mod fixedpoint;
+#[path = "../polymarket.rs"]
+mod polymarket;
+#[path = "../digital_option.rs"]
+mod digital_option;
+#[path = "../strategy.rs"]
+mod strategy;
+#[path = "../presets.rs"]
+mod presets;
+#[path = "../simulator.rs"]
+mod simulator;
+#[path = "../market_path.rs"]
+mod market_path;
+
+use anyhow::{Context, Result};
+use market_path::{MarketPathConfig, MarketPathGenerator, ReturnDistribution};
+use rand::rngs::StdRng;
+use simulator::{optimal_params, simulate, ContextSampler, ParamRange, SearchRanges, SimulatorConfig};
+use std::io::BufRead;
+use strategy::TradeContext;
+
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    anyhow::ensure!(args.len() >= 2, "{}", usage());
+    match args[1].as_str() {
+        "run" => {
+            anyhow::ensure!(args.len() == 7, "{}", usage());
+            let config = presets::get(&args[2]).with_context(|| format!("Unknown preset {:?}", args[2]))?;
+            let sim_config = parse_sim_config(&args[3], &args[4], &args[5])?;
+            let mut sampler = CsvContextSampler::load(&args[6])?;
+            let outcome = simulate(&sim_config, &config, &mut sampler);
+            println!(
+                "mean_terminal_bankroll={:.4} mean_win_rate={:.4} worst_session_drawdown_pct={:.4}",
+                outcome.mean_terminal_bankroll, outcome.mean_win_rate, outcome.worst_session_drawdown_pct
+            );
+            Ok(())
+        }
+        "sweep" => {
+            anyhow::ensure!(args.len() == 14, "{}", usage());
+            let config = presets::get(&args[2]).with_context(|| format!("Unknown preset {:?}", args[2]))?;
+            let sim_config = parse_sim_config(&args[3], &args[4], &args[5])?;
+            let mut sampler = CsvContextSampler::load(&args[6])?;
+            let ranges = SearchRanges {
+                min_edge_pct: parse_range(&args[7], &args[8], &args[9])?,
+                vol_confidence_multiplier: parse_range(&args[10], &args[11], &args[12])?,
+                max_acceptable_drawdown_pct: args[13].parse().context("bad max_acceptable_drawdown_pct")?,
+            };
+            match optimal_params(&sim_config, &config, &ranges, &mut sampler) {
+                Some(best) => println!(
+                    "min_edge_pct={:.4} vol_confidence_multiplier={:.4} score={:.4} mean_terminal_bankroll={:.4} worst_session_drawdown_pct={:.4}",
+                    best.min_edge_pct, best.vol_confidence_multiplier, best.score,
+                    best.outcome.mean_terminal_bankroll, best.outcome.worst_session_drawdown_pct
+                ),
+                None => println!("No grid point: both ranges need at least one step"),
+            }
+            Ok(())
+        }
+        "run-synthetic" => {
+            anyhow::ensure!(args.len() == 15, "{}", usage());
+            let config = presets::get(&args[2]).with_context(|| format!("Unknown preset {:?}", args[2]))?;
+            let sim_config = parse_sim_config(&args[3], &args[4], &args[5])?;
+            let mut sampler = MarketPathGenerator::new(parse_market_path_config(&args[6..15])?);
+            let outcome = simulate(&sim_config, &config, &mut sampler);
+            println!(
+                "mean_terminal_bankroll={:.4} mean_win_rate={:.4} worst_session_drawdown_pct={:.4}",
+                outcome.mean_terminal_bankroll, outcome.mean_win_rate, outcome.worst_session_drawdown_pct
+            );
+            Ok(())
+        }
+        "sweep-synthetic" => {
+            anyhow::ensure!(args.len() == 22, "{}", usage());
+            let config = presets::get(&args[2]).with_context(|| format!("Unknown preset {:?}", args[2]))?;
+            let sim_config = parse_sim_config(&args[3], &args[4], &args[5])?;
+            let mut sampler = MarketPathGenerator::new(parse_market_path_config(&args[6..15])?);
+            let ranges = SearchRanges {
+                min_edge_pct: parse_range(&args[15], &args[16], &args[17])?,
+                vol_confidence_multiplier: parse_range(&args[18], &args[19], &args[20])?,
+                max_acceptable_drawdown_pct: args[21].parse().context("bad max_acceptable_drawdown_pct")?,
+            };
+            match optimal_params(&sim_config, &config, &ranges, &mut sampler) {
+                Some(best) => println!(
+                    "min_edge_pct={:.4} vol_confidence_multiplier={:.4} score={:.4} mean_terminal_bankroll={:.4} worst_session_drawdown_pct={:.4}",
+                    best.min_edge_pct, best.vol_confidence_multiplier, best.score,
+                    best.outcome.mean_terminal_bankroll, best.outcome.worst_session_drawdown_pct
+                ),
+                None => println!("No grid point: both ranges need at least one step"),
+            }
+            Ok(())
+        }
+        other => anyhow::bail!("Unknown mode {other:?}\n{}", usage()),
+    }
+}
+
+fn usage() -> &'static str {
+    "Usage:\n  \
+     strategy_sim run   <preset> <sessions> <trades_per_session> <seed> <contexts.csv>\n  \
+     strategy_sim sweep <preset> <sessions> <trades_per_session> <seed> <contexts.csv> \
+     <min_edge_min> <min_edge_max> <min_edge_steps> <vol_mult_min> <vol_mult_max> <vol_mult_steps> <max_acceptable_drawdown_pct>\n  \
+     strategy_sim run-synthetic   <preset> <sessions> <trades_per_session> <seed> <market-path args...>\n  \
+     strategy_sim sweep-synthetic <preset> <sessions> <trades_per_session> <seed> <market-path args...> \
+     <min_edge_min> <min_edge_max> <min_edge_steps> <vol_mult_min> <vol_mult_max> <vol_mult_steps> <max_acceptable_drawdown_pct>\n  \
+     <market-path args...> = <gaussian|cauchy> <drift> <scale> <jump_probability> <jump_multiplier> \
+     <max_abs_log_return> <window> <starting_price> <seconds_remaining>"
+}
+
+/// Parses the 9 positional `market_path::MarketPathConfig` fields, in
+/// declaration order, from `args`.
+fn parse_market_path_config(args: &[String]) -> Result<MarketPathConfig> {
+    let distribution = match args[0].as_str() {
+        "gaussian" => ReturnDistribution::Gaussian,
+        "cauchy" => ReturnDistribution::Cauchy,
+        other => anyhow::bail!("Unknown distribution {other:?}, expected gaussian or cauchy"),
+    };
+    Ok(MarketPathConfig {
+        distribution,
+        drift: args[1].parse().context("bad drift")?,
+        scale: args[2].parse().context("bad scale")?,
+        jump_probability: args[3].parse().context("bad jump_probability")?,
+        jump_multiplier: args[4].parse().context("bad jump_multiplier")?,
+        max_abs_log_return: args[5].parse().context("bad max_abs_log_return")?,
+        window: args[6].parse().context("bad window")?,
+        starting_price: args[7].parse().context("bad starting_price")?,
+        seconds_remaining: args[8].parse().context("bad seconds_remaining")?,
+    })
+}
+
+fn parse_sim_config(sessions: &str, trades_per_session: &str, seed: &str) -> Result<SimulatorConfig> {
+    Ok(SimulatorConfig {
+        sessions: sessions.parse().context("bad sessions")?,
+        trades_per_session: trades_per_session.parse().context("bad trades_per_session")?,
+        starting_bankroll: 40.0,
+        seed: seed.parse().context("bad seed")?,
+    })
+}
+
+fn parse_range(min: &str, max: &str, steps: &str) -> Result<ParamRange> {
+    Ok(ParamRange {
+        min: min.parse().context("bad range min")?,
+        max: max.parse().context("bad range max")?,
+        steps: steps.parse().context("bad range steps")?,
+    })
+}
+
+/// Replays historical `TradeContext` rows loaded from a CSV, cycling back
+/// to the first row once exhausted so a sweep can draw more samples than
+/// the input has rows.
+struct CsvContextSampler {
+    rows: Vec<TradeContext>,
+    next: usize,
+}
+
+impl CsvContextSampler {
+    fn load(path: &str) -> Result<Self> {
+        let file = std::fs::File::open(path).with_context(|| format!("Cannot open {path}"))?;
+        let mut lines = std::io::BufReader::new(file).lines();
+        let header = lines.next().context("Empty contexts CSV")??;
+        let idx: std::collections::HashMap<&str, usize> = header.split(',').enumerate().map(|(i, name)| (name, i)).collect();
+
+        let mut rows = Vec::new();
+        for (i, line) in lines.enumerate() {
+            let line = line?;
+            let fields: Vec<&str> = line.split(',').collect();
+            let get = |name: &str| -> Result<&str> {
+                idx.get(name).and_then(|&c| fields.get(c)).copied().with_context(|| format!("row {i} missing column {name:?}"))
+            };
+            let parse = |name: &str| -> Result<f64> { get(name)?.parse().with_context(|| format!("row {i} bad {name}")) };
+            let parse_opt = |name: &str| -> Result<Option<f64>> {
+                let v = get(name)?;
+                if v.is_empty() { Ok(None) } else { Ok(Some(v.parse().with_context(|| format!("row {i} bad {name}"))?)) }
+            };
+            rows.push(TradeContext {
+                start_price: parse("start_price")?,
+                chainlink_price: parse("chainlink_price")?,
+                exchange_price: parse_opt("exchange_price")?,
+                rtds_price: parse_opt("rtds_price")?,
+                market_up_price: parse("market_up_price")?,
+                seconds_remaining: get("seconds_remaining")?.parse().with_context(|| format!("row {i} bad seconds_remaining"))?,
+                fee_rate: parse("fee_rate")?,
+                vol_5min_pct: parse("vol_5min_pct")?,
+                spread: parse("spread")?,
+                book_imbalance: parse("book_imbalance")?,
+                num_ws_sources: get("num_ws_sources")?.parse().with_context(|| format!("row {i} bad num_ws_sources"))?,
+                micro_vol: parse("micro_vol")?,
+                momentum_ratio: parse("momentum_ratio")?,
+                fisher: parse("fisher")?,
+                fisher_prev: parse("fisher_prev")?,
+                max_drawdown_bps: parse("max_drawdown_bps")?,
+            });
+        }
+        anyhow::ensure!(!rows.is_empty(), "contexts CSV has no data rows");
+        Ok(Self { rows, next: 0 })
+    }
+}
+
+impl ContextSampler for CsvContextSampler {
+    fn sample(&mut self, _rng: &mut StdRng) -> TradeContext {
+        let ctx = self.rows[self.next].clone();
+        self.next = (self.next + 1) % self.rows.len();
+        ctx
+    }
+
+    fn reset(&mut self) {
+        self.next = 0;
+    }
+}
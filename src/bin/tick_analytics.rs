@@ -0,0 +1,80 @@
+//! Streams a `TickLogger` CSV and emits an enriched CSV with rolling
+//! weighted-mean price, realized volatility, and tick rate over 5s/30s/60s
+//! spans (`analytics::WeightedMeanWindow`), so microstructure can be studied
+//! offline without re-running the live bot. Output keeps the original
+//! `window` column so rows can be joined back to `OutcomeLogger` data.
+//!
+//! There's no `[lib]` target in this crate, so the analytics module is
+//! pulled in by path rather than depended on like a normal crate (see
+//! `src/bin/backfill.rs` for the same pattern).
+//!
+//! Usage: `tick_analytics <ticks.csv> <output.csv> [uniform|expdecay]`
+//! `expdecay` down-weights a tick by how long it's been since the previous
+//! one, so a burst of ticks counts more toward the mean than a tick arriving
+//! after an unusually stale gap.
+#[path = "../analytics.rs"]
+mod analytics;
+
+use analytics::{exp_decay_weight, WeightedMeanWindow};
+use anyhow::{Context, Result};
+use std::io::{BufRead, BufWriter, Write};
+
+const SPANS_MS: [(u64, &str); 3] = [(5_000, "5s"), (30_000, "30s"), (60_000, "60s")];
+const HALF_LIFE_MS: u64 = 15_000;
+
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    anyhow::ensure!(
+        args.len() == 3 || args.len() == 4,
+        "Usage: tick_analytics <ticks.csv> <output.csv> [uniform|expdecay]"
+    );
+    let expdecay = match args.get(3).map(String::as_str) {
+        None | Some("uniform") => false,
+        Some("expdecay") => true,
+        Some(other) => anyhow::bail!("Unknown weight mode {other:?}, expected uniform or expdecay"),
+    };
+
+    let file = std::fs::File::open(&args[1]).with_context(|| format!("Cannot open {}", args[1]))?;
+    let reader = std::io::BufReader::new(file);
+    let out = std::fs::File::create(&args[2]).with_context(|| format!("Cannot create {}", args[2]))?;
+    let mut writer = BufWriter::new(out);
+    write!(writer, "timestamp_ms,window")?;
+    for (_, label) in SPANS_MS {
+        write!(writer, ",mean_{label},realized_vol_{label},tick_rate_{label}")?;
+    }
+    writeln!(writer)?;
+
+    let mut windows: Vec<WeightedMeanWindow> = SPANS_MS.iter().map(|(span, _)| WeightedMeanWindow::new(*span)).collect();
+    let mut last_timestamp_ms: Option<u64> = None;
+    let mut rows = 0u64;
+    for line in reader.lines() {
+        let line = line?;
+        if line.starts_with("timestamp_ms,") {
+            continue; // CSV header
+        }
+        let fields: Vec<&str> = line.split(',').collect();
+        anyhow::ensure!(fields.len() == 4, "malformed tick line: {line:?}");
+        let timestamp_ms: u64 = fields[0].parse().with_context(|| format!("bad timestamp_ms in {line:?}"))?;
+        let price: f64 = fields[2].parse().with_context(|| format!("bad price in {line:?}"))?;
+        let window: u64 = fields[3].parse().with_context(|| format!("bad window in {line:?}"))?;
+
+        let weight = if expdecay {
+            let gap_ms = last_timestamp_ms.map(|t| timestamp_ms.saturating_sub(t)).unwrap_or(0);
+            exp_decay_weight(gap_ms, HALF_LIFE_MS)
+        } else {
+            1.0
+        };
+        last_timestamp_ms = Some(timestamp_ms);
+
+        write!(writer, "{timestamp_ms},{window}")?;
+        for w in &mut windows {
+            w.push(timestamp_ms, price, weight);
+            write!(writer, ",{:.6},{:.6},{:.4}", w.mean(), w.realized_vol(), w.tick_rate())?;
+        }
+        writeln!(writer)?;
+        rows += 1;
+    }
+    writer.flush()?;
+    eprintln!("Wrote {rows} rows to {}", args[2]);
+    Ok(())
+}
@@ -0,0 +1,67 @@
+//! Converts a `TickLogger` CSV day file to/from `BinaryTickLogger`'s packed
+//! `ticks_YYYYMMDD.bin` format, for backtests that want to load pre-existing
+//! CSV history through the fast `BinaryTickReader` path without re-logging.
+//!
+//! There's no `[lib]` target in this crate, so the logger module is pulled
+//! in by path rather than depended on like a normal crate (see
+//! `src/bin/backfill.rs` for the same pattern).
+//!
+//! Usage: `tick_convert <to-bin|to-csv> <input> <output>`
+#[path = "../logger.rs"]
+mod logger;
+#[path = "../macro_data.rs"]
+mod macro_data;
+
+use anyhow::{Context, Result};
+use logger::{BinaryTickReader, TickRecord, TickSource};
+use std::io::{BufRead, BufWriter, Write};
+
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    anyhow::ensure!(args.len() == 4, "Usage: tick_convert <to-bin|to-csv> <input> <output>");
+    match args[1].as_str() {
+        "to-bin" => csv_to_bin(&args[2], &args[3]),
+        "to-csv" => bin_to_csv(&args[2], &args[3]),
+        other => anyhow::bail!("Unknown mode {other:?}, expected to-bin or to-csv"),
+    }
+}
+
+fn csv_to_bin(input: &str, output: &str) -> Result<()> {
+    let file = std::fs::File::open(input).with_context(|| format!("Cannot open {input}"))?;
+    let reader = std::io::BufReader::new(file);
+    let out = std::fs::File::create(output).with_context(|| format!("Cannot create {output}"))?;
+    let mut writer = BufWriter::new(out);
+    let mut count = 0u64;
+    for line in reader.lines() {
+        let line = line?;
+        if line.starts_with("timestamp_ms,") {
+            continue; // CSV header
+        }
+        let fields: Vec<&str> = line.split(',').collect();
+        anyhow::ensure!(fields.len() == 4, "malformed tick line: {line:?}");
+        let record = TickRecord {
+            timestamp_ms: fields[0].parse().with_context(|| format!("bad timestamp_ms in {line:?}"))?,
+            source: TickSource::from_str(fields[1]),
+            price: fields[2].parse().with_context(|| format!("bad price in {line:?}"))?,
+            window: fields[3].parse().with_context(|| format!("bad window in {line:?}"))?,
+        };
+        writer.write_all(&record.encode())?;
+        count += 1;
+    }
+    writer.flush()?;
+    eprintln!("Wrote {count} ticks to {output}");
+    Ok(())
+}
+
+fn bin_to_csv(input: &str, output: &str) -> Result<()> {
+    let reader = BinaryTickReader::open(input).with_context(|| format!("Cannot mmap {input}"))?;
+    let out = std::fs::File::create(output).with_context(|| format!("Cannot create {output}"))?;
+    let mut writer = BufWriter::new(out);
+    writeln!(writer, "timestamp_ms,source,price,window")?;
+    for record in reader.iter() {
+        writeln!(writer, "{},{},{:.2},{}", record.timestamp_ms, record.source.as_str(), record.price, record.window)?;
+    }
+    writer.flush()?;
+    eprintln!("Wrote {} ticks to {output}", reader.len());
+    Ok(())
+}
@@ -0,0 +1,394 @@
+//! Multi-outcome price-range markets ("BTC settles in $X–$Y") as a
+//! complement to the single UP/DOWN path in `strategy::evaluate`. A bucket
+//! set is K mutually-exclusive, collectively-exhaustive price ranges; this
+//! module validates the partition, maps the same z-score/Student-t model
+//! used for UP/DOWN into a per-bucket probability distribution, prices each
+//! bucket with an LMSR cost function, and scores the edge against each
+//! bucket's market price so the caller can trade the most mispriced one.
+
+use crate::strategy::{dynamic_fee, fractional_kelly, price_change_to_probability, Session, Signal, CalibrationMode, StrategyConfig};
+use crate::polymarket::Side;
+
+/// One mutually-exclusive outcome: settlement lands in `[lower, upper)`, or
+/// in `[lower, +inf)` when `upper` is `None` (only valid for the last,
+/// open-ended bucket in a partition).
+#[derive(Debug, Clone, Copy)]
+pub struct Bucket {
+    pub lower: f64,
+    pub upper: Option<f64>,
+    pub market_price: f64,
+}
+
+/// Why a bucket set failed the exhaustive/non-overlapping partition check.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BucketError {
+    Empty,
+    NotSorted,
+    Gap { after_index: usize },
+    Overlap { after_index: usize },
+    OpenEndedBeforeLast { index: usize },
+    MissingOpenUpperBucket,
+    PricesDontSumToOne { total_bps: i64 },
+}
+
+impl std::fmt::Display for BucketError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BucketError::Empty => write!(f, "bucket set is empty"),
+            BucketError::NotSorted => write!(f, "buckets must be sorted by lower bound"),
+            BucketError::Gap { after_index } => write!(f, "gap in partition after bucket {after_index}"),
+            BucketError::Overlap { after_index } => write!(f, "overlap in partition after bucket {after_index}"),
+            BucketError::OpenEndedBeforeLast { index } => write!(f, "bucket {index} is open-ended but isn't the last bucket"),
+            BucketError::MissingOpenUpperBucket => write!(f, "last bucket must be open-ended (upper = None)"),
+            BucketError::PricesDontSumToOne { total_bps } => write!(f, "market prices sum to {:.2} (expected ~1.00)", *total_bps as f64 / 10_000.0),
+        }
+    }
+}
+
+impl std::error::Error for BucketError {}
+
+/// Validates that `buckets` forms an exhaustive, non-overlapping partition
+/// with market prices summing to ~1 (within 5%), as required before any of
+/// them can be priced or traded.
+pub fn validate_partition(buckets: &[Bucket]) -> Result<(), BucketError> {
+    if buckets.is_empty() {
+        return Err(BucketError::Empty);
+    }
+    for i in 1..buckets.len() {
+        if buckets[i].lower < buckets[i - 1].lower {
+            return Err(BucketError::NotSorted);
+        }
+    }
+    for i in 0..buckets.len() - 1 {
+        let upper = buckets[i].upper.ok_or(BucketError::OpenEndedBeforeLast { index: i })?;
+        if upper < buckets[i + 1].lower {
+            return Err(BucketError::Gap { after_index: i });
+        }
+        if upper > buckets[i + 1].lower {
+            return Err(BucketError::Overlap { after_index: i });
+        }
+    }
+    if buckets.last().unwrap().upper.is_some() {
+        return Err(BucketError::MissingOpenUpperBucket);
+    }
+    let total_bps = (buckets.iter().map(|b| b.market_price).sum::<f64>() * 10_000.0).round() as i64;
+    if (total_bps - 10_000).abs() > 500 {
+        return Err(BucketError::PricesDontSumToOne { total_bps });
+    }
+    Ok(())
+}
+
+/// `exp` with the exponent clamped to a safe range so LMSR pricing can
+/// never overflow to infinity (or underflow to zero) on an extreme
+/// model probability.
+fn protected_exp(x: f64) -> f64 {
+    x.clamp(-50.0, 50.0).exp()
+}
+
+/// LMSR cost function C(q) = b·ln(Σ exp(q_i/b)), exposed for callers that
+/// want the raw market-scoring-rule cost rather than just the derived
+/// fair prices below.
+pub fn lmsr_cost(shares: &[f64], b: f64) -> f64 {
+    let sum_exp: f64 = shares.iter().map(|&q| protected_exp(q / b)).sum();
+    b * sum_exp.ln()
+}
+
+/// Per-bucket LMSR fair price ∂C/∂q_i = exp(q_i/b) / Σexp(q_j/b), derived
+/// from a target probability distribution via `q_i = b·ln(p_i)` — `b`
+/// cancels out of the gradient for this particular choice of `q_i`, which
+/// is why it doesn't appear as a parameter here (it matters for
+/// `lmsr_cost` itself, called with real LMSR inventory). A zero-probability
+/// bucket would otherwise drive `q_i` to `-inf`; `protected_exp` degrades
+/// that to ~0 instead of propagating NaN into the normalization.
+pub fn lmsr_fair_prices(model_probs: &[f64]) -> Vec<f64> {
+    let exp_terms: Vec<f64> = model_probs.iter()
+        .map(|&p| if p <= 0.0 { 0.0 } else { protected_exp(p.ln()) })
+        .collect();
+    let sum: f64 = exp_terms.iter().sum();
+    if sum <= 0.0 {
+        return vec![1.0 / model_probs.len() as f64; model_probs.len()];
+    }
+    exp_terms.iter().map(|&e| e / sum).collect()
+}
+
+/// Model probability that settlement lands in each bucket, reusing the same
+/// z-score/Student-t machinery `evaluate` uses for UP/DOWN:
+/// P(bucket) = P(final > lower) − P(final > upper).
+pub fn bucket_probabilities(
+    buckets: &[Bucket],
+    start_price: f64,
+    pct_change: f64,
+    seconds_remaining: u64,
+    vol_5min_pct: f64,
+    confidence_multiplier: f64,
+    student_t_df: f64,
+) -> Vec<f64> {
+    let prob_above = |threshold: f64| -> f64 {
+        let threshold_pct = (threshold - start_price) / start_price * 100.0;
+        price_change_to_probability(pct_change - threshold_pct, seconds_remaining, vol_5min_pct, confidence_multiplier, student_t_df)
+    };
+    buckets.iter()
+        .map(|bucket| {
+            let p_above_lower = prob_above(bucket.lower);
+            let p_above_upper = bucket.upper.map(prob_above).unwrap_or(0.0);
+            (p_above_lower - p_above_upper).max(0.0)
+        })
+        .collect()
+}
+
+/// A `Signal` scored against one bucket of a price-range market.
+#[derive(Debug)]
+pub struct BucketSignal {
+    pub bucket_index: usize,
+    pub signal: Signal,
+}
+
+/// Scores every bucket of a validated partition and returns a `Signal` for
+/// each one whose model-implied edge clears `config.min_edge_pct`, so the
+/// caller can trade the single most mispriced bucket (or several, if the
+/// market allows it) instead of only UP/DOWN.
+pub fn evaluate_buckets(
+    buckets: &[Bucket],
+    start_price: f64,
+    pct_change: f64,
+    seconds_remaining: u64,
+    vol_5min_pct: f64,
+    session: &Session,
+    config: &StrategyConfig,
+) -> Result<Vec<BucketSignal>, BucketError> {
+    validate_partition(buckets)?;
+
+    let model_probs = bucket_probabilities(
+        buckets, start_price, pct_change, seconds_remaining, vol_5min_pct,
+        config.vol_confidence_multiplier, config.student_t_df,
+    );
+    let fair_prices = lmsr_fair_prices(&model_probs);
+    let bankroll = session.bankroll();
+
+    let mut signals = Vec::new();
+    for (i, bucket) in buckets.iter().enumerate() {
+        if bucket.market_price <= 0.0 || bucket.market_price >= 1.0 {
+            continue;
+        }
+        let edge_brut_pct = (fair_prices[i] - bucket.market_price) * 100.0;
+        if edge_brut_pct <= 0.0 {
+            continue;
+        }
+        let fee = dynamic_fee(bucket.market_price, config.fee_rate);
+        let edge_pct = edge_brut_pct - fee * 100.0;
+        if edge_pct < config.min_edge_pct {
+            continue;
+        }
+        let size_usdc = fractional_kelly(
+            fair_prices[i], bucket.market_price, config.fee_rate,
+            config.kelly_fraction, bankroll, config.max_bet_usdc,
+        );
+        if size_usdc <= 0.0 {
+            continue;
+        }
+        signals.push(BucketSignal {
+            bucket_index: i,
+            signal: Signal {
+                side: Side::Buy,
+                edge_pct,
+                edge_brut_pct,
+                fee_pct: fee * 100.0,
+                implied_p_up: fair_prices[i],
+                size_usdc,
+                price: bucket.market_price,
+                // Vol-edge gating is a binary up/down concept (single strike);
+                // bucket markets have no single strike to price as a digital call.
+                implied_vol: 0.0,
+                vol_edge: 0.0,
+            },
+        });
+    }
+    Ok(signals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn three_buckets(low_price: f64, mid_price: f64, high_price: f64) -> Vec<Bucket> {
+        vec![
+            Bucket { lower: 0.0, upper: Some(95_000.0), market_price: low_price },
+            Bucket { lower: 95_000.0, upper: Some(105_000.0), market_price: mid_price },
+            Bucket { lower: 105_000.0, upper: None, market_price: high_price },
+        ]
+    }
+
+    #[test]
+    fn validate_partition_accepts_exhaustive_non_overlapping_buckets() {
+        assert!(validate_partition(&three_buckets(0.2, 0.6, 0.2)).is_ok());
+    }
+
+    #[test]
+    fn validate_partition_rejects_empty_set() {
+        assert_eq!(validate_partition(&[]), Err(BucketError::Empty));
+    }
+
+    #[test]
+    fn validate_partition_rejects_gap() {
+        let buckets = vec![
+            Bucket { lower: 0.0, upper: Some(95_000.0), market_price: 0.3 },
+            Bucket { lower: 96_000.0, upper: None, market_price: 0.7 },
+        ];
+        assert_eq!(validate_partition(&buckets), Err(BucketError::Gap { after_index: 0 }));
+    }
+
+    #[test]
+    fn validate_partition_rejects_overlap() {
+        let buckets = vec![
+            Bucket { lower: 0.0, upper: Some(95_000.0), market_price: 0.3 },
+            Bucket { lower: 94_000.0, upper: None, market_price: 0.7 },
+        ];
+        assert_eq!(validate_partition(&buckets), Err(BucketError::Overlap { after_index: 0 }));
+    }
+
+    #[test]
+    fn validate_partition_rejects_missing_open_upper_bucket() {
+        let buckets = vec![
+            Bucket { lower: 0.0, upper: Some(95_000.0), market_price: 0.5 },
+            Bucket { lower: 95_000.0, upper: Some(105_000.0), market_price: 0.5 },
+        ];
+        assert_eq!(validate_partition(&buckets), Err(BucketError::MissingOpenUpperBucket));
+    }
+
+    #[test]
+    fn validate_partition_rejects_prices_not_summing_to_one() {
+        assert_eq!(
+            validate_partition(&three_buckets(0.2, 0.2, 0.2)),
+            Err(BucketError::PricesDontSumToOne { total_bps: 6_000 })
+        );
+    }
+
+    #[test]
+    fn lmsr_fair_prices_normalizes_to_one() {
+        let prices = lmsr_fair_prices(&[0.2, 0.5, 0.3]);
+        let total: f64 = prices.iter().sum();
+        assert!((total - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn lmsr_fair_prices_matches_input_distribution() {
+        let prices = lmsr_fair_prices(&[0.2, 0.5, 0.3]);
+        assert!((prices[1] - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn lmsr_fair_prices_handles_zero_probability_bucket_without_nan() {
+        let prices = lmsr_fair_prices(&[0.0, 1.0]);
+        assert!(prices.iter().all(|p| p.is_finite()));
+        assert!(prices[1] > prices[0]);
+    }
+
+    #[test]
+    fn lmsr_cost_is_finite_for_extreme_shares() {
+        let cost = lmsr_cost(&[1e6, -1e6], 1.0);
+        assert!(cost.is_finite());
+    }
+
+    #[test]
+    fn bucket_probabilities_sum_to_approximately_one() {
+        let buckets = three_buckets(0.2, 0.6, 0.2);
+        let probs = bucket_probabilities(&buckets, 100_000.0, 0.3, 60, 0.1, 4.0, 4.0);
+        let total: f64 = probs.iter().sum();
+        assert!((total - 1.0).abs() < 1e-6, "got {total}");
+    }
+
+    #[test]
+    fn bucket_probabilities_favor_mid_bucket_with_small_move() {
+        let buckets = three_buckets(0.2, 0.6, 0.2);
+        let probs = bucket_probabilities(&buckets, 100_000.0, 0.0, 120, 0.08, 4.0, 4.0);
+        assert!(probs[1] > probs[0]);
+        assert!(probs[1] > probs[2]);
+    }
+
+    fn test_config() -> StrategyConfig {
+        StrategyConfig {
+            max_bet_usdc: 5.0,
+            min_bet_usdc: 1.0,
+            min_shares: 5,
+            min_edge_pct: 1.0,
+            entry_seconds_before_end: 10,
+            session_profit_target_usdc: 15.0,
+            session_loss_limit_usdc: 10.0,
+            fee_rate: 0.25,
+            min_market_price: 0.05,
+            max_market_price: 0.95,
+            min_delta_pct: 0.0,
+            max_spread: 0.1,
+            kelly_fraction: 0.2,
+            initial_bankroll_usdc: 40.0,
+            always_trade: false,
+            vol_confidence_multiplier: 4.0,
+            min_payout_ratio: 0.0,
+            min_book_imbalance: 0.0,
+            max_vol_5min_pct: 0.0,
+            min_ws_sources: 0,
+            circuit_breaker_window: 10,
+            circuit_breaker_min_wr: 0.3,
+            circuit_breaker_cooldown_s: 900,
+            min_implied_prob: 0.0,
+            max_consecutive_losses: 0,
+            student_t_df: 4.0,
+            min_z_score: 0.0,
+            max_model_divergence: 0.0,
+            quote_spread_pct: 0.0,
+            atr_window: 10,
+            exit_stop_atr_mult: 0.0,
+            exit_tp_atr_mult: 0.0,
+            exit_tp_window: 10,
+            fisher_window: 20,
+            fisher_extreme_threshold: 0.0,
+            min_vol_edge: 0.0,
+            roi_table: Vec::new(),
+            trailing_stop_pct: 0.0,
+            trailing_stop_bps: 0.0,
+            hard_stop_bps: 0.0,
+            min_momentum_exit: 0.0,
+            calibration_breakpoints: Vec::new(),
+            calibration_mode: CalibrationMode::Multiplier,
+            safety_spread_pct: 0.0,
+            trailing_stages: Vec::new(),
+            daily_fee_budget: 0.0,
+            daily_max_volume: 0.0,
+            feed_spread_pct: 0.0,
+            feed_skew_pct: 0.0,
+            symmetric_fee_model: false,
+            symmetric_fee_base_rate: 0.0,
+            consensus_max_deviation_pct: 0.0,
+        }
+    }
+
+    #[test]
+    fn evaluate_buckets_rejects_invalid_partition() {
+        let session = Session::new(40.0);
+        let config = test_config();
+        let result = evaluate_buckets(&[], 100_000.0, 0.0, 60, 0.08, &session, &config);
+        assert_eq!(result.unwrap_err(), BucketError::Empty);
+    }
+
+    #[test]
+    fn evaluate_buckets_finds_mispriced_bucket() {
+        let session = Session::new(40.0);
+        let config = test_config();
+        // Wide enough remaining vol that the model keeps a non-degenerate
+        // probability split (not saturated to exactly 0/1), with the mid
+        // bucket clearly favored but priced far too cheap by the market.
+        let buckets = three_buckets(0.45, 0.10, 0.45);
+        let signals = evaluate_buckets(&buckets, 100_000.0, 0.0, 120, 2.0, &session, &config).unwrap();
+        assert!(signals.iter().any(|s| s.bucket_index == 1));
+    }
+
+    #[test]
+    fn evaluate_buckets_skips_buckets_below_min_edge() {
+        let session = Session::new(40.0);
+        let mut config = test_config();
+        config.min_edge_pct = 99.0; // unreachable edge requirement
+        let buckets = three_buckets(0.2, 0.6, 0.2);
+        let signals = evaluate_buckets(&buckets, 100_000.0, 0.0, 120, 0.08, &session, &config).unwrap();
+        assert!(signals.is_empty());
+    }
+}
@@ -0,0 +1,358 @@
+//! OHLC candle aggregation over the raw per-exchange trade stream (cf.
+//! `[candles]` in config.toml). Unlike `db::CandleDelta` — which rolls
+//! Chainlink/RTDS/WS *ticks already fed into the trading loop* into
+//! per-minute bars keyed by the 5-min trading window — this module buckets
+//! each `exchanges::ExchangeFeed` source's raw updates independently by
+//! wall-clock interval, so the Data Farm preset can accumulate a clean,
+//! per-exchange OHLC history across runs for offline strategy tuning,
+//! decoupled from whether a trade window ever happens to use that source.
+//!
+//! `CandleAggregator::on_tick` only updates an in-memory bucket and hands a
+//! finished `Candle` to the `CandleWriter` when a new bucket starts.
+//! `PgCandleWriter` hands it off fire-and-forget, the same way
+//! `db::DbLogger` does its own writes; `JsonlCandleWriter` appends and
+//! flushes the line synchronously on the caller's task.
+
+use std::collections::HashMap;
+
+/// One finished OHLC bar for a single source.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Candle {
+    pub start_ms: u64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub ticks: u32,
+}
+
+/// Sink for finished candles. Implemented by `JsonlCandleWriter` (always
+/// available) and `PgCandleWriter` (optional Postgres mirror, cf.
+/// `db::DbLogger`).
+pub trait CandleWriter: Send {
+    fn write(&mut self, source: &str, candle: Candle);
+}
+
+struct Bucket {
+    start_ms: u64,
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+    ticks: u32,
+}
+
+impl Bucket {
+    fn open(start_ms: u64, price: f64) -> Self {
+        Self { start_ms, open: price, high: price, low: price, close: price, ticks: 1 }
+    }
+
+    fn push(&mut self, price: f64) {
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.close = price;
+        self.ticks += 1;
+    }
+
+    fn finish(&self) -> Candle {
+        Candle { start_ms: self.start_ms, open: self.open, high: self.high, low: self.low, close: self.close, ticks: self.ticks }
+    }
+}
+
+/// Rolls per-source price updates into fixed-width `interval_ms` OHLC bars,
+/// flushing a bucket to the writer as soon as a tick lands in the next one.
+/// One bucket per source label, so Binance/Coinbase/Kraken (or the mock
+/// `FixedRateSource` used in backtests) each get their own independent
+/// candle history.
+pub struct CandleAggregator {
+    interval_ms: u64,
+    buckets: HashMap<String, Bucket>,
+    writer: Box<dyn CandleWriter>,
+}
+
+impl CandleAggregator {
+    pub fn new(interval_ms: u64, writer: Box<dyn CandleWriter>) -> Self {
+        Self { interval_ms, buckets: HashMap::new(), writer }
+    }
+
+    /// Feeds one `(price, updated_ms)` observation from `source`. Flushes
+    /// and replaces the bucket when `updated_ms` falls in a later interval
+    /// than the one currently open; ticks landing in the same bucket update
+    /// it, and ticks landing in an earlier bucket are dropped.
+    pub fn on_tick(&mut self, source: &str, price: f64, updated_ms: u64) {
+        let bucket_start = (updated_ms / self.interval_ms) * self.interval_ms;
+        match self.buckets.get_mut(source) {
+            Some(bucket) if bucket.start_ms == bucket_start => bucket.push(price),
+            Some(bucket) if bucket_start > bucket.start_ms => {
+                self.writer.write(source, bucket.finish());
+                self.buckets.insert(source.to_string(), Bucket::open(bucket_start, price));
+            }
+            Some(_) => {}
+            None => {
+                self.buckets.insert(source.to_string(), Bucket::open(bucket_start, price));
+            }
+        }
+    }
+
+    /// Flushes every open bucket to the writer, e.g. on graceful shutdown.
+    pub fn flush(&mut self) {
+        for (source, bucket) in self.buckets.drain() {
+            self.writer.write(&source, bucket.finish());
+        }
+    }
+}
+
+/// Appends one JSON line per finished candle to `{dir}/{source}.jsonl`, one
+/// file per source, mirroring `logger::TickLogger`'s daily-file-per-stream
+/// layout but split by source instead of by day.
+pub struct JsonlCandleWriter {
+    dir: std::path::PathBuf,
+    files: HashMap<String, std::io::BufWriter<std::fs::File>>,
+    /// Flipped once a file open fails, mirroring `db::DbLogger`'s `connected`
+    /// flag: a transient FS error (disk full, permissions) degrades this
+    /// writer to a no-op instead of taking down `CandleAggregator::on_tick`,
+    /// which runs synchronously in the live trading loop.
+    disabled: bool,
+}
+
+impl JsonlCandleWriter {
+    pub fn new(dir: impl Into<std::path::PathBuf>) -> anyhow::Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { dir, files: HashMap::new(), disabled: false })
+    }
+}
+
+#[derive(serde::Serialize)]
+struct JsonlRow<'a> {
+    source: &'a str,
+    start_ms: u64,
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+    ticks: u32,
+}
+
+impl CandleWriter for JsonlCandleWriter {
+    fn write(&mut self, source: &str, candle: Candle) {
+        use std::io::Write;
+        if self.disabled {
+            return;
+        }
+        if !self.files.contains_key(source) {
+            let path = self.dir.join(format!("{source}.jsonl"));
+            match std::fs::OpenOptions::new().create(true).append(true).open(&path) {
+                Ok(file) => {
+                    self.files.insert(source.to_string(), std::io::BufWriter::new(file));
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "CandleAggregator: failed to open {}: {e:#}, disabling JSONL candle writes",
+                        path.display()
+                    );
+                    self.disabled = true;
+                    return;
+                }
+            }
+        }
+        let writer = self.files.get_mut(source).expect("just inserted above");
+        let row = JsonlRow {
+            source, start_ms: candle.start_ms, open: candle.open, high: candle.high,
+            low: candle.low, close: candle.close, ticks: candle.ticks,
+        };
+        let result = serde_json::to_string(&row)
+            .map_err(anyhow::Error::from)
+            .and_then(|line| writeln!(writer, "{line}").map_err(anyhow::Error::from))
+            .and_then(|_| writer.flush().map_err(anyhow::Error::from));
+        if let Err(e) = result {
+            tracing::warn!("CandleAggregator: failed to write candle for {source}: {e:#}");
+        }
+    }
+}
+
+const PG_BATCH_SIZE: usize = 200;
+const PG_FLUSH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+struct PgCandleRow {
+    source: String,
+    candle: Candle,
+}
+
+/// Mirrors finished candles into a `{table_prefix}source_candles` Postgres
+/// table, keyed by `(source, start_ms)` so repeated runs against the same
+/// interval accumulate history instead of duplicating rows — same
+/// fire-and-forget batching shape as `db::DbLogger`, just with its own
+/// table rather than reusing `db::DbLogger`'s window-keyed `candles`.
+pub struct PgCandleWriter {
+    tx: tokio::sync::mpsc::Sender<PgCandleRow>,
+}
+
+impl PgCandleWriter {
+    pub async fn connect(connection_string: &str, table_prefix: &str, channel_capacity: usize) -> anyhow::Result<Self> {
+        use anyhow::Context;
+        let (client, connection) = tokio_postgres::connect(connection_string, tokio_postgres::NoTls)
+            .await
+            .context("Postgres connection failed")?;
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                tracing::warn!("Candle writer: Postgres connection lost: {e}");
+            }
+        });
+
+        client
+            .batch_execute(&format!(
+                "CREATE TABLE IF NOT EXISTS {table_prefix}source_candles (
+                    source TEXT NOT NULL,
+                    start_ms BIGINT NOT NULL,
+                    open DOUBLE PRECISION NOT NULL,
+                    high DOUBLE PRECISION NOT NULL,
+                    low DOUBLE PRECISION NOT NULL,
+                    close DOUBLE PRECISION NOT NULL,
+                    ticks INTEGER NOT NULL,
+                    PRIMARY KEY (source, start_ms)
+                );"
+            ))
+            .await
+            .context("Failed to create candle table")?;
+
+        let (tx, rx) = tokio::sync::mpsc::channel(channel_capacity);
+        let prefix = table_prefix.to_string();
+        tokio::spawn(pg_batch_writer(client, rx, prefix));
+        Ok(Self { tx })
+    }
+}
+
+impl CandleWriter for PgCandleWriter {
+    fn write(&mut self, source: &str, candle: Candle) {
+        if self.tx.try_send(PgCandleRow { source: source.to_string(), candle }).is_err() {
+            tracing::warn!("Candle writer channel full or closed, dropping candle for {source}");
+        }
+    }
+}
+
+async fn pg_batch_writer(client: tokio_postgres::Client, mut rx: tokio::sync::mpsc::Receiver<PgCandleRow>, table_prefix: String) {
+    let mut buf = Vec::with_capacity(PG_BATCH_SIZE);
+    let mut ticker = tokio::time::interval(PG_FLUSH_INTERVAL);
+    loop {
+        tokio::select! {
+            row = rx.recv() => {
+                match row {
+                    Some(row) => {
+                        buf.push(row);
+                        if buf.len() >= PG_BATCH_SIZE {
+                            pg_flush(&client, &table_prefix, &mut buf).await;
+                        }
+                    }
+                    None => {
+                        pg_flush(&client, &table_prefix, &mut buf).await;
+                        return;
+                    }
+                }
+            }
+            _ = ticker.tick() => {
+                pg_flush(&client, &table_prefix, &mut buf).await;
+            }
+        }
+    }
+}
+
+async fn pg_flush(client: &tokio_postgres::Client, prefix: &str, buf: &mut Vec<PgCandleRow>) {
+    if buf.is_empty() {
+        return;
+    }
+    let batch = std::mem::take(buf);
+    for row in &batch {
+        let result = client.execute(
+            &format!(
+                "INSERT INTO {prefix}source_candles (source, start_ms, open, high, low, close, ticks) \
+                 VALUES ($1, $2, $3, $4, $5, $6, $7) \
+                 ON CONFLICT (source, start_ms) DO UPDATE SET \
+                    high = GREATEST({prefix}source_candles.high, EXCLUDED.high), \
+                    low = LEAST({prefix}source_candles.low, EXCLUDED.low), \
+                    close = EXCLUDED.close, \
+                    ticks = {prefix}source_candles.ticks + EXCLUDED.ticks"
+            ),
+            &[
+                &row.source, &(row.candle.start_ms as i64), &row.candle.open, &row.candle.high,
+                &row.candle.low, &row.candle.close, &(row.candle.ticks as i32),
+            ],
+        ).await;
+        if let Err(e) = result {
+            tracing::warn!("Candle writer: Postgres insert failed: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Collected = std::sync::Arc<std::sync::Mutex<Vec<(String, Candle)>>>;
+
+    fn make_aggregator(interval_ms: u64) -> (CandleAggregator, Collected) {
+        let collected: Collected = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        struct ProxyWriter(Collected);
+        impl CandleWriter for ProxyWriter {
+            fn write(&mut self, source: &str, candle: Candle) {
+                self.0.lock().unwrap().push((source.to_string(), candle));
+            }
+        }
+        let writer = Box::new(ProxyWriter(collected.clone()));
+        (CandleAggregator::new(interval_ms, writer), collected)
+    }
+
+    #[test]
+    fn ticks_within_interval_roll_into_one_candle() {
+        let (mut agg, collected) = make_aggregator(1_000);
+        agg.on_tick("binance", 100.0, 0);
+        agg.on_tick("binance", 105.0, 200);
+        agg.on_tick("binance", 95.0, 400);
+        agg.on_tick("binance", 102.0, 999);
+        assert!(collected.lock().unwrap().is_empty(), "same-bucket ticks must not flush yet");
+
+        agg.on_tick("binance", 110.0, 1_000);
+        let flushed = collected.lock().unwrap();
+        assert_eq!(flushed.len(), 1);
+        let (source, candle) = &flushed[0];
+        assert_eq!(source, "binance");
+        assert_eq!(candle.open, 100.0);
+        assert_eq!(candle.high, 105.0);
+        assert_eq!(candle.low, 95.0);
+        assert_eq!(candle.close, 102.0);
+        assert_eq!(candle.ticks, 4);
+    }
+
+    #[test]
+    fn sources_are_bucketed_independently() {
+        let (mut agg, collected) = make_aggregator(1_000);
+        agg.on_tick("binance", 100.0, 0);
+        agg.on_tick("kraken", 99.0, 0);
+        agg.on_tick("binance", 101.0, 1_000);
+        assert_eq!(collected.lock().unwrap().len(), 1, "kraken bucket must not be affected by binance rolling over");
+        agg.on_tick("kraken", 98.0, 1_000);
+        assert_eq!(collected.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn stale_tick_does_not_reopen_an_earlier_bucket() {
+        let (mut agg, collected) = make_aggregator(1_000);
+        agg.on_tick("binance", 100.0, 1_000);
+        agg.on_tick("binance", 50.0, 200); // older than the open bucket
+        agg.on_tick("binance", 110.0, 2_000);
+        let flushed = collected.lock().unwrap();
+        assert_eq!(flushed.len(), 1);
+        assert_eq!(flushed[0].1.open, 100.0, "stale tick must not have reset the open bucket");
+        assert_eq!(flushed[0].1.ticks, 1);
+    }
+
+    #[test]
+    fn flush_emits_open_buckets() {
+        let (mut agg, collected) = make_aggregator(1_000);
+        agg.on_tick("binance", 100.0, 0);
+        agg.on_tick("kraken", 99.0, 0);
+        assert!(collected.lock().unwrap().is_empty());
+        agg.flush();
+        assert_eq!(collected.lock().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,295 @@
+//! Generalizes `strategy::evaluate`'s binary UP/DOWN assumption to an
+//! arbitrary N-outcome market (any set of mutually-exclusive, collectively-
+//! exhaustive outcomes, not just price ranges — see `buckets` for the
+//! range-specific case). The caller supplies per-outcome prices and model
+//! probabilities plus a partition of outcome indices into "buy" (the
+//! combined position to take), "sell," and "keep" (left untouched); this
+//! validates that partition like a combinatorial AMM must, renormalizes the
+//! model probabilities, and scores/sizes the aggregate buy-set edge the
+//! same way `evaluate` sizes a single UP/DOWN trade.
+
+use crate::polymarket::Side;
+use crate::strategy::{dynamic_fee, fractional_kelly, Session, Signal, CalibrationMode, StrategyConfig};
+
+/// A partition of outcome indices `0..n_outcomes` into the set to buy, the
+/// set to sell, and the set to leave untouched.
+#[derive(Debug, Clone)]
+pub struct Partition {
+    pub buy: Vec<usize>,
+    pub sell: Vec<usize>,
+    pub keep: Vec<usize>,
+}
+
+/// Why a partition/probability vector was rejected.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PartitionError {
+    EmptyBuySet,
+    EmptySellSet,
+    IndexOutOfRange { index: usize },
+    DuplicateIndex { index: usize },
+    IncompleteCoverage,
+    MismatchedLengths,
+    ProbabilitiesDontNormalize { total_bps: i64 },
+}
+
+impl std::fmt::Display for PartitionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PartitionError::EmptyBuySet => write!(f, "buy set must not be empty"),
+            PartitionError::EmptySellSet => write!(f, "sell set must not be empty"),
+            PartitionError::IndexOutOfRange { index } => write!(f, "outcome index {index} is out of range"),
+            PartitionError::DuplicateIndex { index } => write!(f, "outcome index {index} appears in more than one set"),
+            PartitionError::IncompleteCoverage => write!(f, "buy/sell/keep sets don't cover every outcome"),
+            PartitionError::MismatchedLengths => write!(f, "prices and model probabilities have different lengths"),
+            PartitionError::ProbabilitiesDontNormalize { total_bps } =>
+                write!(f, "model probabilities sum to {:.2} (expected ~1.00)", *total_bps as f64 / 10_000.0),
+        }
+    }
+}
+
+impl std::error::Error for PartitionError {}
+
+/// Validates that `partition` is a pairwise-disjoint, collectively-exhaustive
+/// split of `0..n_outcomes` into buy/sell/keep, with neither buy nor sell
+/// empty — the combinatorial-AMM invariant this whole module depends on.
+pub fn validate_partition(partition: &Partition, n_outcomes: usize) -> Result<(), PartitionError> {
+    if partition.buy.is_empty() {
+        return Err(PartitionError::EmptyBuySet);
+    }
+    if partition.sell.is_empty() {
+        return Err(PartitionError::EmptySellSet);
+    }
+    let mut seen = vec![false; n_outcomes];
+    for &idx in partition.buy.iter().chain(&partition.sell).chain(&partition.keep) {
+        if idx >= n_outcomes {
+            return Err(PartitionError::IndexOutOfRange { index: idx });
+        }
+        if seen[idx] {
+            return Err(PartitionError::DuplicateIndex { index: idx });
+        }
+        seen[idx] = true;
+    }
+    if seen.iter().any(|&covered| !covered) {
+        return Err(PartitionError::IncompleteCoverage);
+    }
+    Ok(())
+}
+
+/// Renormalizes `model_probs` to sum to exactly 1, rejecting inputs whose
+/// raw sum is too far from 1 to trust (more than 5%, mirroring the
+/// tolerance `buckets::validate_partition` applies to bucket market prices).
+fn renormalize(model_probs: &[f64]) -> Result<Vec<f64>, PartitionError> {
+    let total_bps = (model_probs.iter().sum::<f64>() * 10_000.0).round() as i64;
+    if (total_bps - 10_000).abs() > 500 {
+        return Err(PartitionError::ProbabilitiesDontNormalize { total_bps });
+    }
+    let sum: f64 = model_probs.iter().sum();
+    Ok(model_probs.iter().map(|&p| p / sum).collect())
+}
+
+/// Computes the aggregate buy-set edge against the summed buy-set price,
+/// applies `dynamic_fee` and fractional Kelly sizing on that aggregate, and
+/// returns the resulting `Signal` — exactly as `evaluate` would for a
+/// single UP/DOWN trade, just scored over a combined position instead of
+/// one outcome.
+pub fn evaluate_combinatorial(
+    prices: &[f64],
+    model_probs: &[f64],
+    partition: &Partition,
+    session: &Session,
+    config: &StrategyConfig,
+) -> Result<Option<Signal>, PartitionError> {
+    if prices.len() != model_probs.len() {
+        return Err(PartitionError::MismatchedLengths);
+    }
+    validate_partition(partition, prices.len())?;
+    let normalized = renormalize(model_probs)?;
+
+    let buy_price: f64 = partition.buy.iter().map(|&i| prices[i]).sum();
+    let buy_prob: f64 = partition.buy.iter().map(|&i| normalized[i]).sum();
+
+    if buy_price <= 0.0 || buy_price >= 1.0 {
+        return Ok(None);
+    }
+    let edge_brut_pct = (buy_prob - buy_price) * 100.0;
+    if edge_brut_pct <= 0.0 {
+        return Ok(None);
+    }
+    let fee = dynamic_fee(buy_price, config.fee_rate);
+    let edge_pct = edge_brut_pct - fee * 100.0;
+    if edge_pct < config.min_edge_pct {
+        return Ok(None);
+    }
+
+    let bankroll = session.bankroll();
+    let size_usdc = fractional_kelly(
+        buy_prob, buy_price, config.fee_rate,
+        config.kelly_fraction, bankroll, config.max_bet_usdc,
+    );
+    if size_usdc <= 0.0 {
+        return Ok(None);
+    }
+
+    Ok(Some(Signal {
+        side: Side::Buy,
+        edge_pct,
+        edge_brut_pct,
+        fee_pct: fee * 100.0,
+        implied_p_up: buy_prob,
+        size_usdc,
+        price: buy_price,
+        // Single-strike digital-call vol gating doesn't generalize to an
+        // aggregate buy-set over N outcomes.
+        implied_vol: 0.0,
+        vol_edge: 0.0,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> StrategyConfig {
+        StrategyConfig {
+            max_bet_usdc: 5.0,
+            min_bet_usdc: 1.0,
+            min_shares: 5,
+            min_edge_pct: 1.0,
+            entry_seconds_before_end: 10,
+            session_profit_target_usdc: 1000.0,
+            session_loss_limit_usdc: 1000.0,
+            fee_rate: 0.25,
+            min_market_price: 0.05,
+            max_market_price: 0.95,
+            min_delta_pct: 0.0,
+            max_spread: 1.0,
+            kelly_fraction: 0.2,
+            initial_bankroll_usdc: 40.0,
+            always_trade: false,
+            vol_confidence_multiplier: 4.0,
+            min_payout_ratio: 0.0,
+            min_book_imbalance: 0.0,
+            max_vol_5min_pct: 0.0,
+            min_ws_sources: 0,
+            circuit_breaker_window: 0,
+            circuit_breaker_min_wr: 0.0,
+            circuit_breaker_cooldown_s: 0,
+            min_implied_prob: 0.0,
+            max_consecutive_losses: 0,
+            student_t_df: 0.0,
+            min_z_score: 0.0,
+            max_model_divergence: 0.0,
+            quote_spread_pct: 0.0,
+            atr_window: 10,
+            exit_stop_atr_mult: 0.0,
+            exit_tp_atr_mult: 0.0,
+            exit_tp_window: 10,
+            fisher_window: 20,
+            fisher_extreme_threshold: 0.0,
+            min_vol_edge: 0.0,
+            roi_table: Vec::new(),
+            trailing_stop_pct: 0.0,
+            trailing_stop_bps: 0.0,
+            hard_stop_bps: 0.0,
+            min_momentum_exit: 0.0,
+            calibration_breakpoints: Vec::new(),
+            calibration_mode: CalibrationMode::Multiplier,
+            safety_spread_pct: 0.0,
+            trailing_stages: Vec::new(),
+            daily_fee_budget: 0.0,
+            daily_max_volume: 0.0,
+            feed_spread_pct: 0.0,
+            feed_skew_pct: 0.0,
+            symmetric_fee_model: false,
+            symmetric_fee_base_rate: 0.0,
+            consensus_max_deviation_pct: 0.0,
+        }
+    }
+
+    fn three_outcomes() -> (Vec<f64>, Vec<f64>) {
+        // Market underprices outcomes 0+1 combined (0.20+0.20=0.40 priced,
+        // but model says 0.55 combined) vs. outcome 2 (0.60 priced, 0.45 model).
+        (vec![0.20, 0.20, 0.60], vec![0.30, 0.25, 0.45])
+    }
+
+    #[test]
+    fn rejects_empty_buy_set() {
+        let partition = Partition { buy: vec![], sell: vec![2], keep: vec![0, 1] };
+        assert_eq!(validate_partition(&partition, 3), Err(PartitionError::EmptyBuySet));
+    }
+
+    #[test]
+    fn rejects_empty_sell_set() {
+        let partition = Partition { buy: vec![0], sell: vec![], keep: vec![1, 2] };
+        assert_eq!(validate_partition(&partition, 3), Err(PartitionError::EmptySellSet));
+    }
+
+    #[test]
+    fn rejects_out_of_range_index() {
+        let partition = Partition { buy: vec![0], sell: vec![5], keep: vec![1, 2] };
+        assert_eq!(validate_partition(&partition, 3), Err(PartitionError::IndexOutOfRange { index: 5 }));
+    }
+
+    #[test]
+    fn rejects_duplicate_index_across_sets() {
+        let partition = Partition { buy: vec![0, 1], sell: vec![1], keep: vec![2] };
+        assert_eq!(validate_partition(&partition, 3), Err(PartitionError::DuplicateIndex { index: 1 }));
+    }
+
+    #[test]
+    fn rejects_incomplete_coverage() {
+        let partition = Partition { buy: vec![0], sell: vec![1], keep: vec![] };
+        assert_eq!(validate_partition(&partition, 3), Err(PartitionError::IncompleteCoverage));
+    }
+
+    #[test]
+    fn accepts_a_well_formed_partition() {
+        let partition = Partition { buy: vec![0, 1], sell: vec![2], keep: vec![] };
+        assert_eq!(validate_partition(&partition, 3), Ok(()));
+    }
+
+    #[test]
+    fn rejects_probabilities_that_dont_normalize() {
+        let (prices, _) = three_outcomes();
+        let partition = Partition { buy: vec![0, 1], sell: vec![2], keep: vec![] };
+        let config = test_config();
+        let session = Session::new(40.0);
+        let bad_probs = vec![0.30, 0.25, 0.10]; // sums to 0.65, way off 1.0
+        let result = evaluate_combinatorial(&prices, &bad_probs, &partition, &session, &config);
+        assert!(matches!(result, Err(PartitionError::ProbabilitiesDontNormalize { .. })));
+    }
+
+    #[test]
+    fn rejects_mismatched_lengths() {
+        let partition = Partition { buy: vec![0, 1], sell: vec![2], keep: vec![] };
+        let config = test_config();
+        let session = Session::new(40.0);
+        let result = evaluate_combinatorial(&[0.5, 0.5], &[0.3, 0.3, 0.4], &partition, &session, &config);
+        assert_eq!(result.unwrap_err(), PartitionError::MismatchedLengths);
+    }
+
+    #[test]
+    fn finds_mispriced_buy_set() {
+        let (prices, probs) = three_outcomes();
+        let partition = Partition { buy: vec![0, 1], sell: vec![2], keep: vec![] };
+        let config = test_config();
+        let session = Session::new(40.0);
+        let signal = evaluate_combinatorial(&prices, &probs, &partition, &session, &config)
+            .unwrap()
+            .expect("expected a mispriced buy set to produce a signal");
+        assert_eq!(signal.side, Side::Buy);
+        assert!((signal.price - 0.40).abs() < 1e-9);
+        assert!(signal.size_usdc > 0.0);
+    }
+
+    #[test]
+    fn no_signal_when_buy_set_already_fairly_priced() {
+        let prices = vec![0.30, 0.25, 0.45];
+        let probs = vec![0.30, 0.25, 0.45];
+        let partition = Partition { buy: vec![0, 1], sell: vec![2], keep: vec![] };
+        let config = test_config();
+        let session = Session::new(40.0);
+        let signal = evaluate_combinatorial(&prices, &probs, &partition, &session, &config).unwrap();
+        assert!(signal.is_none());
+    }
+}
@@ -0,0 +1,339 @@
+//! Optional Postgres/TimescaleDB mirror of the CSV logs (`[database]` config
+//! section in `main.rs`). Raw ticks and window-grain outcomes (trades, skips,
+//! resolutions) go into separate tables — analytics over ticks scan a very
+//! different row count than analytics over windows — and every row carries
+//! the window start timestamp and the price source that fed it. Ticks are
+//! also rolled into per-minute OHLC `candles`, grouped by the 5-min
+//! `window_start` they belong to, so past windows can be replayed as bars
+//! instead of a raw tick stream.
+//!
+//! Writes never block the hot trading loop: `DbLogger::log_*` calls are
+//! fire-and-forget sends into a bounded channel drained by a background
+//! task that batches rows into one transaction per flush. If the connection
+//! drops, the background task flips `connected` to `false` and every
+//! subsequent `log_*` call becomes a no-op — CSV logging (owned separately
+//! by the caller) keeps running either way, so a dead DB never loses data,
+//! it just stops being mirrored.
+//!
+//! Set `DATABASE_URL` to override `[database].connection_string` from the
+//! environment (same precedence as the `POLY_*` secret overrides).
+//!
+//! `src/bin/backfill.rs` ingests pre-existing CSV logs through this same
+//! `DbLogger`, for deployments that enable the database after already
+//! running on CSV-only logging for a while.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time;
+use tokio_postgres::Client;
+
+const BATCH_SIZE: usize = 200;
+const FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+
+enum DbEvent {
+    Tick {
+        ts_ms: u64, window: u64, source: String, price: f64,
+        micro_vol: f64, momentum_ratio: f64, sign_changes: u32,
+    },
+    Trade { window: u64, ts: u64, side: String, edge_pct: f64, size_usdc: f64, price: f64, price_source: String },
+    Skip { window: u64, ts: u64, reason: String, price_source: String },
+    Resolution { window: u64, ts: u64, result: String, pnl: f64, price_source: String },
+    Outcome { window: u64, btc_start: f64, btc_end: f64, price_source: String },
+}
+
+/// In-flush accumulation of one (window, minute) OHLC bar. `open` and `high`/
+/// `low` only cover ticks seen *in this flush*; merging across flushes
+/// happens in Postgres via `ON CONFLICT ... DO UPDATE` (see `write_batch`),
+/// which is why the in-memory map never needs to persist between flushes.
+struct CandleDelta {
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+    micro_vol: f64,
+    momentum_ratio: f64,
+    sign_changes: u32,
+    num_ticks: u32,
+}
+
+/// Handle to the background batch-writer task. `log_*` calls are
+/// cheap (bounded-channel sends); the actual Postgres I/O never runs on the
+/// caller's task.
+pub struct DbLogger {
+    tx: mpsc::Sender<DbEvent>,
+    connected: Arc<AtomicBool>,
+}
+
+impl DbLogger {
+    /// Connects to `connection_string`, ensures the `{table_prefix}ticks`/
+    /// `{table_prefix}outcomes` tables exist, and spawns the connection
+    /// driver and batch-writer background tasks. `channel_capacity` bounds
+    /// how many events can queue before `log_*` calls start dropping rows
+    /// rather than applying backpressure to the trading loop.
+    pub async fn connect(
+        connection_string: &str,
+        ssl: bool,
+        table_prefix: &str,
+        channel_capacity: usize,
+    ) -> Result<Self> {
+        let connected = Arc::new(AtomicBool::new(true));
+        let client = if ssl {
+            let connector = native_tls::TlsConnector::new().context("Failed to build TLS connector")?;
+            let connector = postgres_native_tls::MakeTlsConnector::new(connector);
+            let (client, connection) = tokio_postgres::connect(connection_string, connector)
+                .await
+                .context("Postgres connection failed")?;
+            let driver_connected = connected.clone();
+            tokio::spawn(async move {
+                if let Err(e) = connection.await {
+                    tracing::warn!("Postgres connection lost, falling back to CSV-only: {e}");
+                    driver_connected.store(false, Ordering::Relaxed);
+                }
+            });
+            client
+        } else {
+            let (client, connection) = tokio_postgres::connect(connection_string, tokio_postgres::NoTls)
+                .await
+                .context("Postgres connection failed")?;
+            let driver_connected = connected.clone();
+            tokio::spawn(async move {
+                if let Err(e) = connection.await {
+                    tracing::warn!("Postgres connection lost, falling back to CSV-only: {e}");
+                    driver_connected.store(false, Ordering::Relaxed);
+                }
+            });
+            client
+        };
+
+        ensure_schema(&client, table_prefix).await?;
+
+        let (tx, rx) = mpsc::channel(channel_capacity);
+        let prefix = table_prefix.to_string();
+        let batch_connected = connected.clone();
+        tokio::spawn(batch_writer(client, rx, prefix, batch_connected));
+
+        Ok(Self { tx, connected })
+    }
+
+    fn send(&self, event: DbEvent) {
+        if !self.connected.load(Ordering::Relaxed) {
+            return;
+        }
+        if self.tx.try_send(event).is_err() {
+            tracing::warn!("Database channel full or closed, dropping event (CSV logging continues)");
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn log_tick(
+        &self, ts_ms: u64, source: &str, price: f64, window: u64,
+        micro_vol: f64, momentum_ratio: f64, sign_changes: u32,
+    ) {
+        self.send(DbEvent::Tick {
+            ts_ms, window, source: source.to_string(), price, micro_vol, momentum_ratio, sign_changes,
+        });
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn log_trade(&self, window: u64, ts: u64, side: &str, edge_pct: f64, size_usdc: f64, price: f64, price_source: &str) {
+        self.send(DbEvent::Trade {
+            window, ts, side: side.to_string(), edge_pct, size_usdc, price,
+            price_source: price_source.to_string(),
+        });
+    }
+
+    pub fn log_skip(&self, window: u64, ts: u64, reason: &str, price_source: &str) {
+        self.send(DbEvent::Skip { window, ts, reason: reason.to_string(), price_source: price_source.to_string() });
+    }
+
+    pub fn log_resolution(&self, window: u64, ts: u64, result: &str, pnl: f64, price_source: &str) {
+        self.send(DbEvent::Resolution {
+            window, ts, result: result.to_string(), pnl,
+            price_source: price_source.to_string(),
+        });
+    }
+
+    pub fn log_outcome(&self, window: u64, btc_start: f64, btc_end: f64, price_source: &str) {
+        self.send(DbEvent::Outcome { window, btc_start, btc_end, price_source: price_source.to_string() });
+    }
+}
+
+async fn ensure_schema(client: &Client, prefix: &str) -> Result<()> {
+    client
+        .batch_execute(&format!(
+            "CREATE TABLE IF NOT EXISTS {prefix}ticks (
+                id BIGSERIAL PRIMARY KEY,
+                ts_ms BIGINT NOT NULL,
+                window_start BIGINT NOT NULL,
+                source TEXT NOT NULL,
+                price DOUBLE PRECISION NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS {prefix}outcomes (
+                id BIGSERIAL PRIMARY KEY,
+                window_start BIGINT NOT NULL,
+                ts BIGINT,
+                event TEXT NOT NULL,
+                side TEXT,
+                edge_pct DOUBLE PRECISION,
+                size_usdc DOUBLE PRECISION,
+                price DOUBLE PRECISION,
+                skip_reason TEXT,
+                result TEXT,
+                pnl DOUBLE PRECISION,
+                btc_start DOUBLE PRECISION,
+                btc_end DOUBLE PRECISION,
+                price_source TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS {prefix}candles (
+                window_start BIGINT NOT NULL,
+                minute_start BIGINT NOT NULL,
+                open DOUBLE PRECISION NOT NULL,
+                high DOUBLE PRECISION NOT NULL,
+                low DOUBLE PRECISION NOT NULL,
+                close DOUBLE PRECISION NOT NULL,
+                micro_vol DOUBLE PRECISION NOT NULL,
+                momentum_ratio DOUBLE PRECISION NOT NULL,
+                sign_changes INTEGER NOT NULL,
+                num_ticks INTEGER NOT NULL,
+                PRIMARY KEY (window_start, minute_start)
+            );"
+        ))
+        .await
+        .context("Failed to create database tables")?;
+    Ok(())
+}
+
+async fn batch_writer(client: Client, mut rx: mpsc::Receiver<DbEvent>, table_prefix: String, connected: Arc<AtomicBool>) {
+    let mut buf = Vec::with_capacity(BATCH_SIZE);
+    let mut ticker = time::interval(FLUSH_INTERVAL);
+    loop {
+        tokio::select! {
+            event = rx.recv() => {
+                match event {
+                    Some(event) => {
+                        buf.push(event);
+                        if buf.len() >= BATCH_SIZE {
+                            flush(&client, &table_prefix, &mut buf, &connected).await;
+                        }
+                    }
+                    None => {
+                        flush(&client, &table_prefix, &mut buf, &connected).await;
+                        return;
+                    }
+                }
+            }
+            _ = ticker.tick() => {
+                flush(&client, &table_prefix, &mut buf, &connected).await;
+            }
+        }
+    }
+}
+
+async fn flush(client: &Client, prefix: &str, buf: &mut Vec<DbEvent>, connected: &Arc<AtomicBool>) {
+    if buf.is_empty() {
+        return;
+    }
+    let batch = std::mem::take(buf);
+    let result = write_batch(client, prefix, &batch).await;
+    if let Err(e) = result {
+        tracing::warn!("Postgres batch insert failed, falling back to CSV-only: {e}");
+        connected.store(false, Ordering::Relaxed);
+    }
+}
+
+async fn write_batch(client: &Client, prefix: &str, batch: &[DbEvent]) -> Result<(), tokio_postgres::Error> {
+    let txn = client.transaction().await?;
+    let mut candles: HashMap<(i64, i64), CandleDelta> = HashMap::new();
+    for event in batch {
+        match event {
+            DbEvent::Tick { ts_ms, window, source, price, micro_vol, momentum_ratio, sign_changes } => {
+                txn.execute(
+                    &format!("INSERT INTO {prefix}ticks (ts_ms, window_start, source, price) VALUES ($1, $2, $3, $4)"),
+                    &[&(*ts_ms as i64), &(*window as i64), source, price],
+                ).await?;
+
+                let minute_start = (*ts_ms / 1000 / 60) * 60;
+                let key = (*window as i64, minute_start as i64);
+                candles.entry(key)
+                    .and_modify(|c| {
+                        c.high = c.high.max(*price);
+                        c.low = c.low.min(*price);
+                        c.close = *price;
+                        c.micro_vol = *micro_vol;
+                        c.momentum_ratio = *momentum_ratio;
+                        c.sign_changes = *sign_changes;
+                        c.num_ticks += 1;
+                    })
+                    .or_insert(CandleDelta {
+                        open: *price, high: *price, low: *price, close: *price,
+                        micro_vol: *micro_vol, momentum_ratio: *momentum_ratio, sign_changes: *sign_changes,
+                        num_ticks: 1,
+                    });
+            }
+            DbEvent::Trade { window, ts, side, edge_pct, size_usdc, price, price_source } => {
+                txn.execute(
+                    &format!(
+                        "INSERT INTO {prefix}outcomes (window_start, ts, event, side, edge_pct, size_usdc, price, price_source) \
+                         VALUES ($1, $2, 'trade', $3, $4, $5, $6, $7)"
+                    ),
+                    &[&(*window as i64), &(*ts as i64), side, edge_pct, size_usdc, price, price_source],
+                ).await?;
+            }
+            DbEvent::Skip { window, ts, reason, price_source } => {
+                txn.execute(
+                    &format!(
+                        "INSERT INTO {prefix}outcomes (window_start, ts, event, skip_reason, price_source) \
+                         VALUES ($1, $2, 'skip', $3, $4)"
+                    ),
+                    &[&(*window as i64), &(*ts as i64), reason, price_source],
+                ).await?;
+            }
+            DbEvent::Resolution { window, ts, result, pnl, price_source } => {
+                txn.execute(
+                    &format!(
+                        "INSERT INTO {prefix}outcomes (window_start, ts, event, result, pnl, price_source) \
+                         VALUES ($1, $2, 'resolution', $3, $4, $5)"
+                    ),
+                    &[&(*window as i64), &(*ts as i64), result, pnl, price_source],
+                ).await?;
+            }
+            DbEvent::Outcome { window, btc_start, btc_end, price_source } => {
+                txn.execute(
+                    &format!(
+                        "INSERT INTO {prefix}outcomes (window_start, event, btc_start, btc_end, price_source) \
+                         VALUES ($1, 'outcome', $2, $3, $4)"
+                    ),
+                    &[&(*window as i64), btc_start, btc_end, price_source],
+                ).await?;
+            }
+        }
+    }
+
+    for ((window_start, minute_start), c) in &candles {
+        txn.execute(
+            &format!(
+                "INSERT INTO {prefix}candles \
+                    (window_start, minute_start, open, high, low, close, micro_vol, momentum_ratio, sign_changes, num_ticks) \
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) \
+                 ON CONFLICT (window_start, minute_start) DO UPDATE SET \
+                    high = GREATEST({prefix}candles.high, EXCLUDED.high), \
+                    low = LEAST({prefix}candles.low, EXCLUDED.low), \
+                    close = EXCLUDED.close, \
+                    micro_vol = EXCLUDED.micro_vol, \
+                    momentum_ratio = EXCLUDED.momentum_ratio, \
+                    sign_changes = EXCLUDED.sign_changes, \
+                    num_ticks = {prefix}candles.num_ticks + EXCLUDED.num_ticks"
+            ),
+            &[
+                window_start, minute_start, &c.open, &c.high, &c.low, &c.close,
+                &c.micro_vol, &c.momentum_ratio, &(c.sign_changes as i32), &(c.num_ticks as i32),
+            ],
+        ).await?;
+    }
+
+    txn.commit().await
+}
@@ -0,0 +1,225 @@
+//! Prices the UP token as a cash-or-nothing digital call and backs out the
+//! volatility the market is implying, so the strategy can tell "BTC moved"
+//! apart from "the market is underpricing vol."
+//!
+//! Fair P(up) = N(d2), d2 = (ln(S/K) - σ²T/2) / (σ√T), r ≈ 0 (5min/15min
+//! windows, no meaningful carry). Given `market_up_price` this is inverted
+//! numerically for σ by bisection, then compared against the realized vol
+//! `VolTracker` already estimates.
+
+use crate::strategy::normal_cdf;
+
+const SECONDS_PER_YEAR: f64 = 365.0 * 24.0 * 3600.0;
+const SIGMA_MIN: f64 = 0.001;
+const SIGMA_MAX: f64 = 5.0;
+const BISECTION_ITERS: u32 = 60;
+
+/// Converts `seconds_remaining` into years, as the BS-style formulas expect.
+pub fn seconds_to_years(seconds_remaining: u64) -> f64 {
+    seconds_remaining as f64 / SECONDS_PER_YEAR
+}
+
+/// Annualizes a 5-minute-window realized vol (the same units `VolTracker`
+/// and `price_change_to_probability` already use) so it's comparable to the
+/// implied vol recovered from `implied_vol`.
+pub fn annualize_vol_5min_pct(vol_5min_pct: f64) -> f64 {
+    (vol_5min_pct / 100.0) * (SECONDS_PER_YEAR / 300.0).sqrt()
+}
+
+/// `d2` term of the cash-or-nothing digital call, expressed directly from
+/// a log-return so `price_change_to_probability` can share it without
+/// reconstructing an (S, K) pair. `σ√T` is clamped away from zero so a
+/// tiny `t_years` (seconds_remaining near zero) can't blow the quotient up
+/// to infinity. The `- 0.5·σ²·T` term is the Itô drift correction a plain
+/// z-score omits: under GBM the log-price is normal with mean
+/// (μ − ½σ²)τ, not μτ, even when μ = 0.
+fn d2_from_log_return(log_return: f64, sigma: f64, t_years: f64) -> f64 {
+    let sigma_sqrt_t = (sigma * t_years.sqrt()).max(1e-9);
+    (log_return - 0.5 * sigma * sigma * t_years) / sigma_sqrt_t
+}
+
+/// `d2` term of the cash-or-nothing digital call, with `σ√T` clamped away
+/// from zero so a tiny `t_years` (seconds_remaining near zero) can't blow
+/// the quotient up to infinity.
+fn d2(s: f64, k: f64, sigma: f64, t_years: f64) -> f64 {
+    d2_from_log_return((s / k).ln(), sigma, t_years)
+}
+
+/// Fair P(up) under the digital-call model for a given volatility.
+pub fn fair_prob_up(s: f64, k: f64, sigma: f64, t_years: f64) -> f64 {
+    normal_cdf(d2(s, k, sigma, t_years))
+}
+
+/// The `d2` z-score for an endpoint digital ("ends above start") settlement,
+/// taking the inputs `price_change_to_probability` already has on hand — a
+/// percent price change and a 5-minute-window realized vol — rather than a
+/// raw (S, K) pair. `pct_change` of -100% or worse (price at or below zero)
+/// has no log-return; the caller should treat that as a certain DOWN move
+/// rather than call this.
+pub(crate) fn endpoint_z(pct_change: f64, seconds_remaining: u64, vol_5min_pct: f64, confidence_multiplier: f64) -> f64 {
+    let t_years = seconds_to_years(seconds_remaining);
+    let sigma = annualize_vol_5min_pct(vol_5min_pct) * confidence_multiplier;
+    let log_return = (1.0 + pct_change / 100.0).ln();
+    d2_from_log_return(log_return, sigma, t_years)
+}
+
+/// Reflection-principle probability that a driftless GBM's running max ever
+/// crosses barrier `b` (relative to current price `s`) over the remaining
+/// time `t_years`: `P(max_{u<=τ} S_u > b) = 2·Φ(-|ln(b/s)|/(σ√τ))`. Unlike
+/// `fair_prob_up` (where does price end up), this is for "did price ever
+/// touch/cross the level" settlement — the kind `WindowTicks::time_above_start_s`
+/// already tracks realized, this is its forward-looking model counterpart.
+/// Returns 1.0 if `s` has already reached `b` (nothing left to cross) and
+/// 0.0 for a non-positive `sigma`/`t_years`/`s`/`b` (no meaningful path).
+pub fn touch_probability(s: f64, b: f64, sigma: f64, t_years: f64) -> f64 {
+    if s <= 0.0 || b <= 0.0 {
+        return 0.0;
+    }
+    if s >= b {
+        // The barrier is already at or below the current price, so the
+        // running extreme has already reached it.
+        return 1.0;
+    }
+    if sigma <= 0.0 || t_years <= 0.0 {
+        return 0.0;
+    }
+    let sigma_sqrt_t = (sigma * t_years.sqrt()).max(1e-9);
+    let z = (b / s).ln().abs() / sigma_sqrt_t;
+    (2.0 * normal_cdf(-z)).min(1.0)
+}
+
+/// Recovers the market-implied volatility by bisecting `fair_prob_up` over
+/// `sigma` against the observed `market_price`. Over the small `σ√T` this
+/// pipeline operates in (seconds-scale windows), `fair_prob_up` is
+/// monotonic in `sigma` across `[SIGMA_MIN, SIGMA_MAX]`, so bisection
+/// converges cleanly. Returns `None` for a non-positive `t_years` (caller
+/// should fall back to the existing direction-lock behavior instead).
+pub fn implied_vol(s: f64, k: f64, market_price: f64, t_years: f64) -> Option<f64> {
+    if t_years <= 0.0 || s <= 0.0 || k <= 0.0 {
+        return None;
+    }
+    let target = market_price.clamp(1e-6, 1.0 - 1e-6);
+    let mut lo = SIGMA_MIN;
+    let mut hi = SIGMA_MAX;
+    let prob_lo = fair_prob_up(s, k, lo, t_years);
+    let prob_hi = fair_prob_up(s, k, hi, t_years);
+    let increasing = prob_hi > prob_lo;
+    for _ in 0..BISECTION_ITERS {
+        let mid = 0.5 * (lo + hi);
+        let prob_mid = fair_prob_up(s, k, mid, t_years);
+        if (prob_mid > target) == increasing {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    Some(0.5 * (lo + hi))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fair_prob_up_is_above_half_when_price_above_strike() {
+        let p = fair_prob_up(101_000.0, 100_000.0, 0.6, seconds_to_years(300));
+        assert!(p > 0.5, "expected p > 0.5, got {p}");
+    }
+
+    #[test]
+    fn fair_prob_up_is_below_half_when_price_below_strike() {
+        let p = fair_prob_up(99_000.0, 100_000.0, 0.6, seconds_to_years(300));
+        assert!(p < 0.5, "expected p < 0.5, got {p}");
+    }
+
+    #[test]
+    fn fair_prob_up_is_half_at_the_money() {
+        let p = fair_prob_up(100_000.0, 100_000.0, 0.6, seconds_to_years(300));
+        assert!((p - 0.5).abs() < 1e-6, "expected ~0.5, got {p}");
+    }
+
+    #[test]
+    fn implied_vol_recovers_the_sigma_used_to_price() {
+        let t_years = seconds_to_years(180);
+        let sigma_true = 0.8;
+        let market_price = fair_prob_up(100_500.0, 100_000.0, sigma_true, t_years);
+        let recovered = implied_vol(100_500.0, 100_000.0, market_price, t_years).unwrap();
+        assert!((recovered - sigma_true).abs() < 1e-3, "got {recovered}, want {sigma_true}");
+    }
+
+    #[test]
+    fn implied_vol_returns_none_for_zero_time_remaining() {
+        assert_eq!(implied_vol(100_500.0, 100_000.0, 0.6, 0.0), None);
+    }
+
+    #[test]
+    fn implied_vol_is_deterministic_across_repeated_calls() {
+        let t_years = seconds_to_years(120);
+        let a = implied_vol(100_200.0, 100_000.0, 0.55, t_years);
+        let b = implied_vol(100_200.0, 100_000.0, 0.55, t_years);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn annualize_vol_5min_pct_scales_up_from_5min_to_annual() {
+        let annual = annualize_vol_5min_pct(0.1);
+        assert!(annual > 0.1 / 100.0);
+    }
+
+    // --- endpoint_z ---
+
+    #[test]
+    fn endpoint_z_matches_fair_prob_up_on_the_same_inputs() {
+        // endpoint_z/normal_cdf should reproduce fair_prob_up exactly -- it's
+        // the same d2, just taking a pct_change instead of a raw (S, K) pair.
+        let z = endpoint_z(0.5, 120, 0.12, 1.0);
+        let via_endpoint_z = normal_cdf(z);
+        let sigma = annualize_vol_5min_pct(0.12);
+        let via_fair_prob_up = fair_prob_up(100_500.0, 100_000.0, sigma, seconds_to_years(120));
+        assert!((via_endpoint_z - via_fair_prob_up).abs() < 1e-9,
+            "got {via_endpoint_z}, want {via_fair_prob_up}");
+    }
+
+    #[test]
+    fn endpoint_z_drift_correction_pulls_the_z_score_down_from_the_naive_ratio() {
+        // The Ito correction (-1/2 sigma^2 tau) should make the z-score for
+        // an UP move strictly smaller than the naive pct_change/vol ratio.
+        let z = endpoint_z(0.5, 120, 0.12, 1.0);
+        let naive_z = 0.5 / (0.12 * ((120.0_f64) / 300.0).sqrt());
+        assert!(z < naive_z, "drift-corrected z {z} should be < naive z {naive_z}");
+    }
+
+    // --- touch_probability ---
+
+    #[test]
+    fn touch_probability_is_one_once_the_barrier_is_already_reached() {
+        assert_eq!(touch_probability(101_000.0, 100_000.0, 0.6, seconds_to_years(300)), 1.0);
+        assert_eq!(touch_probability(100_000.0, 100_000.0, 0.6, seconds_to_years(300)), 1.0);
+    }
+
+    #[test]
+    fn touch_probability_exceeds_the_one_sided_endpoint_probability() {
+        // Reflection gives 2x the tail mass of the endpoint model, since
+        // "ever touches" is strictly easier than "ends above."
+        let t_years = seconds_to_years(300);
+        let sigma = 0.6;
+        let touch = touch_probability(99_000.0, 100_000.0, sigma, t_years);
+        let end_above = fair_prob_up(99_000.0, 100_000.0, sigma, t_years);
+        assert!(touch > end_above, "touch {touch} should exceed endpoint {end_above}");
+    }
+
+    #[test]
+    fn touch_probability_increases_with_more_time_remaining() {
+        let sigma = 0.6;
+        let short = touch_probability(99_000.0, 100_000.0, sigma, seconds_to_years(60));
+        let long = touch_probability(99_000.0, 100_000.0, sigma, seconds_to_years(600));
+        assert!(long > short, "more time should raise the chance of touching: {long} vs {short}");
+    }
+
+    #[test]
+    fn touch_probability_is_zero_for_non_positive_inputs() {
+        assert_eq!(touch_probability(0.0, 100_000.0, 0.6, seconds_to_years(300)), 0.0);
+        assert_eq!(touch_probability(99_000.0, 100_000.0, 0.0, seconds_to_years(300)), 0.0);
+        assert_eq!(touch_probability(99_000.0, 100_000.0, 0.6, 0.0), 0.0);
+    }
+}
@@ -1,12 +1,47 @@
 use anyhow::{Context, Result};
+use async_trait::async_trait;
 use futures::{SinkExt, StreamExt};
 use serde::Deserialize;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::watch;
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 
 const STALE_MS: u64 = 5_000;
 
+/// Shared connection health for one `WsPriceSource`: plain atomics behind an
+/// `Arc`, cloned into the source's background task — same "roll it by hand"
+/// approach as `metrics::Metrics` rather than another watch channel, since
+/// all we need here is a bool and a monotonic counter.
+#[derive(Clone, Default)]
+pub struct ConnHealth(Arc<ConnHealthInner>);
+
+#[derive(Default)]
+struct ConnHealthInner {
+    connected: AtomicBool,
+    reconnects: AtomicU32,
+}
+
+impl ConnHealth {
+    fn set_connected(&self, connected: bool) {
+        self.0.connected.store(connected, Ordering::Relaxed);
+    }
+
+    /// Increments the reconnect counter and returns the new total.
+    fn note_reconnect(&self) -> u32 {
+        self.0.reconnects.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    pub fn connected(&self) -> bool {
+        self.0.connected.load(Ordering::Relaxed)
+    }
+
+    pub fn reconnects(&self) -> u32 {
+        self.0.reconnects.load(Ordering::Relaxed)
+    }
+}
+
 #[derive(Debug, Clone, Copy, Default)]
 #[allow(dead_code)]
 pub struct AggregatedPrice {
@@ -21,45 +56,267 @@ struct Slot {
     updated_ms: u64,
 }
 
+/// One source's health as seen by `ExchangeFeed::source_status`.
+#[derive(Debug, Clone, Copy)]
+pub struct SourceStatus {
+    pub label: &'static str,
+    pub connected: bool,
+    pub reconnects: u32,
+    pub last_price: f64,
+    /// `f64::INFINITY` if the source has never produced a price.
+    pub seconds_since_update: f64,
+}
+
+/// A live price feed pluggable into `ExchangeFeed`: each source owns its own
+/// connect/reconnect loop and pushes updates into the given channel forever.
+/// Named distinctly from `crate::price_source::PriceSource` — that one is a
+/// pull-based `latest_price()` used for the on-chain/RTDS consensus path,
+/// this one is push-based and keyed to a `watch::Sender<Option<Slot>>`, so a
+/// `FixedRateSource` mock can satisfy it without opening any connection at
+/// all (useful for backtests and the `farm` preset's dry-run mode).
+#[async_trait]
+pub trait WsPriceSource: Send + Sync {
+    /// Short name for logs and the aggregated-price exclusion messages.
+    fn label(&self) -> &'static str;
+
+    /// Runs the feed's connect/reconnect loop forever, pushing updates into
+    /// `tx` and connection state into `health`. Never returns under normal
+    /// operation.
+    async fn run(&self, tx: watch::Sender<Option<Slot>>, health: ConnHealth);
+}
+
+pub struct BinanceWs {
+    url: String,
+}
+
+impl BinanceWs {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
+    }
+}
+
+#[async_trait]
+impl WsPriceSource for BinanceWs {
+    fn label(&self) -> &'static str {
+        Exchange::Binance.label()
+    }
+
+    async fn run(&self, tx: watch::Sender<Option<Slot>>, health: ConnHealth) {
+        ws_loop(Exchange::Binance, self.url.clone(), tx, health).await
+    }
+}
+
+pub struct CoinbaseWs {
+    url: String,
+}
+
+impl CoinbaseWs {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
+    }
+}
+
+#[async_trait]
+impl WsPriceSource for CoinbaseWs {
+    fn label(&self) -> &'static str {
+        Exchange::Coinbase.label()
+    }
+
+    async fn run(&self, tx: watch::Sender<Option<Slot>>, health: ConnHealth) {
+        ws_loop(Exchange::Coinbase, self.url.clone(), tx, health).await
+    }
+}
+
+pub struct KrakenWs {
+    url: String,
+}
+
+impl KrakenWs {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
+    }
+}
+
+#[async_trait]
+impl WsPriceSource for KrakenWs {
+    fn label(&self) -> &'static str {
+        Exchange::Kraken.label()
+    }
+
+    async fn run(&self, tx: watch::Sender<Option<Slot>>, health: ConnHealth) {
+        ws_loop(Exchange::Kraken, self.url.clone(), tx, health).await
+    }
+}
+
+/// Deterministic scripted source for backtests and dry-runs: replays a fixed
+/// price, or a scripted `(price, timestamp_ms)` sequence, on a timer instead
+/// of opening a connection. A `timestamp_ms` of `0` in a scripted tick means
+/// "stamp with the current time", so a constant-price source never goes
+/// stale.
+pub struct FixedRateSource {
+    label: &'static str,
+    ticks: Vec<(f64, u64)>,
+    interval: Duration,
+}
+
+impl FixedRateSource {
+    /// A single price, re-pushed every `interval`.
+    pub fn constant(label: &'static str, price: f64, interval: Duration) -> Self {
+        Self { label, ticks: vec![(price, 0)], interval }
+    }
+
+    /// A scripted sequence of `(price, timestamp_ms)` pairs replayed in
+    /// order on `interval`, looping back to the start once exhausted.
+    pub fn scripted(label: &'static str, ticks: Vec<(f64, u64)>, interval: Duration) -> Self {
+        assert!(!ticks.is_empty(), "FixedRateSource needs at least one tick");
+        Self { label, ticks, interval }
+    }
+}
+
+#[async_trait]
+impl WsPriceSource for FixedRateSource {
+    fn label(&self) -> &'static str {
+        self.label
+    }
+
+    async fn run(&self, tx: watch::Sender<Option<Slot>>, health: ConnHealth) {
+        // A mock never disconnects, so it reports connected once and never flaps.
+        health.set_connected(true);
+        let mut interval = tokio::time::interval(self.interval);
+        let mut i = 0usize;
+        loop {
+            interval.tick().await;
+            let (price, ts) = self.ticks[i % self.ticks.len()];
+            let updated_ms = if ts == 0 { now_ms() } else { ts };
+            let _ = tx.send(Some(Slot { price, updated_ms }));
+            i += 1;
+        }
+    }
+}
+
 pub struct ExchangeFeed {
-    rx: [watch::Receiver<Option<Slot>>; 3],
+    rx: Vec<(&'static str, watch::Receiver<Option<Slot>>)>,
+    health: Vec<(&'static str, ConnHealth)>,
+    /// Sources with no update in this long are dropped before aggregation.
+    staleness_ms: u64,
+    /// A source whose price deviates from the cleaned-set median by more
+    /// than this fraction (e.g. 0.01 = 1%) is rejected as an outlier. 0.0
+    /// disables the divergence filter.
+    max_divergence_pct: f64,
 }
 
 impl ExchangeFeed {
-    /// Démarre les 3 connexions WS en background. Non-bloquant.
-    pub async fn start(binance: &str, coinbase: &str, kraken: &str) -> Self {
-        let (tx0, rx0) = watch::channel(None);
-        let (tx1, rx1) = watch::channel(None);
-        let (tx2, rx2) = watch::channel(None);
-        tokio::spawn(ws_loop(Exchange::Binance, binance.to_string(), tx0));
-        tokio::spawn(ws_loop(Exchange::Coinbase, coinbase.to_string(), tx1));
-        tokio::spawn(ws_loop(Exchange::Kraken, kraken.to_string(), tx2));
-        Self { rx: [rx0, rx1, rx2] }
-    }
-
-    /// Dernier prix agrégé (médiane des sources fraîches, non-bloquant).
-    pub fn latest(&self) -> AggregatedPrice {
+    /// Starts a background connection per supplied source. Non-blocking.
+    /// The number of sources is no longer pinned to 3: `latest()` aggregates
+    /// over `sources.len()` instead of a fixed array.
+    pub async fn start(sources: Vec<Box<dyn WsPriceSource>>, staleness_ms: u64, max_divergence_pct: f64) -> Self {
+        let mut rx = Vec::with_capacity(sources.len());
+        let mut health = Vec::with_capacity(sources.len());
+        for source in sources {
+            let (tx, source_rx) = watch::channel(None);
+            let label = source.label();
+            let source_health = ConnHealth::default();
+            let task_health = source_health.clone();
+            tokio::spawn(async move { source.run(tx, task_health).await });
+            rx.push((label, source_rx));
+            health.push((label, source_health));
+        }
+        Self { rx, health, staleness_ms, max_divergence_pct }
+    }
+
+    /// Per-source health for the `/metrics` exporter: connection state,
+    /// cumulative reconnects, and the freshness/value of the last price —
+    /// the same inputs `latest()` already uses to accept or reject a source,
+    /// surfaced instead of thrown away so operators can see *why*
+    /// `num_sources` dropped below `min_ws_sources`.
+    pub fn source_status(&self) -> Vec<SourceStatus> {
+        let now = now_ms();
+        self.rx.iter().zip(&self.health)
+            .map(|((label, rx), (_, health))| {
+                let slot = *rx.borrow();
+                SourceStatus {
+                    label,
+                    connected: health.connected(),
+                    reconnects: health.reconnects(),
+                    last_price: slot.map(|s| s.price).unwrap_or(0.0),
+                    seconds_since_update: slot
+                        .map(|s| now.saturating_sub(s.updated_ms) as f64 / 1000.0)
+                        .unwrap_or(f64::INFINITY),
+                }
+            })
+            .collect()
+    }
+
+    /// Latest aggregated price (median of the fresh, non-divergent sources;
+    /// non-blocking). Rejected sources (stale, or outside `max_divergence_pct`
+    /// of the median) are logged and excluded from the resulting
+    /// `num_sources`, so the `min_ws_sources` gate sees the real count of
+    /// trustworthy sources.
+    ///
+    /// `spread_pct` shrinks the median toward zero (e.g. 0.001 = 0.1%
+    /// conservative shading) and `skew_pct` then shifts it up or down by a
+    /// fixed fraction — see `StrategyConfig::feed_spread_pct`/`feed_skew_pct`.
+    /// Both default to 0.0, which reproduces the naked median.
+    pub fn latest(&self, spread_pct: f64, skew_pct: f64) -> AggregatedPrice {
         let now = now_ms();
-        let mut prices = Vec::with_capacity(3);
+        let mut fresh: Vec<(&'static str, f64)> = Vec::with_capacity(self.rx.len());
         let mut last = 0u64;
-        for rx in &self.rx {
+        for (label, rx) in &self.rx {
             if let Some(slot) = *rx.borrow() {
-                if now.saturating_sub(slot.updated_ms) < STALE_MS {
-                    prices.push(slot.price);
+                if now.saturating_sub(slot.updated_ms) < self.staleness_ms {
+                    fresh.push((label, slot.price));
                     last = last.max(slot.updated_ms);
+                } else {
+                    tracing::debug!("[{}] rejected: stale ({}ms old)", label, now.saturating_sub(slot.updated_ms));
                 }
             }
         }
-        if prices.is_empty() {
+        if fresh.is_empty() {
             return AggregatedPrice::default();
         }
-        prices.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
-        let median = match prices.len() {
-            1 => prices[0],
-            2 => (prices[0] + prices[1]) / 2.0,
-            _ => prices[1],
+
+        let cleaned = if self.max_divergence_pct > 0.0 && fresh.len() > 1 {
+            let pivot = median(fresh.iter().map(|(_, p)| *p).collect());
+            let survivors: Vec<_> = fresh.iter()
+                .filter(|(label, price)| {
+                    let divergence = (price - pivot).abs() / pivot;
+                    let ok = divergence <= self.max_divergence_pct;
+                    if !ok {
+                        tracing::warn!("[{}] rejected: {:.2} diverges {:.2}% from median {:.2}", label, price, divergence * 100.0, pivot);
+                    }
+                    ok
+                })
+                .cloned()
+                .collect();
+            if survivors.is_empty() { fresh } else { survivors }
+        } else {
+            fresh
         };
-        AggregatedPrice { median_price: median, num_sources: prices.len() as u8, last_update_ms: last }
+
+        let median_price = median(cleaned.iter().map(|(_, p)| *p).collect());
+        let median_price = median_price * (1.0 - spread_pct) * (1.0 + skew_pct);
+        AggregatedPrice { median_price, num_sources: cleaned.len() as u8, last_update_ms: last }
+    }
+
+    /// Raw `(price, updated_ms)` per source, with no staleness/divergence
+    /// filtering — unlike `latest()`, which aggregates into one consensus
+    /// price, this exposes every source independently for callers that want
+    /// to track each one on its own (e.g. `candles::CandleAggregator`
+    /// rolling per-exchange OHLC bars).
+    pub fn latest_per_source(&self) -> Vec<(&'static str, Option<(f64, u64)>)> {
+        self.rx.iter()
+            .map(|(label, rx)| (*label, rx.borrow().map(|slot| (slot.price, slot.updated_ms))))
+            .collect()
+    }
+}
+
+fn median(mut prices: Vec<f64>) -> f64 {
+    prices.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
+    match prices.len() {
+        0 => 0.0,
+        1 => prices[0],
+        n if n % 2 == 0 => (prices[n / 2 - 1] + prices[n / 2]) / 2.0,
+        n => prices[n / 2],
     }
 }
 
@@ -67,6 +324,154 @@ fn now_ms() -> u64 {
     SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64
 }
 
+/// Standalone Binance source (outside the 3-exchange aggregation), for
+/// callers that want to pick one specific source rather than
+/// `ExchangeFeed`'s median.
+pub struct BinanceSource {
+    rx: watch::Receiver<Option<Slot>>,
+}
+
+impl BinanceSource {
+    /// Starts the Binance WS connection in the background. Non-blocking.
+    pub async fn start(url: &str) -> Self {
+        let (tx, rx) = watch::channel(None);
+        tokio::spawn(ws_loop(Exchange::Binance, url.to_string(), tx, ConnHealth::default()));
+        Self { rx }
+    }
+
+    /// Latest Binance price if fresh (<5s), with its timestamp, else None.
+    pub fn latest(&self) -> Option<(f64, u64)> {
+        let slot = (*self.rx.borrow())?;
+        let now = now_ms();
+        if now.saturating_sub(slot.updated_ms) < STALE_MS {
+            Some((slot.price, slot.updated_ms))
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct KrakenV1Slot {
+    price: f64,
+    updated_ms: u64,
+}
+
+/// Second, exchange-native Kraken ticker feed (Kraken's original v1 wire API)
+/// used as an independent cross-check on settlement, separate from the v2
+/// feed aggregated inside `ExchangeFeed`.
+pub struct KrakenFeed {
+    rx: watch::Receiver<Option<KrakenV1Slot>>,
+}
+
+impl KrakenFeed {
+    /// Starts the Kraken v1 WS connection in the background. Non-blocking.
+    pub async fn start(url: &str) -> Self {
+        let (tx, rx) = watch::channel(None);
+        tokio::spawn(kraken_v1_ws_loop(url.to_string(), tx));
+        Self { rx }
+    }
+
+    /// Latest Kraken v1 price if fresh (<5s), else None.
+    pub fn latest(&self) -> Option<f64> {
+        let slot = (*self.rx.borrow())?;
+        let now = now_ms();
+        if now.saturating_sub(slot.updated_ms) < STALE_MS {
+            Some(slot.price)
+        } else {
+            None
+        }
+    }
+}
+
+/// Automatic reconnection loop for the Kraken v1 feed.
+/// Exponential backoff: 2s → 4s → 8s → … → 30s max. Reset on clean disconnect.
+async fn kraken_v1_ws_loop(url: String, tx: watch::Sender<Option<KrakenV1Slot>>) {
+    let mut backoff_s = 2u64;
+    let mut reconnects = 0u32;
+    loop {
+        let result = run_kraken_v1(&url, &tx).await;
+        let _ = tx.send(None); // Clear on disconnect
+
+        match result {
+            Ok(()) => {
+                tracing::info!("[KrakenV1] WS disconnected cleanly");
+                backoff_s = 2;
+            }
+            Err(e) => {
+                reconnects += 1;
+                tracing::warn!("[KrakenV1] WS error (reconnect #{}): {e:#}", reconnects);
+                backoff_s = (backoff_s * 2).min(30);
+            }
+        }
+        tokio::time::sleep(Duration::from_secs(backoff_s)).await;
+    }
+}
+
+#[derive(Deserialize)]
+struct KrakenV1TickerData {
+    a: Vec<serde_json::Value>,
+    b: Vec<serde_json::Value>,
+}
+
+/// Parses a ticker message in the v1 array format:
+/// `[channelID, {"a":[ask,...],"b":[bid,...],"c":[last,volume]}, "ticker", "XBT/USD"]`.
+/// Returns the mid of `a[0]`/`b[0]` (decimals encoded as strings).
+fn parse_kraken_v1_ticker(value: &serde_json::Value) -> Option<f64> {
+    let arr = value.as_array()?;
+    let ticker_obj = arr.get(1)?;
+    let data: KrakenV1TickerData = serde_json::from_value(ticker_obj.clone()).ok()?;
+    let ask: f64 = data.a.first()?.as_str()?.parse().ok()?;
+    let bid: f64 = data.b.first()?.as_str()?.parse().ok()?;
+    Some((ask + bid) / 2.0)
+}
+
+async fn run_kraken_v1(url: &str, tx: &watch::Sender<Option<KrakenV1Slot>>) -> Result<()> {
+    let (mut ws, _) = connect_async(url).await.context("connect")?;
+    tracing::info!("[KrakenV1] WS connected");
+    let sub = serde_json::json!({
+        "event": "subscribe",
+        "pair": ["XBT/USD"],
+        "subscription": { "name": "ticker" }
+    });
+    ws.send(Message::Text(sub.to_string().into())).await?;
+    let mut ping_interval = tokio::time::interval(Duration::from_secs(30));
+    ping_interval.tick().await; // skip immediate first tick
+
+    loop {
+        tokio::select! {
+            msg = ws.next() => {
+                match msg {
+                    Some(Ok(Message::Text(ref text))) => {
+                        if let Ok(value) = serde_json::from_str::<serde_json::Value>(text) {
+                            if value.is_array() {
+                                if let Some(mid) = parse_kraken_v1_ticker(&value) {
+                                    let _ = tx.send(Some(KrakenV1Slot { price: mid, updated_ms: now_ms() }));
+                                }
+                            } else if let Some(event) = value.get("event").and_then(|e| e.as_str()) {
+                                // heartbeat is a keep-alive only: no reconnect, no log spam.
+                                if event == "subscriptionStatus" || event == "systemStatus" {
+                                    let status = value.get("status").and_then(|s| s.as_str()).unwrap_or("unknown");
+                                    tracing::info!("[KrakenV1] {event}: {status}");
+                                }
+                            }
+                        }
+                    }
+                    Some(Ok(Message::Ping(data))) => {
+                        let _ = ws.send(Message::Pong(data)).await;
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => return Err(e.into()),
+                    None => return Ok(()),
+                }
+            }
+            _ = ping_interval.tick() => {
+                ws.send(Message::Ping(vec![].into())).await.context("ping failed")?;
+            }
+        }
+    }
+}
+
 #[derive(Clone, Copy)]
 enum Exchange { Binance, Coinbase, Kraken }
 
@@ -76,19 +481,19 @@ impl Exchange {
     }
 }
 
-/// Boucle de reconnexion automatique pour chaque exchange.
+/// Automatic reconnection loop for each exchange.
 /// Exponential backoff: 2s → 4s → 8s → … → 30s max. Reset on clean disconnect.
-async fn ws_loop(ex: Exchange, url: String, tx: watch::Sender<Option<Slot>>) {
+async fn ws_loop(ex: Exchange, url: String, tx: watch::Sender<Option<Slot>>, health: ConnHealth) {
     let mut backoff_s = 2u64;
-    let mut reconnects = 0u32;
     loop {
         let result = match ex {
-            Exchange::Binance => run_binance(&url, &tx).await,
-            Exchange::Coinbase => run_coinbase(&url, &tx).await,
-            Exchange::Kraken => run_kraken(&url, &tx).await,
+            Exchange::Binance => run_binance(&url, &tx, &health).await,
+            Exchange::Coinbase => run_coinbase(&url, &tx, &health).await,
+            Exchange::Kraken => run_kraken(&url, &tx, &health).await,
         };
         // Clear slot on disconnect
         let _ = tx.send(None);
+        health.set_connected(false);
 
         match result {
             Ok(()) => {
@@ -97,7 +502,7 @@ async fn ws_loop(ex: Exchange, url: String, tx: watch::Sender<Option<Slot>>) {
                 backoff_s = 2;
             }
             Err(e) => {
-                reconnects += 1;
+                let reconnects = health.note_reconnect();
                 tracing::warn!("[{}] WS error (reconnect #{}): {e:#}", ex.label(), reconnects);
                 backoff_s = (backoff_s * 2).min(30);
             }
@@ -111,9 +516,10 @@ async fn ws_loop(ex: Exchange, url: String, tx: watch::Sender<Option<Slot>>) {
 #[derive(Deserialize)]
 struct BinanceTrade { p: String, #[serde(rename = "T")] ts: u64 }
 
-async fn run_binance(url: &str, tx: &watch::Sender<Option<Slot>>) -> Result<()> {
+async fn run_binance(url: &str, tx: &watch::Sender<Option<Slot>>, health: &ConnHealth) -> Result<()> {
     let (mut ws, _) = connect_async(url).await.context("connect")?;
     tracing::info!("[Binance] WS connected");
+    health.set_connected(true);
     let mut ping_interval = tokio::time::interval(Duration::from_secs(30));
     ping_interval.tick().await; // skip immediate first tick
 
@@ -145,16 +551,21 @@ async fn run_binance(url: &str, tx: &watch::Sender<Option<Slot>>) -> Result<()>
 
 // --- Coinbase: wss://ws-feed.exchange.coinbase.com ---
 
+/// Tagged on the wire's `type` field. `subscriptions` (ack) and `heartbeat`
+/// fall into `Other` — we only need to act on price ticks and errors.
 #[derive(Deserialize)]
-struct CoinbaseTicker {
-    #[serde(rename = "type")]
-    msg_type: String,
-    price: Option<String>,
+#[serde(tag = "type", rename_all = "snake_case")]
+enum CoinbaseMsg {
+    Ticker { price: Option<String> },
+    Error { message: String, #[serde(default)] reason: Option<String> },
+    #[serde(other)]
+    Other,
 }
 
-async fn run_coinbase(url: &str, tx: &watch::Sender<Option<Slot>>) -> Result<()> {
+async fn run_coinbase(url: &str, tx: &watch::Sender<Option<Slot>>, health: &ConnHealth) -> Result<()> {
     let (mut ws, _) = connect_async(url).await.context("connect")?;
     tracing::info!("[Coinbase] WS connected");
+    health.set_connected(true);
     let sub = serde_json::json!({
         "type": "subscribe",
         "channels": ["ticker"],
@@ -169,14 +580,19 @@ async fn run_coinbase(url: &str, tx: &watch::Sender<Option<Slot>>) -> Result<()>
             msg = ws.next() => {
                 match msg {
                     Some(Ok(Message::Text(ref text))) => {
-                        if let Ok(t) = serde_json::from_str::<CoinbaseTicker>(text) {
-                            if t.msg_type == "ticker" {
-                                if let Some(ref ps) = t.price {
-                                    if let Ok(p) = ps.parse::<f64>() {
-                                        let _ = tx.send(Some(Slot { price: p, updated_ms: now_ms() }));
-                                    }
+                        match serde_json::from_str::<CoinbaseMsg>(text) {
+                            Ok(CoinbaseMsg::Ticker { price: Some(ps) }) => {
+                                if let Ok(p) = ps.parse::<f64>() {
+                                    let _ = tx.send(Some(Slot { price: p, updated_ms: now_ms() }));
                                 }
                             }
+                            Ok(CoinbaseMsg::Error { message, reason }) => {
+                                anyhow::bail!(
+                                    "coinbase subscription error: {message}{}",
+                                    reason.map(|r| format!(" ({r})")).unwrap_or_default()
+                                );
+                            }
+                            _ => {}
                         }
                     }
                     Some(Ok(Message::Ping(data))) => {
@@ -196,15 +612,43 @@ async fn run_coinbase(url: &str, tx: &watch::Sender<Option<Slot>>) -> Result<()>
 
 // --- Kraken v2: wss://ws.kraken.com/v2 ---
 
+/// A request-ack frame (has `method`/`success`) and a channel-push frame
+/// (has `channel`/`data`) never share a key, so `#[serde(untagged)]` between
+/// the two is unambiguous; `KrakenChannelMsg` then tags on `channel` itself.
 #[derive(Deserialize)]
-struct KrakenMsg { channel: Option<String>, data: Option<Vec<KrakenTicker>> }
+#[serde(untagged)]
+enum KrakenMsg {
+    Method(KrakenMethodResult),
+    Channel(KrakenChannelMsg),
+}
+
+#[derive(Deserialize)]
+struct KrakenMethodResult {
+    method: String,
+    success: bool,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "channel", rename_all = "snake_case")]
+enum KrakenChannelMsg {
+    Ticker { data: Vec<KrakenTicker> },
+    Status { data: Vec<KrakenStatusData> },
+    #[serde(other)]
+    Other,
+}
 
 #[derive(Deserialize)]
 struct KrakenTicker { last: Option<f64> }
 
-async fn run_kraken(url: &str, tx: &watch::Sender<Option<Slot>>) -> Result<()> {
+#[derive(Deserialize)]
+struct KrakenStatusData { system: Option<String> }
+
+async fn run_kraken(url: &str, tx: &watch::Sender<Option<Slot>>, health: &ConnHealth) -> Result<()> {
     let (mut ws, _) = connect_async(url).await.context("connect")?;
     tracing::info!("[Kraken] WS connected");
+    health.set_connected(true);
     let sub = serde_json::json!({
         "method": "subscribe",
         "params": { "channel": "ticker", "symbol": ["BTC/USD"] }
@@ -218,16 +662,25 @@ async fn run_kraken(url: &str, tx: &watch::Sender<Option<Slot>>) -> Result<()> {
             msg = ws.next() => {
                 match msg {
                     Some(Ok(Message::Text(ref text))) => {
-                        if let Ok(m) = serde_json::from_str::<KrakenMsg>(text) {
-                            if m.channel.as_deref() == Some("ticker") {
-                                if let Some(ref data) = m.data {
-                                    if let Some(t) = data.first() {
-                                        if let Some(p) = t.last {
-                                            let _ = tx.send(Some(Slot { price: p, updated_ms: now_ms() }));
-                                        }
+                        match serde_json::from_str::<KrakenMsg>(text) {
+                            Ok(KrakenMsg::Method(m)) if !m.success => {
+                                anyhow::bail!("kraken {} subscription failed: {}", m.method, m.error.unwrap_or_default());
+                            }
+                            Ok(KrakenMsg::Channel(KrakenChannelMsg::Ticker { data })) => {
+                                if let Some(t) = data.first() {
+                                    if let Some(p) = t.last {
+                                        let _ = tx.send(Some(Slot { price: p, updated_ms: now_ms() }));
                                     }
                                 }
                             }
+                            Ok(KrakenMsg::Channel(KrakenChannelMsg::Status { data })) => {
+                                let online = data.first().is_some_and(|s| s.system.as_deref() == Some("online"));
+                                if !online {
+                                    tracing::warn!("[Kraken] system status offline/in maintenance, clearing slot");
+                                    let _ = tx.send(None);
+                                }
+                            }
+                            _ => {}
                         }
                     }
                     Some(Ok(Message::Ping(data))) => {
@@ -250,14 +703,26 @@ mod tests {
     use super::*;
 
     fn make_feed(slots: [Option<Slot>; 3]) -> ExchangeFeed {
-        let (tx0, rx0) = watch::channel(slots[0]);
-        let (tx1, rx1) = watch::channel(slots[1]);
-        let (tx2, rx2) = watch::channel(slots[2]);
-        // Keep senders alive for the duration of the test
-        std::mem::forget(tx0);
-        std::mem::forget(tx1);
-        std::mem::forget(tx2);
-        ExchangeFeed { rx: [rx0, rx1, rx2] }
+        make_feed_with(slots, STALE_MS, 0.0)
+    }
+
+    fn make_feed_with(slots: [Option<Slot>; 3], staleness_ms: u64, max_divergence_pct: f64) -> ExchangeFeed {
+        let labels = [Exchange::Binance.label(), Exchange::Coinbase.label(), Exchange::Kraken.label()];
+        let rx = labels
+            .into_iter()
+            .zip(slots)
+            .map(|(label, slot)| {
+                let (tx, rx) = watch::channel(slot);
+                // Keep the sender alive for the duration of the test
+                std::mem::forget(tx);
+                (label, rx)
+            })
+            .collect();
+        let health = [Exchange::Binance.label(), Exchange::Coinbase.label(), Exchange::Kraken.label()]
+            .into_iter()
+            .map(|label| (label, ConnHealth::default()))
+            .collect();
+        ExchangeFeed { rx, health, staleness_ms, max_divergence_pct }
     }
 
     #[test]
@@ -268,7 +733,7 @@ mod tests {
             Some(Slot { price: 97200.0, updated_ms: now }),
             Some(Slot { price: 97150.0, updated_ms: now }),
         ]);
-        let agg = feed.latest();
+        let agg = feed.latest(0.0, 0.0);
         assert_eq!(agg.num_sources, 3);
         assert!((agg.median_price - 97150.0).abs() < 0.01);
     }
@@ -281,7 +746,7 @@ mod tests {
             Some(Slot { price: 97200.0, updated_ms: now }),
             None,
         ]);
-        let agg = feed.latest();
+        let agg = feed.latest(0.0, 0.0);
         assert_eq!(agg.num_sources, 2);
         assert!((agg.median_price - 97150.0).abs() < 0.01);
     }
@@ -294,7 +759,7 @@ mod tests {
             None,
             None,
         ]);
-        let agg = feed.latest();
+        let agg = feed.latest(0.0, 0.0);
         assert_eq!(agg.num_sources, 1);
         assert!((agg.median_price - 97100.0).abs() < 0.01);
     }
@@ -307,18 +772,179 @@ mod tests {
             Some(Slot { price: 97200.0, updated_ms: now.saturating_sub(10_000) }),
             None,
         ]);
-        let agg = feed.latest();
+        let agg = feed.latest(0.0, 0.0);
+        assert_eq!(agg.num_sources, 1);
+    }
+
+    #[test]
+    fn custom_staleness_ms_excludes_slightly_old_sources() {
+        let now = now_ms();
+        let feed = make_feed_with([
+            Some(Slot { price: 97100.0, updated_ms: now }),
+            Some(Slot { price: 97200.0, updated_ms: now.saturating_sub(2_000) }),
+            None,
+        ], 1_000, 0.0);
+        let agg = feed.latest(0.0, 0.0);
         assert_eq!(agg.num_sources, 1);
     }
 
+    #[test]
+    fn divergent_source_is_rejected() {
+        let now = now_ms();
+        // Two sources agree near 97100-97150; one prints a wild 99000 (~2%
+        // off the median) and should be dropped by the 1% divergence filter.
+        let feed = make_feed_with([
+            Some(Slot { price: 97100.0, updated_ms: now }),
+            Some(Slot { price: 97150.0, updated_ms: now }),
+            Some(Slot { price: 99000.0, updated_ms: now }),
+        ], STALE_MS, 0.01);
+        let agg = feed.latest(0.0, 0.0);
+        assert_eq!(agg.num_sources, 2);
+        assert!((agg.median_price - 97125.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn divergence_filter_disabled_at_zero() {
+        let now = now_ms();
+        let feed = make_feed_with([
+            Some(Slot { price: 97100.0, updated_ms: now }),
+            Some(Slot { price: 97150.0, updated_ms: now }),
+            Some(Slot { price: 99000.0, updated_ms: now }),
+        ], STALE_MS, 0.0);
+        let agg = feed.latest(0.0, 0.0);
+        assert_eq!(agg.num_sources, 3);
+    }
+
     #[test]
     fn no_sources_returns_default() {
         let feed = make_feed([None, None, None]);
-        let agg = feed.latest();
+        let agg = feed.latest(0.0, 0.0);
         assert_eq!(agg.num_sources, 0);
         assert_eq!(agg.median_price, 0.0);
     }
 
+    #[tokio::test]
+    async fn exchange_feed_aggregates_an_arbitrary_number_of_sources() {
+        let sources: Vec<Box<dyn WsPriceSource>> = vec![
+            Box::new(FixedRateSource::constant("a", 97100.0, Duration::from_millis(5))),
+            Box::new(FixedRateSource::constant("b", 97200.0, Duration::from_millis(5))),
+            Box::new(FixedRateSource::constant("c", 97150.0, Duration::from_millis(5))),
+            Box::new(FixedRateSource::constant("d", 97300.0, Duration::from_millis(5))),
+        ];
+        let feed = ExchangeFeed::start(sources, STALE_MS, 0.0).await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let agg = feed.latest(0.0, 0.0);
+        assert_eq!(agg.num_sources, 4);
+        assert!((agg.median_price - 97175.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn spread_shades_the_median_toward_zero() {
+        let now = now_ms();
+        let feed = make_feed([
+            Some(Slot { price: 100_000.0, updated_ms: now }),
+            Some(Slot { price: 100_000.0, updated_ms: now }),
+            Some(Slot { price: 100_000.0, updated_ms: now }),
+        ]);
+        let agg = feed.latest(0.01, 0.0);
+        assert!((agg.median_price - 99_000.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn skew_shifts_the_median_up_or_down() {
+        let now = now_ms();
+        let feed = make_feed([
+            Some(Slot { price: 100_000.0, updated_ms: now }),
+            Some(Slot { price: 100_000.0, updated_ms: now }),
+            Some(Slot { price: 100_000.0, updated_ms: now }),
+        ]);
+        let up = feed.latest(0.0, 0.01);
+        assert!((up.median_price - 101_000.0).abs() < 0.01);
+
+        let feed = make_feed([
+            Some(Slot { price: 100_000.0, updated_ms: now }),
+            Some(Slot { price: 100_000.0, updated_ms: now }),
+            Some(Slot { price: 100_000.0, updated_ms: now }),
+        ]);
+        let down = feed.latest(0.0, -0.01);
+        assert!((down.median_price - 99_000.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn zero_spread_and_skew_reproduce_the_naked_median() {
+        let now = now_ms();
+        let feed = make_feed([
+            Some(Slot { price: 97100.0, updated_ms: now }),
+            Some(Slot { price: 97200.0, updated_ms: now }),
+            Some(Slot { price: 97150.0, updated_ms: now }),
+        ]);
+        let agg = feed.latest(0.0, 0.0);
+        assert_eq!(agg.median_price, 97150.0);
+    }
+
+    #[tokio::test]
+    async fn fixed_rate_source_replays_a_scripted_sequence() {
+        let source = FixedRateSource::scripted("mock", vec![(100.0, 1), (200.0, 2)], Duration::from_millis(5));
+        let (tx, rx) = watch::channel(None);
+        let health = ConnHealth::default();
+        tokio::spawn(async move { source.run(tx, health).await });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let slot = rx.borrow().unwrap();
+        assert!(slot.price == 100.0 || slot.price == 200.0);
+    }
+
+    #[tokio::test]
+    async fn fixed_rate_source_reports_connected() {
+        let source = FixedRateSource::constant("mock", 100.0, Duration::from_millis(5));
+        let (tx, _rx) = watch::channel(None);
+        let health = ConnHealth::default();
+        let health_check = health.clone();
+        tokio::spawn(async move { source.run(tx, health).await });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(health_check.connected());
+        assert_eq!(health_check.reconnects(), 0);
+    }
+
+    #[test]
+    fn source_status_reflects_health_and_last_slot() {
+        let now = now_ms();
+        let feed = make_feed([
+            Some(Slot { price: 97100.0, updated_ms: now }),
+            None,
+            None,
+        ]);
+        feed.health[0].1.set_connected(true);
+        let statuses = feed.source_status();
+        assert_eq!(statuses[0].label, Exchange::Binance.label());
+        assert!(statuses[0].connected);
+        assert_eq!(statuses[0].reconnects, 0);
+        assert!((statuses[0].last_price - 97100.0).abs() < 0.01);
+        assert!(statuses[0].seconds_since_update < 1.0);
+
+        assert!(!statuses[1].connected);
+        assert_eq!(statuses[1].last_price, 0.0);
+        assert!(statuses[1].seconds_since_update.is_infinite());
+    }
+
+    #[test]
+    fn binance_source_returns_fresh_price() {
+        let now = now_ms();
+        let (tx, rx) = watch::channel(Some(Slot { price: 97150.0, updated_ms: now }));
+        std::mem::forget(tx);
+        let source = BinanceSource { rx };
+        let (price, updated_ms) = source.latest().unwrap();
+        assert!((price - 97150.0).abs() < 0.01);
+        assert_eq!(updated_ms, now);
+    }
+
+    #[test]
+    fn binance_source_returns_none_when_stale() {
+        let (tx, rx) = watch::channel(Some(Slot { price: 97150.0, updated_ms: now_ms().saturating_sub(10_000) }));
+        std::mem::forget(tx);
+        let source = BinanceSource { rx };
+        assert!(source.latest().is_none());
+    }
+
     #[test]
     fn parse_binance_trade() {
         let json = r#"{"e":"trade","E":1234567890123,"s":"BTCUSDT","t":12345,"p":"97150.50","q":"0.001","b":88,"a":50,"T":1234567890123,"m":true,"M":true}"#;
@@ -330,16 +956,112 @@ mod tests {
     #[test]
     fn parse_coinbase_ticker() {
         let json = r#"{"type":"ticker","sequence":123,"product_id":"BTC-USD","price":"97150.50","open_24h":"96000","volume_24h":"1234","time":"2026-02-18T12:00:00.000000Z"}"#;
-        let t: CoinbaseTicker = serde_json::from_str(json).unwrap();
-        assert_eq!(t.msg_type, "ticker");
-        assert_eq!(t.price.unwrap(), "97150.50");
+        match serde_json::from_str::<CoinbaseMsg>(json).unwrap() {
+            CoinbaseMsg::Ticker { price } => assert_eq!(price.unwrap(), "97150.50"),
+            _ => panic!("expected a Ticker variant"),
+        }
+    }
+
+    #[test]
+    fn parse_coinbase_subscriptions_ack_as_other() {
+        let json = r#"{"type":"subscriptions","channels":[{"name":"ticker","product_ids":["BTC-USD"]}]}"#;
+        assert!(matches!(serde_json::from_str::<CoinbaseMsg>(json).unwrap(), CoinbaseMsg::Other));
+    }
+
+    #[test]
+    fn parse_coinbase_error() {
+        let json = r#"{"type":"error","message":"invalid product_id","reason":"BTC-USD"}"#;
+        match serde_json::from_str::<CoinbaseMsg>(json).unwrap() {
+            CoinbaseMsg::Error { message, reason } => {
+                assert_eq!(message, "invalid product_id");
+                assert_eq!(reason.unwrap(), "BTC-USD");
+            }
+            _ => panic!("expected an Error variant"),
+        }
     }
 
     #[test]
     fn parse_kraken_ticker() {
         let json = r#"{"channel":"ticker","type":"update","data":[{"symbol":"BTC/USD","bid":97100.0,"ask":97200.0,"last":97150.0,"volume":1234.5}]}"#;
-        let m: KrakenMsg = serde_json::from_str(json).unwrap();
-        assert_eq!(m.channel.unwrap(), "ticker");
-        assert_eq!(m.data.unwrap()[0].last.unwrap(), 97150.0);
+        match serde_json::from_str::<KrakenMsg>(json).unwrap() {
+            KrakenMsg::Channel(KrakenChannelMsg::Ticker { data }) => assert_eq!(data[0].last.unwrap(), 97150.0),
+            _ => panic!("expected a Ticker channel message"),
+        }
+    }
+
+    #[test]
+    fn parse_kraken_subscribe_success() {
+        let json = r#"{"method":"subscribe","result":{"channel":"ticker","symbol":"BTC/USD"},"success":true,"time_in":"t","time_out":"t"}"#;
+        match serde_json::from_str::<KrakenMsg>(json).unwrap() {
+            KrakenMsg::Method(m) => assert!(m.success),
+            _ => panic!("expected a Method result"),
+        }
+    }
+
+    #[test]
+    fn parse_kraken_subscribe_failure() {
+        let json = r#"{"method":"subscribe","success":false,"error":"Currency pair not supported","time_in":"t"}"#;
+        match serde_json::from_str::<KrakenMsg>(json).unwrap() {
+            KrakenMsg::Method(m) => {
+                assert!(!m.success);
+                assert_eq!(m.error.unwrap(), "Currency pair not supported");
+            }
+            _ => panic!("expected a Method result"),
+        }
+    }
+
+    #[test]
+    fn parse_kraken_system_status_offline() {
+        let json = r#"{"channel":"status","type":"update","data":[{"api_version":"v2","connection_id":1,"system":"maintenance","version":"2.0.0"}]}"#;
+        match serde_json::from_str::<KrakenMsg>(json).unwrap() {
+            KrakenMsg::Channel(KrakenChannelMsg::Status { data }) => assert_eq!(data[0].system.as_deref(), Some("maintenance")),
+            _ => panic!("expected a Status channel message"),
+        }
+    }
+
+    #[test]
+    fn parse_kraken_heartbeat_as_other() {
+        let json = r#"{"channel":"heartbeat"}"#;
+        assert!(matches!(serde_json::from_str::<KrakenMsg>(json).unwrap(), KrakenMsg::Channel(KrakenChannelMsg::Other)));
+    }
+
+    #[test]
+    fn parse_kraken_v1_array_ticker() {
+        let json = r#"[340,{"a":["97200.10000","1","1.000"],"b":["97100.50000","2","2.000"],"c":["97150.00000","0.001"]},"ticker","XBT/USD"]"#;
+        let value: serde_json::Value = serde_json::from_str(json).unwrap();
+        let mid = parse_kraken_v1_ticker(&value).unwrap();
+        assert!((mid - 97150.30).abs() < 0.01);
+    }
+
+    #[test]
+    fn parse_kraken_v1_ignores_event_frames() {
+        let json = r#"{"event":"heartbeat"}"#;
+        let value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert!(parse_kraken_v1_ticker(&value).is_none());
+
+        let json = r#"{"event":"subscriptionStatus","status":"subscribed","pair":"XBT/USD"}"#;
+        let value: serde_json::Value = serde_json::from_str(json).unwrap();
+        assert!(parse_kraken_v1_ticker(&value).is_none());
+    }
+
+    #[test]
+    fn kraken_feed_returns_fresh_price() {
+        let (tx, rx) = watch::channel(Some(KrakenV1Slot { price: 97150.0, updated_ms: now_ms() }));
+        std::mem::forget(tx);
+        let feed = KrakenFeed { rx };
+        let price = feed.latest();
+        assert!(price.is_some());
+        assert!((price.unwrap() - 97150.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn kraken_feed_returns_none_when_stale() {
+        let (tx, rx) = watch::channel(Some(KrakenV1Slot {
+            price: 97150.0,
+            updated_ms: now_ms().saturating_sub(10_000),
+        }));
+        std::mem::forget(tx);
+        let feed = KrakenFeed { rx };
+        assert!(feed.latest().is_none());
     }
 }
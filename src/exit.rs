@@ -0,0 +1,665 @@
+use std::collections::VecDeque;
+
+use crate::polymarket::Side;
+use crate::strategy::{Position, CalibrationMode, StrategyConfig, TradeContext, WindowTicks};
+
+/// Open BTC position on the current 5min window — the entry price is the
+/// reference BTC price at trade time (`start_price`), not the Polymarket
+/// share price, since early-exit levels are judged against the underlying.
+#[derive(Debug, Clone, Copy)]
+pub struct OpenPosition {
+    pub side: Side,
+    pub entry_price: f64,
+    pub entry_ts_ms: u64,
+}
+
+/// Early-exit signal, emitted before the window's settlement.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExitSignal {
+    StopLoss,
+    TakeProfit,
+    /// Forced liquidation (circuit breaker, manual stop, ...), independent
+    /// of the ROI/trailing-stop levels below.
+    Forced,
+}
+
+/// Tracks a rolling average of the realized profit factor over the last
+/// `window` windows, to vary the take-profit multiplier: widen it after
+/// recent wins, tighten it after recent losses.
+#[derive(Debug)]
+pub struct AdaptiveTp {
+    recent_profit_factors: VecDeque<f64>,
+    window: usize,
+}
+
+impl AdaptiveTp {
+    pub fn new(window: usize) -> Self {
+        Self { recent_profit_factors: VecDeque::with_capacity(window), window }
+    }
+
+    /// Records the realized profit factor for the last closed window.
+    /// `f64::INFINITY` (no loss yet) is capped so it doesn't skew the
+    /// average.
+    pub fn record(&mut self, profit_factor: f64) {
+        self.recent_profit_factors.push_back(profit_factor.min(5.0));
+        if self.recent_profit_factors.len() > self.window {
+            self.recent_profit_factors.pop_front();
+        }
+    }
+
+    /// `base_tp` scaled by the rolling average profit factor, clamped to
+    /// [0.5x, 2x] so an isolated hot/cold streak can't push the take-profit
+    /// outside a reasonable range.
+    pub fn tp_multiplier(&self, base_tp: f64) -> f64 {
+        if self.recent_profit_factors.is_empty() {
+            return base_tp;
+        }
+        let avg = self.recent_profit_factors.iter().sum::<f64>() / self.recent_profit_factors.len() as f64;
+        base_tp * avg.clamp(0.5, 2.0)
+    }
+}
+
+/// Evaluates whether an open position should be closed early. The stop
+/// trails the peak (UP) / trough (DOWN) reached since entry, at `k` ATR
+/// away; the take-profit is set at `tp` ATR from entry. Returns `None`
+/// until a level is crossed, or if the ATR isn't available yet (not enough
+/// elapsed intervals).
+pub fn evaluate_exit(
+    position: &OpenPosition,
+    ticks: &WindowTicks,
+    atr: f64,
+    k: f64,
+    tp: f64,
+) -> Option<ExitSignal> {
+    if atr <= 0.0 {
+        return None;
+    }
+    let current = ticks.last_price()?;
+
+    match position.side {
+        Side::Buy => {
+            let peak = ticks.peak_since(position.entry_ts_ms).unwrap_or(position.entry_price).max(position.entry_price);
+            let stop = peak - k * atr;
+            let take_profit = position.entry_price + tp * atr;
+            if current <= stop {
+                Some(ExitSignal::StopLoss)
+            } else if current >= take_profit {
+                Some(ExitSignal::TakeProfit)
+            } else {
+                None
+            }
+        }
+        Side::Sell => {
+            let trough = ticks.trough_since(position.entry_ts_ms).unwrap_or(position.entry_price).min(position.entry_price);
+            let stop = trough + k * atr;
+            let take_profit = position.entry_price - tp * atr;
+            if current >= stop {
+                Some(ExitSignal::StopLoss)
+            } else if current <= take_profit {
+                Some(ExitSignal::TakeProfit)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// Looks up `roi_table` (pairs of `(seconds_remaining_threshold, min_roi)`)
+/// for the threshold closest to window end that is still `>=
+/// seconds_remaining`, and returns its minimum ROI — so less profit is
+/// required to exit as the window approaches its end. `None` if
+/// `seconds_remaining` is past every threshold in the table, or if the
+/// table is empty (ROI exit disabled).
+fn min_roi_for(roi_table: &[(u64, f64)], seconds_remaining: u64) -> Option<f64> {
+    roi_table
+        .iter()
+        .filter(|&&(threshold, _)| threshold >= seconds_remaining)
+        .min_by_key(|&&(threshold, _)| threshold)
+        .map(|&(_, min_roi)| min_roi)
+}
+
+/// Evaluates a position's exit on the mark price (Polymarket share), not
+/// the BTC underlying — complementary to `evaluate_exit`, which stays the
+/// ATR evaluation on `start_price`. Priority: `force_exit` (circuit
+/// breaker / manual liquidation) first, then the trailing stop on the
+/// high-water mark, then the time-remaining ROI table.
+pub fn evaluate_position_exit(
+    side: Side,
+    entry_price: f64,
+    mark_price: f64,
+    high_water_mark: f64,
+    seconds_remaining: u64,
+    roi_table: &[(u64, f64)],
+    trailing_stop_pct: f64,
+    force_exit: bool,
+) -> Option<ExitSignal> {
+    if force_exit {
+        return Some(ExitSignal::Forced);
+    }
+    if entry_price <= 0.0 {
+        return None;
+    }
+    let roi = match side {
+        Side::Buy => (mark_price - entry_price) / entry_price,
+        Side::Sell => (entry_price - mark_price) / entry_price,
+    };
+
+    if trailing_stop_pct > 0.0 {
+        let gain = match side {
+            Side::Buy => high_water_mark - entry_price,
+            Side::Sell => entry_price - high_water_mark,
+        };
+        if gain > 0.0 {
+            let retrace = match side {
+                Side::Buy => high_water_mark - mark_price,
+                Side::Sell => mark_price - high_water_mark,
+            };
+            if retrace >= trailing_stop_pct * gain {
+                return Some(ExitSignal::TakeProfit);
+            }
+        }
+    }
+
+    if let Some(min_roi) = min_roi_for(roi_table, seconds_remaining) {
+        if roi >= min_roi {
+            return Some(ExitSignal::TakeProfit);
+        }
+    }
+
+    None
+}
+
+/// Protective/early exit driven by the `WindowTicks` microstructure signals
+/// already folded into `TradeContext`, complementary to the BTC-underlying
+/// `evaluate_exit` and the ROI-table `evaluate_position_exit`: exits when
+/// the move has stopped being directional (`momentum_ratio` collapse),
+/// volatility has spiked relative to the window's realized vol (the same
+/// "spike" threshold `evaluate()`'s regime_factor sizing already uses),
+/// intra-window drawdown since entry has blown past a hard stop, or a
+/// trailing take-profit on the held token's own price retraces past its
+/// ratchet-up high-water mark (see `Position::update_high_water`).
+pub fn evaluate_microstructure_exit(
+    position: &Position,
+    ctx: &TradeContext,
+    config: &StrategyConfig,
+) -> Option<ExitSignal> {
+    if config.min_momentum_exit > 0.0 && ctx.momentum_ratio < config.min_momentum_exit {
+        return Some(ExitSignal::StopLoss);
+    }
+
+    if ctx.vol_5min_pct > 0.0 && ctx.micro_vol > ctx.vol_5min_pct * 2.0 {
+        return Some(ExitSignal::StopLoss);
+    }
+
+    if config.hard_stop_bps > 0.0 && ctx.max_drawdown_bps >= config.hard_stop_bps {
+        return Some(ExitSignal::StopLoss);
+    }
+
+    if config.trailing_stop_bps > 0.0 {
+        let current_price = match position.side {
+            Side::Buy => ctx.market_up_price,
+            Side::Sell => 1.0 - ctx.market_up_price,
+        };
+        let gain_bps = (position.high_water_price() - position.avg_entry_price) * 10_000.0;
+        if gain_bps > 0.0 {
+            let retrace_bps = (position.high_water_price() - current_price) * 10_000.0;
+            if retrace_bps >= config.trailing_stop_bps {
+                return Some(ExitSignal::TakeProfit);
+            }
+        }
+    }
+
+    None
+}
+
+/// Tracks a staged, ratchet-up trailing stop on the *held token's own sell
+/// price* (not the BTC underlying) — complementary to `evaluate_exit` (ATR
+/// on the underlying) and `evaluate_position_exit`/`evaluate_microstructure_exit`
+/// (single-rate trailing stop on mark price). `stages` is an ascending list
+/// of `(activation_ratio, callback_rate)` pairs: once the favorable move
+/// since entry crosses a stage's activation ratio, the stop arms at that
+/// stage's callback rate. Crossing a later (further) stage re-arms at its
+/// own rate, so the armed rate always reflects the furthest stage reached.
+/// Once armed, a retrace from the peak past the armed callback rate signals
+/// `ExitSignal::TakeProfit`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StagedTrailingStop {
+    pub peak_favorable: f64,
+    pub armed_callback_rate: Option<f64>,
+}
+
+impl StagedTrailingStop {
+    /// Feed the latest sell price reachable for the held token. `stages`
+    /// must be sorted by ascending `activation_ratio`.
+    pub fn update(&mut self, side: Side, entry_price: f64, current_sell_price: f64, stages: &[(f64, f64)]) -> Option<ExitSignal> {
+        if entry_price <= 0.0 || stages.is_empty() {
+            return None;
+        }
+        if self.peak_favorable == 0.0 {
+            self.peak_favorable = entry_price;
+        }
+        self.peak_favorable = match side {
+            Side::Buy => current_sell_price.max(self.peak_favorable),
+            Side::Sell => current_sell_price.min(self.peak_favorable),
+        };
+
+        let favorable_ratio = match side {
+            Side::Buy => (self.peak_favorable - entry_price) / entry_price,
+            Side::Sell => (entry_price - self.peak_favorable) / entry_price,
+        };
+        for &(activation_ratio, callback_rate) in stages {
+            if favorable_ratio >= activation_ratio {
+                self.armed_callback_rate = Some(callback_rate);
+            }
+        }
+
+        let armed_rate = self.armed_callback_rate?;
+        let retrace_ratio = match side {
+            Side::Buy => (self.peak_favorable - current_sell_price) / self.peak_favorable,
+            Side::Sell => (current_sell_price - self.peak_favorable) / self.peak_favorable,
+        };
+        if retrace_ratio >= armed_rate {
+            Some(ExitSignal::TakeProfit)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> StrategyConfig {
+        StrategyConfig {
+            max_bet_usdc: 5.0,
+            min_bet_usdc: 1.0,
+            min_shares: 5,
+            min_edge_pct: 0.0,
+            entry_seconds_before_end: 10,
+            session_profit_target_usdc: 0.0,
+            session_loss_limit_usdc: 0.0,
+            fee_rate: 0.0,
+            min_market_price: 0.0,
+            max_market_price: 1.0,
+            min_delta_pct: 0.0,
+            max_spread: 1.0,
+            kelly_fraction: 0.10,
+            initial_bankroll_usdc: 40.0,
+            always_trade: false,
+            vol_confidence_multiplier: 1.0,
+            min_payout_ratio: 0.0,
+            min_book_imbalance: 0.0,
+            max_vol_5min_pct: 0.0,
+            min_ws_sources: 0,
+            circuit_breaker_window: 0,
+            circuit_breaker_min_wr: 0.0,
+            circuit_breaker_cooldown_s: 0,
+            min_implied_prob: 0.0,
+            max_consecutive_losses: 0,
+            student_t_df: 0.0,
+            min_z_score: 0.0,
+            max_model_divergence: 0.0,
+            quote_spread_pct: 0.0,
+            atr_window: 10,
+            exit_stop_atr_mult: 0.0,
+            exit_tp_atr_mult: 0.0,
+            exit_tp_window: 10,
+            fisher_window: 20,
+            fisher_extreme_threshold: 0.0,
+            min_vol_edge: 0.0,
+            roi_table: Vec::new(),
+            trailing_stop_pct: 0.0,
+            trailing_stop_bps: 0.0,
+            hard_stop_bps: 0.0,
+            min_momentum_exit: 0.0,
+            calibration_breakpoints: Vec::new(),
+            calibration_mode: CalibrationMode::Multiplier,
+            safety_spread_pct: 0.0,
+            trailing_stages: Vec::new(),
+            daily_fee_budget: 0.0,
+            daily_max_volume: 0.0,
+            feed_spread_pct: 0.0,
+            feed_skew_pct: 0.0,
+            symmetric_fee_model: false,
+            symmetric_fee_base_rate: 0.0,
+            consensus_max_deviation_pct: 0.0,
+        }
+    }
+
+    fn test_ctx() -> TradeContext {
+        TradeContext {
+            start_price: 100_000.0,
+            chainlink_price: 100_000.0,
+            exchange_price: None,
+            rtds_price: None,
+            market_up_price: 0.5,
+            seconds_remaining: 120,
+            fee_rate: 0.0,
+            vol_5min_pct: 0.10,
+            spread: 0.0,
+            book_imbalance: 0.0,
+            num_ws_sources: 1,
+            micro_vol: 0.0,
+            momentum_ratio: 1.0,
+            fisher: 0.0,
+            fisher_prev: 0.0,
+            max_drawdown_bps: 0.0,
+        }
+    }
+
+    #[test]
+    fn microstructure_exit_holds_with_no_thresholds_configured() {
+        let pos = Position::new(Side::Buy, 10.0, 0.50, 0.0);
+        let config = test_config();
+        assert_eq!(evaluate_microstructure_exit(&pos, &test_ctx(), &config), None);
+    }
+
+    #[test]
+    fn microstructure_exit_holds_a_directional_move() {
+        // Mirrors window_ticks_momentum_ratio_directional: high momentum stays under the floor.
+        let pos = Position::new(Side::Buy, 10.0, 0.50, 0.0);
+        let config = StrategyConfig { min_momentum_exit: 0.5, ..test_config() };
+        let ctx = TradeContext { momentum_ratio: 0.9, ..test_ctx() };
+        assert_eq!(evaluate_microstructure_exit(&pos, &ctx, &config), None);
+    }
+
+    #[test]
+    fn microstructure_exit_triggers_on_choppy_momentum_collapse() {
+        // Mirrors window_ticks_momentum_ratio_choppy: low momentum breaches the floor.
+        let pos = Position::new(Side::Buy, 10.0, 0.50, 0.0);
+        let config = StrategyConfig { min_momentum_exit: 0.5, ..test_config() };
+        let ctx = TradeContext { momentum_ratio: 0.3, ..test_ctx() };
+        assert_eq!(evaluate_microstructure_exit(&pos, &ctx, &config), Some(ExitSignal::StopLoss));
+    }
+
+    #[test]
+    fn microstructure_exit_triggers_on_micro_vol_spike() {
+        let pos = Position::new(Side::Buy, 10.0, 0.50, 0.0);
+        let config = test_config();
+        let ctx = TradeContext { vol_5min_pct: 0.10, micro_vol: 0.25, ..test_ctx() };
+        assert_eq!(evaluate_microstructure_exit(&pos, &ctx, &config), Some(ExitSignal::StopLoss));
+    }
+
+    #[test]
+    fn microstructure_exit_holds_below_the_vol_spike_threshold() {
+        let pos = Position::new(Side::Buy, 10.0, 0.50, 0.0);
+        let config = test_config();
+        let ctx = TradeContext { vol_5min_pct: 0.10, micro_vol: 0.15, ..test_ctx() };
+        assert_eq!(evaluate_microstructure_exit(&pos, &ctx, &config), None);
+    }
+
+    #[test]
+    fn microstructure_exit_triggers_on_hard_stop_drawdown() {
+        let pos = Position::new(Side::Buy, 10.0, 0.50, 0.0);
+        let config = StrategyConfig { hard_stop_bps: 200.0, ..test_config() };
+        let ctx = TradeContext { max_drawdown_bps: 250.0, ..test_ctx() };
+        assert_eq!(evaluate_microstructure_exit(&pos, &ctx, &config), Some(ExitSignal::StopLoss));
+    }
+
+    #[test]
+    fn microstructure_exit_holds_below_the_hard_stop() {
+        let pos = Position::new(Side::Buy, 10.0, 0.50, 0.0);
+        let config = StrategyConfig { hard_stop_bps: 200.0, ..test_config() };
+        let ctx = TradeContext { max_drawdown_bps: 100.0, ..test_ctx() };
+        assert_eq!(evaluate_microstructure_exit(&pos, &ctx, &config), None);
+    }
+
+    #[test]
+    fn microstructure_exit_trailing_take_profit_triggers_on_retrace() {
+        let mut pos = Position::new(Side::Buy, 10.0, 0.50, 0.0);
+        pos.update_high_water(0.65);
+        let config = StrategyConfig { trailing_stop_bps: 100.0, ..test_config() };
+        // Retraced from the 0.65 high-water mark to 0.64 -> 100bps, at the threshold.
+        let ctx = TradeContext { market_up_price: 0.64, ..test_ctx() };
+        assert_eq!(evaluate_microstructure_exit(&pos, &ctx, &config), Some(ExitSignal::TakeProfit));
+    }
+
+    #[test]
+    fn microstructure_exit_trailing_take_profit_holds_within_the_retrace_band() {
+        let mut pos = Position::new(Side::Buy, 10.0, 0.50, 0.0);
+        pos.update_high_water(0.65);
+        let config = StrategyConfig { trailing_stop_bps: 100.0, ..test_config() };
+        let ctx = TradeContext { market_up_price: 0.645, ..test_ctx() };
+        assert_eq!(evaluate_microstructure_exit(&pos, &ctx, &config), None);
+    }
+
+    #[test]
+    fn microstructure_exit_trailing_take_profit_for_a_short() {
+        let mut pos = Position::new(Side::Sell, 10.0, 0.50, 0.0);
+        // Short's own token price is 1 - market_up_price; high-water ratchets on that.
+        pos.update_high_water(0.60);
+        let config = StrategyConfig { trailing_stop_bps: 100.0, ..test_config() };
+        // market_up_price 0.49 -> held-token price 0.51, a 900bps retrace from 0.60.
+        let ctx = TradeContext { market_up_price: 0.49, ..test_ctx() };
+        assert_eq!(evaluate_microstructure_exit(&pos, &ctx, &config), Some(ExitSignal::TakeProfit));
+    }
+
+    fn ticks_from(prices: &[(f64, u64)]) -> WindowTicks {
+        let mut wt = WindowTicks::new();
+        for &(p, ts) in prices {
+            wt.tick(p, ts);
+        }
+        wt
+    }
+
+    #[test]
+    fn no_atr_means_no_signal() {
+        let pos = OpenPosition { side: Side::Buy, entry_price: 100.0, entry_ts_ms: 0 };
+        let ticks = ticks_from(&[(100.0, 0), (90.0, 100)]);
+        assert_eq!(evaluate_exit(&pos, &ticks, 0.0, 1.5, 2.5), None);
+    }
+
+    #[test]
+    fn long_stop_loss_triggers_below_trailing_peak() {
+        let pos = OpenPosition { side: Side::Buy, entry_price: 100.0, entry_ts_ms: 0 };
+        // Peak of 102 then drops 2 ATR below it (ATR=1.0, k=1.5 -> stop at 100.5)
+        let ticks = ticks_from(&[(100.0, 0), (102.0, 100), (100.0, 200)]);
+        assert_eq!(evaluate_exit(&pos, &ticks, 1.0, 1.5, 2.5), Some(ExitSignal::StopLoss));
+    }
+
+    #[test]
+    fn long_take_profit_triggers_above_entry_plus_tp_atr() {
+        let pos = OpenPosition { side: Side::Buy, entry_price: 100.0, entry_ts_ms: 0 };
+        // tp=2.5 ATR of 1.0 -> take-profit at 102.5
+        let ticks = ticks_from(&[(100.0, 0), (103.0, 100)]);
+        assert_eq!(evaluate_exit(&pos, &ticks, 1.0, 1.5, 2.5), Some(ExitSignal::TakeProfit));
+    }
+
+    #[test]
+    fn long_no_signal_inside_band() {
+        let pos = OpenPosition { side: Side::Buy, entry_price: 100.0, entry_ts_ms: 0 };
+        let ticks = ticks_from(&[(100.0, 0), (100.5, 100)]);
+        assert_eq!(evaluate_exit(&pos, &ticks, 1.0, 1.5, 2.5), None);
+    }
+
+    #[test]
+    fn short_stop_loss_triggers_above_trailing_trough() {
+        let pos = OpenPosition { side: Side::Sell, entry_price: 100.0, entry_ts_ms: 0 };
+        let ticks = ticks_from(&[(100.0, 0), (98.0, 100), (100.0, 200)]);
+        assert_eq!(evaluate_exit(&pos, &ticks, 1.0, 1.5, 2.5), Some(ExitSignal::StopLoss));
+    }
+
+    #[test]
+    fn short_take_profit_triggers_below_entry_minus_tp_atr() {
+        let pos = OpenPosition { side: Side::Sell, entry_price: 100.0, entry_ts_ms: 0 };
+        let ticks = ticks_from(&[(100.0, 0), (97.0, 100)]);
+        assert_eq!(evaluate_exit(&pos, &ticks, 1.0, 1.5, 2.5), Some(ExitSignal::TakeProfit));
+    }
+
+    #[test]
+    fn peak_ignores_ticks_before_entry() {
+        let pos = OpenPosition { side: Side::Buy, entry_price: 100.0, entry_ts_ms: 200 };
+        // Spike to 110 happens before entry, shouldn't set the trailing stop.
+        let ticks = ticks_from(&[(110.0, 0), (100.0, 200), (100.4, 300)]);
+        assert_eq!(evaluate_exit(&pos, &ticks, 1.0, 1.5, 2.5), None);
+    }
+
+    #[test]
+    fn adaptive_tp_widens_after_wins_and_shrinks_after_losses() {
+        let mut tp = AdaptiveTp::new(5);
+        tp.record(2.0);
+        tp.record(2.0);
+        assert!(tp.tp_multiplier(2.5) > 2.5);
+
+        let mut tp = AdaptiveTp::new(5);
+        tp.record(0.3);
+        tp.record(0.3);
+        assert!(tp.tp_multiplier(2.5) < 2.5);
+    }
+
+    #[test]
+    fn adaptive_tp_defaults_to_base_with_no_history() {
+        let tp = AdaptiveTp::new(5);
+        assert_eq!(tp.tp_multiplier(2.5), 2.5);
+    }
+
+    #[test]
+    fn adaptive_tp_clamps_extreme_profit_factor() {
+        let mut tp = AdaptiveTp::new(5);
+        tp.record(f64::INFINITY);
+        // Clamped to 5.0 internally then bounded to 2x by tp_multiplier.
+        assert!((tp.tp_multiplier(2.5) - 5.0).abs() < 1e-9);
+    }
+
+    // --- min_roi_for / evaluate_position_exit ---
+
+    #[test]
+    fn min_roi_for_picks_the_tightest_threshold_still_reachable() {
+        let table = [(300, 0.15), (120, 0.08), (30, 0.03)];
+        assert_eq!(min_roi_for(&table, 200), Some(0.15));
+        assert_eq!(min_roi_for(&table, 50), Some(0.08));
+        assert_eq!(min_roi_for(&table, 10), Some(0.03));
+    }
+
+    #[test]
+    fn min_roi_for_is_none_beyond_the_furthest_threshold() {
+        let table = [(300, 0.15), (120, 0.08), (30, 0.03)];
+        assert_eq!(min_roi_for(&table, 400), None);
+    }
+
+    #[test]
+    fn min_roi_for_is_none_with_an_empty_table() {
+        assert_eq!(min_roi_for(&[], 100), None);
+    }
+
+    #[test]
+    fn force_exit_overrides_everything() {
+        let signal = evaluate_position_exit(Side::Buy, 0.50, 0.10, 0.50, 999, &[], 0.0, true);
+        assert_eq!(signal, Some(ExitSignal::Forced));
+    }
+
+    #[test]
+    fn long_hold_with_no_roi_table_or_trailing_stop() {
+        let signal = evaluate_position_exit(Side::Buy, 0.50, 0.60, 0.60, 100, &[], 0.0, false);
+        assert_eq!(signal, None);
+    }
+
+    #[test]
+    fn long_roi_table_triggers_exit_once_min_roi_reached() {
+        let table = [(120, 0.10)];
+        // Entry 0.50 -> mark 0.56 is +12% ROI, above the 10% requirement.
+        let signal = evaluate_position_exit(Side::Buy, 0.50, 0.56, 0.56, 100, &table, 0.0, false);
+        assert_eq!(signal, Some(ExitSignal::TakeProfit));
+    }
+
+    #[test]
+    fn long_roi_table_holds_below_min_roi() {
+        let table = [(120, 0.10)];
+        let signal = evaluate_position_exit(Side::Buy, 0.50, 0.52, 0.52, 100, &table, 0.0, false);
+        assert_eq!(signal, None);
+    }
+
+    #[test]
+    fn long_trailing_stop_triggers_on_retrace_from_high_water() {
+        // Gained 0.20 over entry, then gave back 0.07 of it (35%, >= 30% retrace).
+        let signal = evaluate_position_exit(Side::Buy, 0.50, 0.63, 0.70, 100, &[], 0.30, false);
+        assert_eq!(signal, Some(ExitSignal::TakeProfit));
+    }
+
+    #[test]
+    fn long_trailing_stop_holds_within_the_retrace_band() {
+        let signal = evaluate_position_exit(Side::Buy, 0.50, 0.68, 0.70, 100, &[], 0.30, false);
+        assert_eq!(signal, None);
+    }
+
+    #[test]
+    fn short_roi_table_triggers_exit_once_min_roi_reached() {
+        let table = [(120, 0.10)];
+        // Entry 0.50 -> mark 0.44 is +12% ROI for a short.
+        let signal = evaluate_position_exit(Side::Sell, 0.50, 0.44, 0.44, 100, &table, 0.0, false);
+        assert_eq!(signal, Some(ExitSignal::TakeProfit));
+    }
+
+    #[test]
+    fn short_trailing_stop_triggers_on_retrace_from_low_water() {
+        // Low-water mark at 0.36 (gain of 0.14), then retraces 0.05 (>= 30%).
+        let signal = evaluate_position_exit(Side::Sell, 0.50, 0.41, 0.36, 100, &[], 0.30, false);
+        assert_eq!(signal, Some(ExitSignal::TakeProfit));
+    }
+
+    #[test]
+    fn trailing_stop_disabled_does_not_block_roi_table() {
+        let table = [(120, 0.10)];
+        let signal = evaluate_position_exit(Side::Buy, 0.50, 0.56, 0.56, 100, &table, 0.0, false);
+        assert_eq!(signal, Some(ExitSignal::TakeProfit));
+    }
+
+    // --- StagedTrailingStop ---
+
+    #[test]
+    fn staged_trailing_stop_no_signal_before_any_stage_activates() {
+        let mut trail = StagedTrailingStop::default();
+        let stages = [(0.01, 0.3), (0.02, 0.2)];
+        assert_eq!(trail.update(Side::Buy, 0.50, 0.502, &stages), None);
+        assert_eq!(trail.armed_callback_rate, None);
+    }
+
+    #[test]
+    fn staged_trailing_stop_arms_first_stage_and_exits_on_retrace() {
+        let mut trail = StagedTrailingStop::default();
+        let stages = [(0.01, 0.3), (0.02, 0.2)];
+        // Favorable move crosses only the first activation ratio (1.4% < 2%), arming a 30% callback.
+        assert_eq!(trail.update(Side::Buy, 0.50, 0.507, &stages), None);
+        assert_eq!(trail.armed_callback_rate, Some(0.3));
+        // Retraces from the 0.507 peak by more than 30% of it.
+        assert_eq!(trail.update(Side::Buy, 0.50, 0.35, &stages), Some(ExitSignal::TakeProfit));
+    }
+
+    #[test]
+    fn staged_trailing_stop_holds_within_the_armed_callback_band() {
+        let mut trail = StagedTrailingStop::default();
+        let stages = [(0.01, 0.3), (0.02, 0.2)];
+        assert_eq!(trail.update(Side::Buy, 0.50, 0.507, &stages), None);
+        // Small retrace, well inside the 30% callback band.
+        assert_eq!(trail.update(Side::Buy, 0.50, 0.505, &stages), None);
+    }
+
+    #[test]
+    fn staged_trailing_stop_rearms_tighter_on_a_further_stage() {
+        let mut trail = StagedTrailingStop::default();
+        let stages = [(0.01, 0.3), (0.02, 0.2)];
+        assert_eq!(trail.update(Side::Buy, 0.50, 0.507, &stages), None);
+        assert_eq!(trail.armed_callback_rate, Some(0.3));
+        // A 3% move crosses the second activation ratio (2%) — rearms at the tighter 20% rate.
+        assert_eq!(trail.update(Side::Buy, 0.50, 0.515, &stages), None);
+        assert_eq!(trail.armed_callback_rate, Some(0.2));
+    }
+
+    #[test]
+    fn staged_trailing_stop_for_a_short_tracks_the_falling_peak() {
+        let mut trail = StagedTrailingStop::default();
+        let stages = [(0.01, 0.3)];
+        assert_eq!(trail.update(Side::Sell, 0.50, 0.49, &stages), None);
+        assert_eq!(trail.armed_callback_rate, Some(0.3));
+        // Retraces back up from the 0.49 trough by more than 30% of it.
+        assert_eq!(trail.update(Side::Sell, 0.50, 0.64, &stages), Some(ExitSignal::TakeProfit));
+    }
+
+    #[test]
+    fn staged_trailing_stop_no_stages_never_signals() {
+        let mut trail = StagedTrailingStop::default();
+        assert_eq!(trail.update(Side::Buy, 0.50, 0.60, &[]), None);
+        assert_eq!(trail.update(Side::Buy, 0.50, 0.30, &[]), None);
+    }
+}
@@ -0,0 +1,586 @@
+//! Signed fixed-point arithmetic for the core decision path (fee, Kelly
+//! sizing, z-score). Replaying a session should produce bit-identical
+//! signals to the live run, which `f64` does not guarantee across
+//! platforms/optimization levels — and `f64` leaves latent NaN/Inf hazards
+//! that today get papered over with `partial_cmp(...).unwrap_or(Equal)`
+//! sorts. `Fp` has a total order and checked/saturating ops instead.
+//!
+//! Layout is Q32.32 (32 integer bits, 32 fractional bits) backed by `i64`,
+//! not the 64.64-over-i128 layout some fixed-point crates use: with Q32.32
+//! the multiply/divide intermediate fits in `i128` without a custom
+//! wide-multiply routine, while still giving ±2^31 range and 2^-32
+//! precision — comfortably more than the prices/probabilities/fees this
+//! pipeline ever carries.
+
+use std::cmp::Ordering;
+use std::ops::{Add, Mul, Neg, Sub};
+
+const FRAC_BITS: u32 = 32;
+const SCALE: i64 = 1i64 << FRAC_BITS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fp(i64);
+
+impl Fp {
+    pub const ZERO: Fp = Fp(0);
+    pub const ONE: Fp = Fp(SCALE);
+    pub const MAX: Fp = Fp(i64::MAX);
+    pub const MIN: Fp = Fp(i64::MIN);
+
+    /// Converts from `f64` at the config-parsing boundary. NaN maps to
+    /// zero; out-of-range values saturate instead of wrapping.
+    pub fn from_f64(v: f64) -> Fp {
+        if v.is_nan() {
+            return Fp::ZERO;
+        }
+        let scaled = v * SCALE as f64;
+        if scaled >= i64::MAX as f64 {
+            Fp::MAX
+        } else if scaled <= i64::MIN as f64 {
+            Fp::MIN
+        } else {
+            Fp(scaled as i64)
+        }
+    }
+
+    /// Converts back to `f64`, only at the logging/reporting boundary.
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / SCALE as f64
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    pub fn abs(self) -> Fp {
+        if self.0 == i64::MIN { Fp::MAX } else { Fp(self.0.abs()) }
+    }
+
+    pub fn checked_add(self, rhs: Fp) -> Option<Fp> {
+        self.0.checked_add(rhs.0).map(Fp)
+    }
+
+    pub fn checked_sub(self, rhs: Fp) -> Option<Fp> {
+        self.0.checked_sub(rhs.0).map(Fp)
+    }
+
+    /// Widens to `i128` for the intermediate product so Q32.32 values never
+    /// overflow the multiply itself, then narrows back to `i64`.
+    pub fn checked_mul(self, rhs: Fp) -> Option<Fp> {
+        let product = ((self.0 as i128) * (rhs.0 as i128)) >> FRAC_BITS;
+        i64::try_from(product).ok().map(Fp)
+    }
+
+    /// Returns `None` on division by zero instead of producing NaN/Inf.
+    pub fn checked_div(self, rhs: Fp) -> Option<Fp> {
+        if rhs.0 == 0 {
+            return None;
+        }
+        let numerator = (self.0 as i128) << FRAC_BITS;
+        i64::try_from(numerator / (rhs.0 as i128)).ok().map(Fp)
+    }
+
+    pub fn saturating_add(self, rhs: Fp) -> Fp {
+        self.checked_add(rhs).unwrap_or(if rhs.0 >= 0 { Fp::MAX } else { Fp::MIN })
+    }
+
+    pub fn saturating_sub(self, rhs: Fp) -> Fp {
+        self.checked_sub(rhs).unwrap_or(if rhs.0 >= 0 { Fp::MIN } else { Fp::MAX })
+    }
+
+    /// Total order over `Fp`, useful as a drop-in for the
+    /// `partial_cmp(...).unwrap_or(Ordering::Equal)` sort hack `f64` needs.
+    pub fn cmp_total(&self, other: &Fp) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+
+    /// e^x via argument reduction (repeated halving) + a Taylor series on
+    /// the reduced argument, then squaring back up. Only exercised with
+    /// `x <= 0` in this crate (the normal-CDF approximation's exponent
+    /// `-x²/2` is never positive), so the result always lands in `(0, 1]`
+    /// and can't overflow; a deeply negative `x` saturates to zero rather
+    /// than producing subnormal garbage.
+    pub fn exp(self) -> Fp {
+        if self.to_f64() < -40.0 {
+            return Fp::ZERO;
+        }
+        let two = Fp::from_f64(2.0);
+        let mut k: u32 = 0;
+        let mut reduced = self;
+        while reduced.abs() > Fp::ONE && k < 32 {
+            reduced = reduced.checked_div(two).unwrap_or(Fp::ZERO);
+            k += 1;
+        }
+        let mut term = Fp::ONE;
+        let mut sum = Fp::ONE;
+        for n in 1..=12 {
+            term = term.checked_mul(reduced).unwrap_or(Fp::ZERO)
+                .checked_div(Fp::from_f64(n as f64)).unwrap_or(Fp::ZERO);
+            sum = sum.saturating_add(term);
+        }
+        let mut result = sum;
+        for _ in 0..k {
+            result = result * result;
+        }
+        result
+    }
+}
+
+impl Add for Fp {
+    type Output = Fp;
+    fn add(self, rhs: Fp) -> Fp {
+        self.saturating_add(rhs)
+    }
+}
+
+impl Sub for Fp {
+    type Output = Fp;
+    fn sub(self, rhs: Fp) -> Fp {
+        self.saturating_sub(rhs)
+    }
+}
+
+impl Mul for Fp {
+    type Output = Fp;
+    fn mul(self, rhs: Fp) -> Fp {
+        self.checked_mul(rhs).unwrap_or(if (self.0 >= 0) == (rhs.0 >= 0) { Fp::MAX } else { Fp::MIN })
+    }
+}
+
+impl Neg for Fp {
+    type Output = Fp;
+    fn neg(self) -> Fp {
+        Fp::ZERO.saturating_sub(self)
+    }
+}
+
+const MONEY_SCALE: i128 = 1_000_000; // 6 decimal places, matching USDC's on-chain precision
+
+/// Decimal fixed-point type for settlement math (PnL, fees), scaled to 6
+/// decimal places. `Fp`'s Q32.32 binary layout is the right shape
+/// for probabilities/z-scores/Kelly fractions, but binary fractions can't
+/// represent "$0.01" exactly — and a resolution ledger needs repeated runs
+/// over the same inputs to settle to the exact same number, not just a
+/// number within tolerance. `Money` trades Fp's bit-shift scaling for
+/// decimal-exact rounding: `checked_mul`/`checked_div` round the true
+/// (unrounded) product/quotient to the nearest `1 / MONEY_SCALE` using
+/// round-half-up, the conventional rounding for money rather than Fp's
+/// implicit truncation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Money(i128);
+
+impl Money {
+    pub const ZERO: Money = Money(0);
+    pub const ONE: Money = Money(MONEY_SCALE);
+    pub const MAX: Money = Money(i128::MAX);
+    pub const MIN: Money = Money(i128::MIN);
+
+    /// Converts from `f64` at the config/signal boundary, rounding to the
+    /// nearest `1 / MONEY_SCALE`. NaN maps to zero; out-of-range values
+    /// saturate instead of wrapping.
+    pub fn from_f64(v: f64) -> Money {
+        if v.is_nan() {
+            return Money::ZERO;
+        }
+        let scaled = (v * MONEY_SCALE as f64).round();
+        if scaled >= i128::MAX as f64 {
+            Money::MAX
+        } else if scaled <= i128::MIN as f64 {
+            Money::MIN
+        } else {
+            Money(scaled as i128)
+        }
+    }
+
+    /// Converts back to `f64`, only at the logging/reporting boundary.
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / MONEY_SCALE as f64
+    }
+
+    pub fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    pub fn checked_add(self, rhs: Money) -> Option<Money> {
+        self.0.checked_add(rhs.0).map(Money)
+    }
+
+    pub fn checked_sub(self, rhs: Money) -> Option<Money> {
+        self.0.checked_sub(rhs.0).map(Money)
+    }
+
+    /// Multiplies first, then divides by the scale, so the intermediate
+    /// keeps full precision before it's rounded back down to the mantissa.
+    pub fn checked_mul(self, rhs: Money) -> Option<Money> {
+        let product = self.0.checked_mul(rhs.0)?;
+        round_div(product, MONEY_SCALE).map(Money)
+    }
+
+    /// Divides against a scale-widened numerator (the opposite order from
+    /// `checked_mul`) so the division itself doesn't lose precision.
+    /// Returns `None` on division by zero instead of producing NaN/Inf.
+    pub fn checked_div(self, rhs: Money) -> Option<Money> {
+        if rhs.0 == 0 {
+            return None;
+        }
+        let numerator = self.0.checked_mul(MONEY_SCALE)?;
+        round_div(numerator, rhs.0).map(Money)
+    }
+
+    pub fn saturating_add(self, rhs: Money) -> Money {
+        self.checked_add(rhs).unwrap_or(if rhs.0 >= 0 { Money::MAX } else { Money::MIN })
+    }
+
+    pub fn saturating_sub(self, rhs: Money) -> Money {
+        self.checked_sub(rhs).unwrap_or(if rhs.0 >= 0 { Money::MIN } else { Money::MAX })
+    }
+
+    pub fn saturating_mul(self, rhs: Money) -> Money {
+        self.checked_mul(rhs).unwrap_or(if (self.0 >= 0) == (rhs.0 >= 0) { Money::MAX } else { Money::MIN })
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+    fn add(self, rhs: Money) -> Money {
+        self.saturating_add(rhs)
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+    fn sub(self, rhs: Money) -> Money {
+        self.saturating_sub(rhs)
+    }
+}
+
+impl Mul for Money {
+    type Output = Money;
+    fn mul(self, rhs: Money) -> Money {
+        self.saturating_mul(rhs)
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+    fn neg(self) -> Money {
+        Money::ZERO.saturating_sub(self)
+    }
+}
+
+/// Divides `numerator` by `denominator` rounding half-up (ties away from
+/// zero) rather than `i128`'s default truncation-toward-zero — the
+/// conventional rounding rule for money. `unsigned_abs` sidesteps the usual
+/// `i128::MIN.abs()` overflow since the magnitude fits in `u128`.
+fn round_div(numerator: i128, denominator: i128) -> Option<i128> {
+    if denominator == 0 {
+        return None;
+    }
+    let num_abs = numerator.unsigned_abs();
+    let denom_abs = denominator.unsigned_abs();
+    let quotient = num_abs / denom_abs;
+    let remainder = num_abs % denom_abs;
+    let rounded_abs = if remainder * 2 >= denom_abs { quotient + 1 } else { quotient };
+    let rounded = i128::try_from(rounded_abs).ok()?;
+    if (numerator >= 0) == (denominator >= 0) { Some(rounded) } else { rounded.checked_neg() }
+}
+
+const BPS_SCALE: u128 = 10_000;
+
+/// Splits `gross` (an indivisible minor unit — integer shares, or cents)
+/// into the integer fee taken at `fee_bps` and the basis-point remainder
+/// the integer division truncated away. `fee = gross * fee_bps / 10_000`
+/// and `remainder = gross * fee_bps % 10_000`, widened through `u128` so
+/// the intermediate product can't overflow `u64`. `gross - fee` (always
+/// non-negative for `fee_bps <= 10_000`) is the net the other party
+/// receives; `remainder` is what a caller settling many trades should
+/// accumulate and fold in as an extra fee unit once it crosses 10_000,
+/// rather than letting each trade's truncated fraction evaporate as dust.
+pub fn fee_and_remainder(gross: u64, fee_bps: u32) -> (u64, u64) {
+    let product = gross as u128 * fee_bps as u128;
+    let fee = (product / BPS_SCALE) as u64;
+    let remainder = (product % BPS_SCALE) as u64;
+    (fee, remainder)
+}
+
+/// Splits `gross` into the net amount the receiving party is paid and the
+/// fee the paying party owes, both derived from the same
+/// `fee_and_remainder` split so `net + fee == gross` holds exactly —
+/// settling each leg independently risks the two sides disagreeing by a
+/// unit after their own separate rounding.
+pub fn settle_with_fee(gross: u64, fee_bps: u32) -> (u64, u64) {
+    let (fee, _remainder) = fee_and_remainder(gross, fee_bps);
+    (gross - fee, fee)
+}
+
+/// Beyond this |x|, `exp(-x²/2)` has long since underflowed f64/Fp
+/// resolution and the Abramowitz & Stegun polynomial's quoted error bound
+/// (1.5e-7) no longer means anything — the limiting probability is returned
+/// directly rather than computed, so a `pct_change`/`remaining_vol` ratio
+/// that blows up (tiny `seconds_remaining`, near-zero vol) can't silently
+/// round-trip through an overflowing `x*x` (Q32.32 squaring overflows past
+/// |x| ≈ 46340) and come out right only by accident.
+const CDF_SATURATION_X: f64 = 37.0;
+
+/// Deterministic fixed-point counterpart of `strategy::normal_cdf`
+/// (Abramowitz & Stegun approximation, same coefficients). Division isn't
+/// overloaded on `Fp` — a zero divisor is a real error case, not just
+/// overflow — so this uses `checked_div` explicitly and falls back to 0.0
+/// (matching `t`'s well-defined range: the denominator `1 + 0.2316419·|x|`
+/// is never zero for finite `x`, so the fallback is unreachable in practice).
+pub fn normal_cdf(x: Fp) -> Fp {
+    let x_f64 = x.to_f64();
+    if x_f64 >= CDF_SATURATION_X {
+        return Fp::ONE;
+    }
+    if x_f64 <= -CDF_SATURATION_X {
+        return Fp::ZERO;
+    }
+    let one = Fp::ONE;
+    let c1 = Fp::from_f64(0.2316419);
+    let t = one.checked_div(one + c1 * x.abs()).unwrap_or(Fp::ZERO);
+    let d = Fp::from_f64(0.398_942_280_401_432_7);
+    let exp_term = (-(Fp::from_f64(0.5) * x * x)).exp();
+    let poly = t * (Fp::from_f64(0.319381530)
+        + t * (Fp::from_f64(-0.356563782)
+            + t * (Fp::from_f64(1.781477937)
+                + t * (Fp::from_f64(-1.821255978)
+                    + t * Fp::from_f64(1.330274429)))));
+    let p = d * exp_term * poly;
+    if x.0 >= 0 { one - p } else { p }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundtrip_through_f64_is_within_precision() {
+        let v = Fp::from_f64(0.0156);
+        assert!((v.to_f64() - 0.0156).abs() < 1e-9);
+    }
+
+    #[test]
+    fn from_f64_saturates_on_nan_and_infinity() {
+        assert_eq!(Fp::from_f64(f64::NAN), Fp::ZERO);
+        assert_eq!(Fp::from_f64(f64::INFINITY), Fp::MAX);
+        assert_eq!(Fp::from_f64(f64::NEG_INFINITY), Fp::MIN);
+    }
+
+    #[test]
+    fn checked_add_overflows_to_none() {
+        assert_eq!(Fp::MAX.checked_add(Fp::ONE), None);
+    }
+
+    #[test]
+    fn saturating_add_clamps_instead_of_panicking() {
+        assert_eq!(Fp::MAX.saturating_add(Fp::ONE), Fp::MAX);
+        assert_eq!(Fp::MIN.saturating_add(Fp::from_f64(-1.0)), Fp::MIN);
+    }
+
+    #[test]
+    fn checked_mul_matches_float_multiplication() {
+        let a = Fp::from_f64(0.25);
+        let b = Fp::from_f64(0.25);
+        let product = a.checked_mul(b).unwrap();
+        assert!((product.to_f64() - 0.0625).abs() < 1e-9);
+    }
+
+    #[test]
+    fn checked_div_returns_none_for_zero_divisor() {
+        assert_eq!(Fp::ONE.checked_div(Fp::ZERO), None);
+    }
+
+    #[test]
+    fn checked_div_matches_float_division() {
+        let a = Fp::from_f64(1.0);
+        let b = Fp::from_f64(4.0);
+        let quotient = a.checked_div(b).unwrap();
+        assert!((quotient.to_f64() - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ordering_is_total_with_no_nan_case() {
+        let mut values = vec![Fp::from_f64(3.0), Fp::from_f64(-1.5), Fp::from_f64(0.0)];
+        values.sort_unstable();
+        assert_eq!(values, vec![Fp::from_f64(-1.5), Fp::from_f64(0.0), Fp::from_f64(3.0)]);
+    }
+
+    #[test]
+    fn abs_handles_i64_min_without_overflow() {
+        assert_eq!(Fp::MIN.abs(), Fp::MAX);
+    }
+
+    #[test]
+    fn exp_matches_f64_for_negative_arguments() {
+        for x in [0.0, -0.5, -1.0, -2.0, -5.0, -10.0] {
+            let got = Fp::from_f64(x).exp().to_f64();
+            let want = x.exp();
+            assert!((got - want).abs() < 1e-4, "exp({x}): got {got}, want {want}");
+        }
+    }
+
+    #[test]
+    fn exp_saturates_to_zero_for_deeply_negative_arguments() {
+        assert_eq!(Fp::from_f64(-1000.0).exp(), Fp::ZERO);
+    }
+
+    #[test]
+    fn exp_is_deterministic_across_repeated_calls() {
+        let a = Fp::from_f64(-3.25).exp();
+        let b = Fp::from_f64(-3.25).exp();
+        assert_eq!(a, b);
+    }
+
+    /// Reference float implementation of `strategy::normal_cdf`, kept local
+    /// to this test so the fixed-point port can be checked against it
+    /// without creating a dependency from fixedpoint.rs on strategy.rs.
+    fn normal_cdf_f64(x: f64) -> f64 {
+        let t = 1.0 / (1.0 + 0.2316419 * x.abs());
+        let d = 0.3989422804014327;
+        let p = d * (-x * x / 2.0).exp()
+            * (t * (0.319381530
+                + t * (-0.356563782
+                    + t * (1.781477937 + t * (-1.821255978 + t * 1.330274429)))));
+        if x >= 0.0 { 1.0 - p } else { p }
+    }
+
+    #[test]
+    fn normal_cdf_matches_f64_reference_within_tolerance() {
+        for x in [-3.0, -1.5, -0.5, 0.0, 0.5, 1.5, 3.0] {
+            let got = normal_cdf(Fp::from_f64(x)).to_f64();
+            let want = normal_cdf_f64(x);
+            assert!((got - want).abs() < 1e-4, "normal_cdf({x}): got {got}, want {want}");
+        }
+    }
+
+    #[test]
+    fn normal_cdf_is_deterministic_across_repeated_calls() {
+        let a = normal_cdf(Fp::from_f64(1.23));
+        let b = normal_cdf(Fp::from_f64(1.23));
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn normal_cdf_is_monotonically_increasing() {
+        let low = normal_cdf(Fp::from_f64(-1.0)).to_f64();
+        let mid = normal_cdf(Fp::from_f64(0.0)).to_f64();
+        let high = normal_cdf(Fp::from_f64(1.0)).to_f64();
+        assert!(low < mid && mid < high);
+    }
+
+    #[test]
+    fn normal_cdf_saturates_cleanly_for_extreme_z() {
+        assert_eq!(normal_cdf(Fp::from_f64(1e6)), Fp::ONE);
+        assert_eq!(normal_cdf(Fp::from_f64(-1e6)), Fp::ZERO);
+    }
+
+    #[test]
+    fn normal_cdf_stays_monotone_across_the_saturation_threshold() {
+        let xs = [30.0, 36.0, 37.0, 38.0, 100.0, 1e6];
+        let mut prev = normal_cdf(Fp::from_f64(-1e9)).to_f64();
+        for x in xs {
+            let cur = normal_cdf(Fp::from_f64(x)).to_f64();
+            assert!(cur >= prev, "normal_cdf({x}) = {cur} should be >= previous {prev}");
+            prev = cur;
+        }
+    }
+
+    #[test]
+    fn money_roundtrip_through_f64_is_exact_to_the_scale() {
+        let v = Money::from_f64(1.99);
+        assert_eq!(v.to_f64(), 1.99);
+    }
+
+    #[test]
+    fn money_from_f64_saturates_on_nan_and_infinity() {
+        assert_eq!(Money::from_f64(f64::NAN), Money::ZERO);
+        assert_eq!(Money::from_f64(f64::INFINITY), Money::MAX);
+        assert_eq!(Money::from_f64(f64::NEG_INFINITY), Money::MIN);
+    }
+
+    #[test]
+    fn money_checked_add_overflows_to_none() {
+        assert_eq!(Money::MAX.checked_add(Money::ONE), None);
+    }
+
+    #[test]
+    fn money_saturating_add_clamps_instead_of_panicking() {
+        assert_eq!(Money::MAX.saturating_add(Money::ONE), Money::MAX);
+        assert_eq!(Money::MIN.saturating_add(Money::from_f64(-1.0)), Money::MIN);
+    }
+
+    #[test]
+    fn money_checked_mul_is_decimal_exact() {
+        let a = Money::from_f64(0.1);
+        let b = Money::from_f64(0.2);
+        assert_eq!(a.checked_mul(b).unwrap().to_f64(), 0.02);
+    }
+
+    #[test]
+    fn money_checked_div_returns_none_for_zero_divisor() {
+        assert_eq!(Money::ONE.checked_div(Money::ZERO), None);
+    }
+
+    #[test]
+    fn money_checked_div_rounds_half_up() {
+        // 1 / 3 = 0.333333... → rounds to 0.333333 (truncation would also
+        // give this), but 2 / 3 = 0.666666... → rounds up to 0.666667.
+        let one = Money::from_f64(1.0);
+        let two = Money::from_f64(2.0);
+        let three = Money::from_f64(3.0);
+        assert_eq!(one.checked_div(three).unwrap().to_f64(), 0.333333);
+        assert_eq!(two.checked_div(three).unwrap().to_f64(), 0.666667);
+    }
+
+    #[test]
+    fn money_avoids_the_float_binary_rounding_f64_cannot() {
+        // The canonical case f64 gets wrong: 0.1 + 0.2 != 0.3 in binary
+        // floating point, but is exact at Money's decimal scale.
+        let sum = Money::from_f64(0.1) + Money::from_f64(0.2);
+        assert_eq!(sum, Money::from_f64(0.3));
+    }
+
+    #[test]
+    fn money_ordering_is_total() {
+        let mut values = vec![Money::from_f64(3.0), Money::from_f64(-1.5), Money::from_f64(0.0)];
+        values.sort_unstable();
+        assert_eq!(values, vec![Money::from_f64(-1.5), Money::from_f64(0.0), Money::from_f64(3.0)]);
+    }
+
+    #[test]
+    fn fee_and_remainder_splits_bps_exactly() {
+        // 100 * 33 = 3300; 3300 / 10_000 = 0 fee, 3300 left as remainder.
+        assert_eq!(fee_and_remainder(100, 33), (0, 3300));
+        // 10_000 * 33 = 330_000; 330_000 / 10_000 = 33 fee, remainder 0.
+        assert_eq!(fee_and_remainder(10_000, 33), (33, 0));
+    }
+
+    #[test]
+    fn fee_and_remainder_preserves_gross_across_a_sweep() {
+        let grosses = [0u64, 1, 7, 100, 9_999, 10_000, 1_234_567, u64::MAX / 20_000];
+        let fee_bps_values = [0u32, 1, 25, 100, 250, 9_999, 10_000];
+        for gross in grosses {
+            for fee_bps in fee_bps_values {
+                let (fee, remainder) = fee_and_remainder(gross, fee_bps);
+                assert!(fee <= gross, "fee {fee} exceeds gross {gross} at fee_bps={fee_bps}");
+                assert!(remainder < BPS_SCALE as u64, "remainder {remainder} should be < 10_000");
+                assert_eq!(fee + (gross - fee), gross);
+            }
+        }
+    }
+
+    #[test]
+    fn settle_with_fee_reconciles_net_plus_fee_to_gross() {
+        for gross in [0u64, 17, 1_000, 999_999] {
+            for fee_bps in [0u32, 50, 500, 10_000] {
+                let (net, fee) = settle_with_fee(gross, fee_bps);
+                assert_eq!(net + fee, gross, "gross={gross} fee_bps={fee_bps}");
+            }
+        }
+    }
+}
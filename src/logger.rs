@@ -1,7 +1,7 @@
 use crate::macro_data::MacroData;
 use anyhow::{Context, Result};
 use std::fs::File;
-use std::io::{BufWriter, Write};
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
 
 pub struct CsvLogger {
     writer: BufWriter<File>,
@@ -294,10 +294,329 @@ impl TickLogger {
     }
 }
 
+/// Source tag for a `TickRecord`, packed as a single byte instead of the
+/// free-form `&str` the CSV path uses. Covers the handful of values
+/// `TickLogger::log_tick` actually sees in this codebase (`"RTDS"`, `"WS"`,
+/// `"CL"`); anything else falls back to `Other` so the binary log can never
+/// fail to encode a tick just because a new source string shows up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum TickSource {
+    Rtds = 0,
+    Ws = 1,
+    Cl = 2,
+    Other = 255,
+}
+
+impl TickSource {
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "RTDS" => Self::Rtds,
+            "WS" => Self::Ws,
+            "CL" => Self::Cl,
+            _ => Self::Other,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Rtds => "RTDS",
+            Self::Ws => "WS",
+            Self::Cl => "CL",
+            Self::Other => "OTHER",
+        }
+    }
+
+    fn from_u8(b: u8) -> Self {
+        match b {
+            0 => Self::Rtds,
+            1 => Self::Ws,
+            2 => Self::Cl,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Number of bytes a single `TickRecord` occupies on disk.
+pub const TICK_RECORD_SIZE: usize = 25; // 8 (timestamp_ms) + 1 (source) + 8 (price) + 8 (window)
+
+/// One tick as stored by `BinaryTickLogger`. Hand-packed rather than
+/// bincode/serde so the on-disk layout is exactly `TICK_RECORD_SIZE` bytes
+/// with no tag or length prefix — `BinaryTickReader` can mmap a whole day
+/// file and address record `i` at `i * TICK_RECORD_SIZE` without parsing
+/// anything ahead of it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TickRecord {
+    pub timestamp_ms: u64,
+    pub source: TickSource,
+    pub price: f64,
+    pub window: u64,
+}
+
+impl TickRecord {
+    pub fn encode(self) -> [u8; TICK_RECORD_SIZE] {
+        let mut buf = [0u8; TICK_RECORD_SIZE];
+        buf[0..8].copy_from_slice(&self.timestamp_ms.to_le_bytes());
+        buf[8] = self.source as u8;
+        buf[9..17].copy_from_slice(&self.price.to_le_bytes());
+        buf[17..25].copy_from_slice(&self.window.to_le_bytes());
+        buf
+    }
+
+    /// Decodes one record from a `TICK_RECORD_SIZE`-byte slice. Panics if
+    /// `buf.len() != TICK_RECORD_SIZE` — callers (`BinaryTickReader`) only
+    /// ever hand it slices they've already bounds-checked against the file
+    /// length.
+    fn decode(buf: &[u8]) -> Self {
+        assert_eq!(buf.len(), TICK_RECORD_SIZE, "tick record must be {TICK_RECORD_SIZE} bytes");
+        Self {
+            timestamp_ms: u64::from_le_bytes(buf[0..8].try_into().unwrap()),
+            source: TickSource::from_u8(buf[8]),
+            price: f64::from_le_bytes(buf[9..17].try_into().unwrap()),
+            window: u64::from_le_bytes(buf[17..25].try_into().unwrap()),
+        }
+    }
+}
+
+/// Number of bytes a single index entry occupies in a `ticks_YYYYMMDD.idx` file.
+const TICK_INDEX_ENTRY_SIZE: usize = 24; // 8 (window) + 8 (byte_offset) + 8 (len)
+
+/// One `(window, byte_offset, len)` entry in a `ticks_YYYYMMDD.idx` file,
+/// pointing at the span of the paired `.bin` file holding that window's
+/// ticks. Hand-packed the same way as `TickRecord`, for the same reason.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TickIndexEntry {
+    window: u64,
+    byte_offset: u64,
+    len: u64,
+}
+
+impl TickIndexEntry {
+    fn encode(self) -> [u8; TICK_INDEX_ENTRY_SIZE] {
+        let mut buf = [0u8; TICK_INDEX_ENTRY_SIZE];
+        buf[0..8].copy_from_slice(&self.window.to_le_bytes());
+        buf[8..16].copy_from_slice(&self.byte_offset.to_le_bytes());
+        buf[16..24].copy_from_slice(&self.len.to_le_bytes());
+        buf
+    }
+
+    fn decode(buf: &[u8]) -> Self {
+        assert_eq!(buf.len(), TICK_INDEX_ENTRY_SIZE, "tick index entry must be {TICK_INDEX_ENTRY_SIZE} bytes");
+        Self {
+            window: u64::from_le_bytes(buf[0..8].try_into().unwrap()),
+            byte_offset: u64::from_le_bytes(buf[8..16].try_into().unwrap()),
+            len: u64::from_le_bytes(buf[16..24].try_into().unwrap()),
+        }
+    }
+}
+
+/// Compact binary alternative to `TickLogger`: same daily `ticks_YYYYMMDD`
+/// rotation, but each tick is a fixed-width `TickRecord` instead of a CSV
+/// line. Cuts both file size and write cost versus the `writeln!`-per-tick
+/// CSV path, and lets `BinaryTickReader` scan a day of ticks without
+/// line-by-line parsing.
+///
+/// Alongside `ticks_YYYYMMDD.bin` it maintains a companion
+/// `ticks_YYYYMMDD.idx` (ledger-style index + data), appending a
+/// `(window, byte_offset, len)` entry every time the logged `window` value
+/// changes. `TickWindowReader` binary-searches that index to seek straight
+/// to one window's ticks instead of scanning the whole day.
+pub struct BinaryTickLogger {
+    writer: BufWriter<File>,
+    idx_writer: BufWriter<File>,
+    base_dir: String,
+    current_date: String,
+    data_offset: u64,
+    /// `(window, byte_offset of its first record)` for the run of ticks
+    /// currently being written; flushed to the index once `window` changes
+    /// or the logger rotates/drops.
+    pending: Option<(u64, u64)>,
+}
+
+impl BinaryTickLogger {
+    pub fn new(base_dir: &str) -> Result<Self> {
+        std::fs::create_dir_all(base_dir).context("Cannot create ticks directory")?;
+        let date = TickLogger::today_str();
+        let (writer, idx_writer, data_offset) = Self::open_date(base_dir, &date)?;
+        Ok(Self { writer, idx_writer, base_dir: base_dir.to_string(), current_date: date, data_offset, pending: None })
+    }
+
+    fn open_date(base_dir: &str, date: &str) -> Result<(BufWriter<File>, BufWriter<File>, u64)> {
+        let data_path = format!("{base_dir}/ticks_{date}.bin");
+        let data_file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&data_path)
+            .context("Cannot create binary tick log file")?;
+        let data_offset = data_file.metadata().map(|m| m.len()).unwrap_or(0);
+        let idx_path = format!("{base_dir}/ticks_{date}.idx");
+        let idx_file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&idx_path)
+            .context("Cannot create tick index file")?;
+        Ok((BufWriter::new(data_file), BufWriter::new(idx_file), data_offset))
+    }
+
+    pub fn log_tick(&mut self, timestamp_ms: u64, source: TickSource, price: f64, window: u64) {
+        let _ = self.rotate_if_needed();
+        if self.pending.is_none_or(|(w, _)| w != window) {
+            self.flush_pending();
+            self.pending = Some((window, self.data_offset));
+        }
+        let record = TickRecord { timestamp_ms, source, price, window };
+        if let Err(e) = self.writer.write_all(&record.encode()).and_then(|_| self.writer.flush()) {
+            tracing::warn!("Binary tick write error: {e}");
+            return;
+        }
+        self.data_offset += TICK_RECORD_SIZE as u64;
+    }
+
+    /// Writes the in-progress index entry (if any) now that its window's
+    /// ticks are known to be complete, i.e. right before the window changes
+    /// or the logger rotates/drops.
+    fn flush_pending(&mut self) {
+        if let Some((window, start)) = self.pending.take() {
+            let entry = TickIndexEntry { window, byte_offset: start, len: self.data_offset - start };
+            if let Err(e) = self.idx_writer.write_all(&entry.encode()).and_then(|_| self.idx_writer.flush()) {
+                tracing::warn!("Tick index write error: {e}");
+            }
+        }
+    }
+
+    fn rotate_if_needed(&mut self) -> Result<()> {
+        let today = TickLogger::today_str();
+        if today != self.current_date {
+            self.flush_pending();
+            let (writer, idx_writer, data_offset) = Self::open_date(&self.base_dir, &today)?;
+            self.writer = writer;
+            self.idx_writer = idx_writer;
+            self.data_offset = data_offset;
+            self.current_date = today;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for BinaryTickLogger {
+    fn drop(&mut self) {
+        self.flush_pending();
+    }
+}
+
+/// Zero-copy reader over a `BinaryTickLogger` day file via `memmap2`. Ticks
+/// are only decoded on access (`record`/`iter`), so a backtest can scan
+/// millions of ticks without the allocation and line-splitting overhead of
+/// `TickLogger`'s CSV files.
+pub struct BinaryTickReader {
+    mmap: memmap2::Mmap,
+}
+
+impl BinaryTickReader {
+    pub fn open(path: &str) -> Result<Self> {
+        let file = File::open(path).with_context(|| format!("Cannot open {path}"))?;
+        // SAFETY: the file is only ever appended to by `BinaryTickLogger` in
+        // this same process; nothing truncates or rewrites it in place, so
+        // the mapping can't observe a torn record.
+        let mmap = unsafe { memmap2::Mmap::map(&file) }.with_context(|| format!("Cannot mmap {path}"))?;
+        anyhow::ensure!(
+            mmap.len() % TICK_RECORD_SIZE == 0,
+            "{path}: length {} is not a multiple of the {TICK_RECORD_SIZE}-byte record size",
+            mmap.len()
+        );
+        Ok(Self { mmap })
+    }
+
+    pub fn len(&self) -> usize {
+        self.mmap.len() / TICK_RECORD_SIZE
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mmap.is_empty()
+    }
+
+    pub fn record(&self, i: usize) -> TickRecord {
+        let start = i * TICK_RECORD_SIZE;
+        TickRecord::decode(&self.mmap[start..start + TICK_RECORD_SIZE])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = TickRecord> + '_ {
+        (0..self.len()).map(move |i| self.record(i))
+    }
+}
+
+/// Calendar date immediately before `date` (a `YYYYMMDD` string), for
+/// `TickWindowReader`'s day-boundary fallback. Hand-rolled like
+/// `TickLogger::date_from_epoch` rather than pulling in a date crate.
+fn previous_date_str(date: &str) -> String {
+    let y: u32 = date[0..4].parse().unwrap_or(1970);
+    let m: u32 = date[4..6].parse().unwrap_or(1);
+    let d: u32 = date[6..8].parse().unwrap_or(1);
+    if d > 1 {
+        return format!("{y}{m:02}{:02}", d - 1);
+    }
+    let (py, pm) = if m > 1 { (y, m - 1) } else { (y - 1, 12) };
+    let leap = py.is_multiple_of(4) && (!py.is_multiple_of(100) || py.is_multiple_of(400));
+    let months = [31, if leap { 29 } else { 28 }, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+    format!("{py}{pm:02}{:02}", months[(pm - 1) as usize])
+}
+
+/// Reads a single 5-minute window's ticks out of `BinaryTickLogger`'s paired
+/// `ticks_YYYYMMDD.bin`/`.idx` files without scanning the rest of the day:
+/// binary-searches the index (sorted by window, since ticks are appended in
+/// chronological order) for the matching run, then seeks the data file
+/// straight to its `byte_offset`. A window that straddles UTC midnight has
+/// its ticks split across two day files, so `read_window` also checks the
+/// previous calendar day and concatenates any match found there.
+pub struct TickWindowReader {
+    base_dir: String,
+    date: String,
+}
+
+impl TickWindowReader {
+    pub fn new(base_dir: &str, date: &str) -> Self {
+        Self { base_dir: base_dir.to_string(), date: date.to_string() }
+    }
+
+    pub fn read_window(&self, window: u64) -> Result<Vec<TickRecord>> {
+        let mut out = self.read_window_in_date(window, &self.date)?;
+        out.extend(self.read_window_in_date(window, &previous_date_str(&self.date))?);
+        Ok(out)
+    }
+
+    fn read_window_in_date(&self, window: u64, date: &str) -> Result<Vec<TickRecord>> {
+        let idx_path = format!("{}/ticks_{date}.idx", self.base_dir);
+        let Ok(idx_bytes) = std::fs::read(&idx_path) else {
+            return Ok(Vec::new());
+        };
+        anyhow::ensure!(
+            idx_bytes.len() % TICK_INDEX_ENTRY_SIZE == 0,
+            "{idx_path}: length {} is not a multiple of the {TICK_INDEX_ENTRY_SIZE}-byte index entry size",
+            idx_bytes.len()
+        );
+        let entries: Vec<TickIndexEntry> = idx_bytes.chunks_exact(TICK_INDEX_ENTRY_SIZE).map(TickIndexEntry::decode).collect();
+        let lo = entries.partition_point(|e| e.window < window);
+        let hi = entries.partition_point(|e| e.window <= window);
+        if lo == hi {
+            return Ok(Vec::new());
+        }
+
+        let data_path = format!("{}/ticks_{date}.bin", self.base_dir);
+        let mut file = File::open(&data_path).with_context(|| format!("Cannot open {data_path}"))?;
+        let mut out = Vec::new();
+        for entry in &entries[lo..hi] {
+            file.seek(SeekFrom::Start(entry.byte_offset))?;
+            let mut buf = vec![0u8; entry.len as usize];
+            file.read_exact(&mut buf)?;
+            out.extend(buf.chunks_exact(TICK_RECORD_SIZE).map(TickRecord::decode));
+        }
+        Ok(out)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::io::Read;
 
     #[test]
     fn csv_header_and_trade_line() {
@@ -514,4 +833,90 @@ mod tests {
         // 1970-01-01
         assert_eq!(super::TickLogger::date_from_epoch(0), "19700101");
     }
+
+    #[test]
+    fn tick_record_round_trips_through_bytes() {
+        let record = TickRecord { timestamp_ms: 1700000000123, source: TickSource::Rtds, price: 97150.52, window: 1699999800 };
+        let decoded = TickRecord::decode(&record.encode());
+        assert_eq!(decoded, record);
+    }
+
+    #[test]
+    fn tick_source_unknown_strings_fall_back_to_other() {
+        assert_eq!(TickSource::from_str("RTDS"), TickSource::Rtds);
+        assert_eq!(TickSource::from_str("WS"), TickSource::Ws);
+        assert_eq!(TickSource::from_str("CL"), TickSource::Cl);
+        assert_eq!(TickSource::from_str("Kraken"), TickSource::Other);
+        assert_eq!(TickSource::from_str("Kraken").as_str(), "OTHER");
+    }
+
+    #[test]
+    fn binary_tick_logger_writes_and_reads_back() {
+        let dir = "/tmp/poly5m_test_bin_ticks";
+        let _ = std::fs::remove_dir_all(dir);
+        let mut logger = super::BinaryTickLogger::new(dir).unwrap();
+        logger.log_tick(1700000000000, TickSource::Rtds, 97150.50, 1699999800);
+        logger.log_tick(1700000000100, TickSource::Ws, 97150.80, 1699999800);
+        drop(logger);
+
+        let entries: Vec<_> = std::fs::read_dir(dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "bin"))
+            .collect();
+        assert_eq!(entries.len(), 1);
+        let reader = super::BinaryTickReader::open(entries[0].path().to_str().unwrap()).unwrap();
+        assert_eq!(reader.len(), 2);
+        let records: Vec<_> = reader.iter().collect();
+        assert_eq!(records[0], TickRecord { timestamp_ms: 1700000000000, source: TickSource::Rtds, price: 97150.50, window: 1699999800 });
+        assert_eq!(records[1].source, TickSource::Ws);
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn binary_tick_reader_rejects_truncated_file() {
+        let path = "/tmp/poly5m_test_bin_truncated.bin";
+        std::fs::write(path, [0u8; TICK_RECORD_SIZE - 1]).unwrap();
+        assert!(super::BinaryTickReader::open(path).is_err());
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn previous_date_str_handles_month_and_year_rollover() {
+        assert_eq!(super::previous_date_str("20260301"), "20260228");
+        assert_eq!(super::previous_date_str("20260101"), "20251231");
+        assert_eq!(super::previous_date_str("20240301"), "20240229"); // leap year
+        assert_eq!(super::previous_date_str("20260215"), "20260214");
+    }
+
+    #[test]
+    fn tick_window_reader_seeks_directly_to_one_window() {
+        let dir = "/tmp/poly5m_test_window_index";
+        let _ = std::fs::remove_dir_all(dir);
+        let date = super::TickLogger::today_str();
+        {
+            let mut logger = super::BinaryTickLogger::new(dir).unwrap();
+            logger.log_tick(1700000000000, TickSource::Rtds, 97150.0, 1699999800);
+            logger.log_tick(1700000000100, TickSource::Rtds, 97150.5, 1699999800);
+            logger.log_tick(1700000000200, TickSource::Ws, 97200.0, 1700000100);
+            logger.log_tick(1700000000300, TickSource::Cl, 97300.0, 1700000400);
+        } // Drop flushes the final window's index entry.
+
+        let reader = super::TickWindowReader::new(dir, &date);
+        let first_window = reader.read_window(1699999800).unwrap();
+        assert_eq!(first_window.len(), 2);
+        assert_eq!(first_window[0].price, 97150.0);
+        assert_eq!(first_window[1].price, 97150.5);
+
+        let second_window = reader.read_window(1700000100).unwrap();
+        assert_eq!(second_window.len(), 1);
+        assert_eq!(second_window[0].source, TickSource::Ws);
+
+        let last_window = reader.read_window(1700000400).unwrap();
+        assert_eq!(last_window.len(), 1);
+        assert_eq!(last_window[0].source, TickSource::Cl);
+
+        assert!(reader.read_window(999).unwrap().is_empty());
+        std::fs::remove_dir_all(dir).ok();
+    }
 }
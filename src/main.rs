@@ -1,10 +1,26 @@
+mod amount;
+mod analytics;
+mod backtest;
+mod buckets;
+mod candles;
 mod chainlink;
+mod combinatorial;
+mod db;
+mod digital_option;
 mod exchanges;
+mod exit;
+mod fixedpoint;
 mod logger;
 mod macro_data;
+mod market_path;
+mod metrics;
+mod notify;
 mod polymarket;
+mod portfolio;
 mod presets;
+mod price_source;
 mod rtds;
+mod simulator;
 mod strategy;
 
 use alloy::primitives::Address;
@@ -12,6 +28,7 @@ use alloy::providers::ProviderBuilder;
 use anyhow::{Context, Result};
 use futures::future::select_ok;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::time;
 
@@ -28,6 +45,26 @@ struct Config {
     exchanges: ExchangesConfig,
     #[serde(default)]
     logging: LoggingConfig,
+    #[serde(default)]
+    database: DatabaseConfig,
+    #[serde(default)]
+    candles: CandlesConfig,
+    #[serde(default)]
+    notifications: NotificationsConfig,
+    #[serde(default)]
+    schedule: ScheduleConfig,
+    #[serde(default)]
+    metrics: MetricsConfig,
+}
+
+#[derive(Deserialize, Default)]
+struct MetricsConfig {
+    #[serde(default)]
+    enabled: bool,
+    /// e.g. "127.0.0.1:9090". Empty (or `enabled = false`) disables the
+    /// `/metrics` endpoint entirely.
+    #[serde(default)]
+    bind_addr: String,
 }
 
 #[derive(Deserialize, Default)]
@@ -36,6 +73,69 @@ struct LoggingConfig {
     csv_path: String,
 }
 
+#[derive(Deserialize, Default)]
+struct DatabaseConfig {
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default)]
+    connection_string: String,
+    #[serde(default)]
+    ssl: bool,
+    #[serde(default = "default_table_prefix")]
+    table_prefix: String,
+    #[serde(default = "default_db_channel_capacity")]
+    channel_capacity: usize,
+}
+
+fn default_table_prefix() -> String { "poly5m_".into() }
+fn default_db_channel_capacity() -> usize { 1024 }
+
+/// Per-exchange OHLC candle aggregation (cf. `src/candles.rs`), independent
+/// of `[database]` — built for the Data Farm preset to accumulate a clean
+/// bar history across runs, not just mirror the trade window's ticks.
+#[derive(Deserialize, Default)]
+struct CandlesConfig {
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default = "default_candle_interval_ms")]
+    interval_ms: u64,
+    #[serde(default = "default_candle_out_dir")]
+    out_dir: String,
+    #[serde(default)]
+    database_enabled: bool,
+    #[serde(default)]
+    connection_string: String,
+    #[serde(default = "default_table_prefix")]
+    table_prefix: String,
+    #[serde(default = "default_db_channel_capacity")]
+    channel_capacity: usize,
+}
+
+fn default_candle_interval_ms() -> u64 { 60_000 }
+fn default_candle_out_dir() -> String { "candles".into() }
+
+#[derive(Deserialize)]
+struct NotificationsConfig {
+    #[serde(default)]
+    enabled: bool,
+    #[serde(default)]
+    webhook_url: String,
+    #[serde(default)]
+    chat_id: String,
+    #[serde(default = "default_notify_events")]
+    events: Vec<String>,
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self { enabled: false, webhook_url: String::new(), chat_id: String::new(), events: default_notify_events() }
+    }
+}
+
+fn default_notify_events() -> Vec<String> {
+    vec!["fill".into(), "circuit_breaker".into(), "max_consecutive_losses".into(), "session_limit".into()]
+}
+
 #[derive(Deserialize)]
 struct ChainlinkConfig {
     rpc_urls: Vec<String>,
@@ -70,11 +170,38 @@ struct ExchangesConfig {
     coinbase_ws: String,
     #[serde(default = "default_kraken_ws")]
     kraken_ws: String,
+    #[serde(default = "default_kraken_v1_ws")]
+    kraken_v1_ws: String,
+    #[serde(default = "default_source_staleness_ms")]
+    source_staleness_ms: u64,
+    #[serde(default = "default_max_source_divergence_pct")]
+    max_source_divergence_pct: f64,
 }
 
 fn default_binance_ws() -> String { "wss://stream.binance.com:9443/ws/btcusdt@trade".into() }
 fn default_coinbase_ws() -> String { "wss://ws-feed.exchange.coinbase.com".into() }
 fn default_kraken_ws() -> String { "wss://ws.kraken.com/v2".into() }
+fn default_kraken_v1_ws() -> String { "wss://ws.kraken.com".into() }
+fn default_source_staleness_ms() -> u64 { 5_000 }
+fn default_max_source_divergence_pct() -> f64 { 0.01 }
+
+#[derive(Deserialize, Default)]
+struct ScheduleConfig {
+    #[serde(default)]
+    enabled: bool,
+    /// UTC hours (0-23) during which trading is allowed. Empty = all hours allowed.
+    #[serde(default)]
+    allowed_hours_utc: Vec<u8>,
+    /// UTC weekdays during which trading is allowed (0=Sunday .. 6=Saturday).
+    /// Empty = all days allowed.
+    #[serde(default)]
+    allowed_weekdays_utc: Vec<u8>,
+    /// Instead of a hard stop when the session PnL limit is hit, cooldown
+    /// this many seconds, then reset `session.pnl_usdc` and resume at the
+    /// next allowed window. 0 = hard stop (previous behavior).
+    #[serde(default)]
+    session_cooldown_s: u64,
+}
 
 #[derive(Deserialize)]
 struct PolymarketConfig {
@@ -82,6 +209,14 @@ struct PolymarketConfig {
     api_secret: String,
     passphrase: String,
     private_key: String,
+    /// Proxy/Gnosis-Safe address that funds orders, if trading through one
+    /// rather than directly from the EOA derived from `private_key`.
+    #[serde(default)]
+    funder_address: Option<String>,
+    /// 1 = POLY_PROXY, 2 = POLY_GNOSIS_SAFE. Ignored (and defaults to EOA)
+    /// if `funder_address` is unset.
+    #[serde(default)]
+    funder_signature_type: Option<u8>,
 }
 
 #[derive(Deserialize)]
@@ -113,6 +248,12 @@ struct StrategyToml {
     order_type: String,
     #[serde(default = "default_maker_timeout")]
     maker_timeout_s: u64,
+    /// For `order_type = "HYBRID"`: once `seconds_remaining` in the window
+    /// drops below this, an unfilled maker order is cancelled and
+    /// immediately re-submitted as a taker FOK at `best_ask` instead of
+    /// waiting out the rest of `maker_timeout_s`.
+    #[serde(default = "default_hybrid_escalation_s")]
+    hybrid_escalation_s: u64,
     #[serde(default)]
     min_delta_pct: f64,
     #[serde(default = "default_max_spread")]
@@ -149,6 +290,63 @@ struct StrategyToml {
     min_z_score: f64,
     #[serde(default = "default_max_model_divergence")]
     max_model_divergence: f64,
+    #[serde(default = "default_quote_spread_pct")]
+    quote_spread_pct: f64,
+    #[serde(default = "default_atr_window")]
+    atr_window: usize,
+    #[serde(default)]
+    exit_stop_atr_mult: f64,
+    #[serde(default)]
+    exit_tp_atr_mult: f64,
+    #[serde(default = "default_atr_window")]
+    exit_tp_window: usize,
+    #[serde(default = "default_fisher_window")]
+    fisher_window: usize,
+    #[serde(default)]
+    fisher_extreme_threshold: f64,
+    #[serde(default)]
+    min_vol_edge: f64,
+    #[serde(default)]
+    roi_table: Vec<(u64, f64)>,
+    #[serde(default)]
+    trailing_stop_pct: f64,
+    #[serde(default)]
+    trailing_stop_bps: f64,
+    #[serde(default)]
+    hard_stop_bps: f64,
+    #[serde(default)]
+    min_momentum_exit: f64,
+    /// "multiplier" (default) or "isotonic" — see `strategy::CalibrationMode`.
+    #[serde(default = "default_calibration_mode")]
+    calibration_mode: String,
+    #[serde(default = "default_safety_spread_pct")]
+    safety_spread_pct: f64,
+    /// Ascending `(activation_ratio, callback_rate)` pairs — see
+    /// `strategy::StrategyConfig::trailing_stages`. Empty disables the
+    /// staged trailing stop.
+    #[serde(default)]
+    trailing_stages: Vec<(f64, f64)>,
+    #[serde(default)]
+    daily_fee_budget: f64,
+    #[serde(default)]
+    daily_max_volume: f64,
+    /// Conservative shading applied to the aggregated WS index price by
+    /// `ExchangeFeed::latest()` — see `strategy::StrategyConfig::feed_spread_pct`.
+    #[serde(default)]
+    feed_spread_pct: f64,
+    /// Directional bias applied on top of `feed_spread_pct` — see
+    /// `strategy::StrategyConfig::feed_skew_pct`.
+    #[serde(default)]
+    feed_skew_pct: f64,
+    /// See `strategy::StrategyConfig::symmetric_fee_model`.
+    #[serde(default)]
+    symmetric_fee_model: bool,
+    /// See `strategy::StrategyConfig::symmetric_fee_base_rate`.
+    #[serde(default = "default_symmetric_fee_base_rate")]
+    symmetric_fee_base_rate: f64,
+    /// See `strategy::StrategyConfig::consensus_max_deviation_pct`.
+    #[serde(default)]
+    consensus_max_deviation_pct: f64,
     #[serde(default)]
     extreme: ExtremeToml,
 }
@@ -197,6 +395,7 @@ fn default_vol_lookback() -> usize { 20 }
 fn default_vol_pct() -> f64 { 0.12 }
 fn default_order_type() -> String { "FOK".into() }
 fn default_maker_timeout() -> u64 { 5 }
+fn default_hybrid_escalation_s() -> u64 { 3 }
 fn default_max_spread() -> f64 { 0.0 }
 fn default_kelly_fraction() -> f64 { 0.10 }
 fn default_initial_bankroll() -> f64 { 40.0 }
@@ -206,6 +405,12 @@ fn default_circuit_breaker_cooldown() -> u64 { 1800 }
 fn default_student_t_df() -> f64 { 4.0 }
 fn default_min_z_score() -> f64 { 0.5 }
 fn default_max_model_divergence() -> f64 { 0.30 }
+fn default_quote_spread_pct() -> f64 { 0.02 }
+fn default_atr_window() -> usize { 12 }
+fn default_fisher_window() -> usize { 20 }
+fn default_calibration_mode() -> String { "multiplier".into() }
+fn default_safety_spread_pct() -> f64 { 0.02 }
+fn default_symmetric_fee_base_rate() -> f64 { 0.02 }
 
 impl From<StrategyToml> for strategy::StrategyConfig {
     fn from(s: StrategyToml) -> Self {
@@ -238,6 +443,33 @@ impl From<StrategyToml> for strategy::StrategyConfig {
             student_t_df: s.student_t_df,
             min_z_score: s.min_z_score,
             max_model_divergence: s.max_model_divergence,
+            quote_spread_pct: s.quote_spread_pct,
+            atr_window: s.atr_window,
+            exit_stop_atr_mult: s.exit_stop_atr_mult,
+            exit_tp_atr_mult: s.exit_tp_atr_mult,
+            exit_tp_window: s.exit_tp_window,
+            fisher_window: s.fisher_window,
+            fisher_extreme_threshold: s.fisher_extreme_threshold,
+            min_vol_edge: s.min_vol_edge,
+            roi_table: s.roi_table,
+            trailing_stop_pct: s.trailing_stop_pct,
+            trailing_stop_bps: s.trailing_stop_bps,
+            hard_stop_bps: s.hard_stop_bps,
+            min_momentum_exit: s.min_momentum_exit,
+            calibration_breakpoints: Vec::new(),
+            calibration_mode: match s.calibration_mode.as_str() {
+                "isotonic" => strategy::CalibrationMode::Isotonic,
+                _ => strategy::CalibrationMode::Multiplier,
+            },
+            safety_spread_pct: s.safety_spread_pct,
+            trailing_stages: s.trailing_stages,
+            daily_fee_budget: s.daily_fee_budget,
+            daily_max_volume: s.daily_max_volume,
+            feed_spread_pct: s.feed_spread_pct,
+            feed_skew_pct: s.feed_skew_pct,
+            symmetric_fee_model: s.symmetric_fee_model,
+            symmetric_fee_base_rate: s.symmetric_fee_base_rate,
+            consensus_max_deviation_pct: s.consensus_max_deviation_pct,
             extreme: strategy::ExtremeConfig {
                 enabled: s.extreme.enabled,
                 min_velocity: s.extreme.min_velocity,
@@ -263,6 +495,11 @@ fn load_config() -> Result<Config> {
     if let Ok(v) = std::env::var("POLY_API_SECRET") { config.polymarket.api_secret = v; }
     if let Ok(v) = std::env::var("POLY_PASSPHRASE") { config.polymarket.passphrase = v; }
     if let Ok(v) = std::env::var("POLY_PRIVATE_KEY") { config.polymarket.private_key = v; }
+    if let Ok(v) = std::env::var("POLY_FUNDER_ADDRESS") { config.polymarket.funder_address = Some(v); }
+    if let Ok(v) = std::env::var("POLY_FUNDER_SIGNATURE_TYPE") {
+        config.polymarket.funder_signature_type = Some(v.parse().context("POLY_FUNDER_SIGNATURE_TYPE invalide")?);
+    }
+    if let Ok(v) = std::env::var("DATABASE_URL") { config.database.connection_string = v; }
 
     Ok(config)
 }
@@ -279,6 +516,30 @@ async fn main() -> Result<()> {
         .init();
 
     let config = load_config()?;
+
+    // --backtest <dir>: replay recorded ticks_*.csv files (written by
+    // `logger::TickLogger`) through the strategy offline, print the result,
+    // and exit — no RPCs, WS feeds, or Polymarket client touched.
+    if let Some(dir) = std::env::args().skip_while(|a| a != "--backtest").nth(1) {
+        let vol_lookback = config.strategy.vol_lookback_intervals;
+        let default_vol = config.strategy.default_vol_pct;
+        let initial_bankroll = config.strategy.initial_bankroll_usdc;
+        let strat_config = strategy::StrategyConfig::from(config.strategy);
+        let report = backtest::run_csv_replay(
+            std::path::Path::new(&dir),
+            strat_config,
+            initial_bankroll,
+            300,
+            vol_lookback,
+            default_vol,
+        )?;
+        println!(
+            "Backtest replay of {dir}: {} trades | win rate {:.1}% | PnL ${:.2} | Brier {:.4}",
+            report.trades, report.win_rate * 100.0, report.pnl_usdc, report.brier_score,
+        );
+        return Ok(());
+    }
+
     let poll_ms_base = config.chainlink.poll_interval_ms;
     let poll_ms_ws = config.chainlink.poll_interval_ms_with_ws;
     let default_fee_rate_bps = config.strategy.fee_rate_bps;
@@ -289,7 +550,7 @@ async fn main() -> Result<()> {
         .skip_while(|a| a != "--profile")
         .nth(1);
 
-    let (strat_config, dry_run, order_type, maker_timeout_s, vol_lookback, default_vol) =
+    let (strat_config, dry_run, order_type, maker_timeout_s, hybrid_escalation_s, vol_lookback, default_vol) =
         if let Some(ref name) = profile_name {
             let preset = presets::get(name)
                 .unwrap_or_else(|| {
@@ -303,7 +564,7 @@ async fn main() -> Result<()> {
             };
             let maker_timeout_s = if &order_type == "GTC" { 3 } else { config.strategy.maker_timeout_s };
             tracing::info!("Profil: {name}");
-            (preset, dry_run, order_type, maker_timeout_s,
+            (preset, dry_run, order_type, maker_timeout_s, config.strategy.hybrid_escalation_s,
                 config.strategy.vol_lookback_intervals, config.strategy.default_vol_pct)
         } else if let Some(name) = presets::interactive_menu() {
             let preset = presets::get(name).unwrap();
@@ -314,20 +575,29 @@ async fn main() -> Result<()> {
             };
             let maker_timeout_s = if &order_type == "GTC" { 3 } else { config.strategy.maker_timeout_s };
             tracing::info!("Profil: {name}");
-            (preset, dry_run, order_type, maker_timeout_s,
+            (preset, dry_run, order_type, maker_timeout_s, config.strategy.hybrid_escalation_s,
                 config.strategy.vol_lookback_intervals, config.strategy.default_vol_pct)
         } else {
             let dry_run = config.strategy.dry_run;
             let order_type = config.strategy.order_type.clone();
             let maker_timeout_s = config.strategy.maker_timeout_s;
+            let hybrid_escalation_s = config.strategy.hybrid_escalation_s;
             let vol_lookback = config.strategy.vol_lookback_intervals;
             let default_vol = config.strategy.default_vol_pct;
             let strat_config = strategy::StrategyConfig::from(config.strategy);
-            (strat_config, dry_run, order_type, maker_timeout_s, vol_lookback, default_vol)
+            (strat_config, dry_run, order_type, maker_timeout_s, hybrid_escalation_s, vol_lookback, default_vol)
         };
 
     let mut strat_config = strat_config;
 
+    // --spread <pct> overrides the configured/preset quote_spread_pct at startup.
+    let spread_pct = std::env::args()
+        .skip_while(|a| a != "--spread")
+        .nth(1)
+        .and_then(|s| s.parse::<f64>().ok())
+        .unwrap_or(strat_config.quote_spread_pct);
+    let quote_spread = price_source::Spread::new(spread_pct)?;
+
     // Providers Chainlink — timeouts serrés pour le racing
     let providers = config.chainlink.rpc_urls.iter()
         .map(|url| {
@@ -338,11 +608,24 @@ async fn main() -> Result<()> {
     anyhow::ensure!(!providers.is_empty(), "Au moins un rpc_url requis");
 
     // Client Polymarket (optionnel en dry-run si credentials manquants)
-    let poly = match polymarket::PolymarketClient::new(
+    let funder = match &config.polymarket.funder_address {
+        Some(addr) => {
+            let funder = addr.parse().context("funder_address invalide")?;
+            let signature_type = match config.polymarket.funder_signature_type {
+                Some(1) | None => polymarket::SignatureType::PolyProxy,
+                Some(2) => polymarket::SignatureType::PolyGnosisSafe,
+                Some(other) => anyhow::bail!("funder_signature_type inconnu: {other}"),
+            };
+            Some(polymarket::FunderConfig { funder, signature_type })
+        }
+        None => None,
+    };
+    let poly = match polymarket::PolymarketClient::from_private_key(
         config.polymarket.api_key,
         config.polymarket.api_secret,
         config.polymarket.passphrase,
         &config.polymarket.private_key,
+        funder,
     ) {
         Ok(c) => Some(c),
         Err(e) if dry_run => {
@@ -354,10 +637,15 @@ async fn main() -> Result<()> {
 
     // Exchange WebSocket feed (optionnel)
     let exchange_feed = if config.exchanges.enabled {
+        let sources: Vec<Box<dyn exchanges::WsPriceSource>> = vec![
+            Box::new(exchanges::BinanceWs::new(config.exchanges.binance_ws.clone())),
+            Box::new(exchanges::CoinbaseWs::new(config.exchanges.coinbase_ws.clone())),
+            Box::new(exchanges::KrakenWs::new(config.exchanges.kraken_ws.clone())),
+        ];
         let ef = exchanges::ExchangeFeed::start(
-            &config.exchanges.binance_ws,
-            &config.exchanges.coinbase_ws,
-            &config.exchanges.kraken_ws,
+            sources,
+            config.exchanges.source_staleness_ms,
+            config.exchanges.max_source_divergence_pct,
         ).await;
         tracing::info!("Exchange WS feed démarré (Binance + Coinbase + Kraken)");
         Some(ef)
@@ -365,6 +653,16 @@ async fn main() -> Result<()> {
         None
     };
 
+    // Kraken v1 feed — second flux natif indépendant, pour sanity-check du settlement
+    // hors de la médiane Binance/Coinbase/Kraken v2 ci-dessus.
+    let kraken_cross_check = if config.exchanges.enabled {
+        let kf = exchanges::KrakenFeed::start(&config.exchanges.kraken_v1_ws).await;
+        tracing::info!("Kraken v1 cross-check feed démarré");
+        Some(kf)
+    } else {
+        None
+    };
+
     // RTDS feed (Polymarket settlement price, optionnel)
     let rtds_feed = if config.rtds.enabled {
         let rf = rtds::RtdsFeed::start(&config.rtds.ws_url, &config.rtds.symbol).await;
@@ -438,6 +736,75 @@ async fn main() -> Result<()> {
         None
     };
 
+    // Postgres/TimescaleDB mirror (optionnel, cf. [database] dans config.toml).
+    // Une connexion ratée ne bloque jamais le bot : on loggue un warn et on
+    // continue en CSV-only.
+    let db_logger = if config.database.enabled {
+        match db::DbLogger::connect(
+            &config.database.connection_string,
+            config.database.ssl,
+            &config.database.table_prefix,
+            config.database.channel_capacity,
+        ).await {
+            Ok(l) => {
+                tracing::info!("Database mirroring → {}ticks / {}outcomes", config.database.table_prefix, config.database.table_prefix);
+                Some(l)
+            }
+            Err(e) => {
+                tracing::warn!("Failed to connect to database, falling back to CSV-only: {e:#}");
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // Per-exchange OHLC candle aggregation (optionnel, cf. [candles] dans
+    // config.toml). A Postgres mirror is opt-in on top of the JSONL writer;
+    // a failed connection falls back to JSONL-only the same way `database`
+    // falls back to CSV-only.
+    let mut candle_aggregator = if config.candles.enabled {
+        let writer: Box<dyn candles::CandleWriter> = if config.candles.database_enabled {
+            match candles::PgCandleWriter::connect(
+                &config.candles.connection_string,
+                &config.candles.table_prefix,
+                config.candles.channel_capacity,
+            ).await {
+                Ok(w) => {
+                    tracing::info!("Candle mirroring → {}source_candles", config.candles.table_prefix);
+                    Box::new(w)
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to connect candle writer to database, falling back to JSONL-only: {e:#}");
+                    Box::new(candles::JsonlCandleWriter::new(&config.candles.out_dir)?)
+                }
+            }
+        } else {
+            Box::new(candles::JsonlCandleWriter::new(&config.candles.out_dir)?)
+        };
+        tracing::info!("Candle aggregation → {} ({}ms bars)", config.candles.out_dir, config.candles.interval_ms);
+        Some(candles::CandleAggregator::new(config.candles.interval_ms, writer))
+    } else {
+        None
+    };
+    let mut candle_last_seen_ms: HashMap<&'static str, u64> = HashMap::new();
+
+    // Push notifications for fills/circuit-breaker/session-limit events
+    // (cf. [notifications] in config.toml). Notifier::start spawns a no-op
+    // dispatcher when disabled, so `notifier.send(...)` is always safe to
+    // call below.
+    let notifier = notify::Notifier::start(
+        config.notifications.webhook_url.clone(),
+        config.notifications.chat_id.clone(),
+        if config.notifications.enabled { config.notifications.events.clone() } else { Vec::new() },
+    ).await;
+
+    // Prometheus /metrics endpoint (cf. [metrics] in config.toml). No-ops
+    // when disabled, so the `metrics.set_*`/`record_*` calls below are
+    // always safe to call.
+    let metrics = metrics::Metrics::new();
+    metrics.start(if config.metrics.enabled { config.metrics.bind_addr.clone() } else { String::new() }).await;
+
     let macro_http = reqwest::Client::builder()
         .timeout(Duration::from_secs(3))
         .build()?;
@@ -452,6 +819,12 @@ async fn main() -> Result<()> {
     let mut traded_this_window = false;
     let mut cached_market: Option<polymarket::Market> = None;
     let mut pending_bet: Option<PendingBet> = load_pending_bet();
+    if pending_bet.is_none() {
+        if let Some(ref poly) = poly {
+            pending_bet = reconcile_order_lifecycle(poly).await;
+        }
+    }
+    let mut daily_budget = load_daily_budget(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs());
     let mut last_mid = 0.0f64;
     let mut skip_reason = String::from("startup");
     #[allow(unused_assignments)]
@@ -462,6 +835,8 @@ async fn main() -> Result<()> {
     let mut reversal_detected_this_window = false;
     let mut calibrator = strategy::Calibrator::new(200);
     calibrator.set_current_vcm(strat_config.vol_confidence_multiplier);
+    let mut adaptive_tp = exit::AdaptiveTp::new(strat_config.exit_tp_window);
+    let mut exited_this_window = false;
 
     // Load saved calibration if available (not when using a preset)
     if profile_name.is_none() {
@@ -489,16 +864,102 @@ async fn main() -> Result<()> {
         }
 
         let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        daily_budget.reset_if_new_day(now);
         let window = (now / 300) * 300;
         let window_end = window + 300;
         let remaining = window_end.saturating_sub(now);
 
-        // Prix BTC : RTDS (settlement, primaire) > WS exchanges > Chainlink on-chain (fallback)
-        let rtds_price = rtds_feed.as_ref().and_then(|rf| rf.latest());
-        let ws_agg = exchange_feed.as_ref().map(|ef| ef.latest());
+        // Prix BTC : RTDS (settlement, primaire) > WS exchanges > Chainlink on-chain (fallback).
+        // Goes through the `price_source::PriceSource` trait rather than `RtdsFeed`'s own
+        // inherent `latest()` so this call site actually depends on the same abstraction
+        // `CompositeSource`/`ConsensusReport` are built against, instead of only the tests.
+        let rtds_price = match rtds_feed.as_ref() {
+            Some(rf) => match price_source::PriceSource::latest_price(rf).await {
+                Ok(opt) => opt.map(|p| p.price_usd),
+                Err(e) => {
+                    tracing::warn!("RTDS price source error: {e:#}");
+                    None
+                }
+            },
+            None => None,
+        };
+        let ws_agg = exchange_feed.as_ref().map(|ef| ef.latest(strat_config.feed_spread_pct, strat_config.feed_skew_pct));
         let ws_price = ws_agg.filter(|a| a.num_sources > 0).map(|a| a.median_price);
         let num_ws = ws_agg.map_or(0, |a| a.num_sources);
 
+        // Feed the per-exchange candle aggregator straight off the raw
+        // sources, skipping sources whose `Slot` hasn't moved since the
+        // last poll so an unchanged re-read doesn't count as a new tick.
+        if let Some(agg) = candle_aggregator.as_mut() {
+            if let Some(ef) = exchange_feed.as_ref() {
+                for (source, slot) in ef.latest_per_source() {
+                    if let Some((price, updated_ms)) = slot {
+                        let last_seen = candle_last_seen_ms.get(source).copied().unwrap_or(0);
+                        if updated_ms > last_seen {
+                            agg.on_tick(source, price, updated_ms);
+                            candle_last_seen_ms.insert(source, updated_ms);
+                        }
+                    }
+                }
+            }
+        }
+
+        let kraken_btc = kraken_cross_check.as_ref().and_then(|kf| kf.latest());
+
+        // Sanity-check the primary price against Kraken's native feed — this never
+        // overrides `current_btc`, it just surfaces a divergence warning.
+        if let Some(kraken_btc) = kraken_btc {
+            let reference = rtds_price.or(ws_price);
+            if let Some(reference) = reference {
+                let divergence_pct = ((kraken_btc - reference) / reference * 100.0).abs();
+                if divergence_pct > 0.5 {
+                    tracing::warn!(
+                        "Kraken cross-check diverges from primary feed by {divergence_pct:.2}% (kraken=${kraken_btc:.2}, primary=${reference:.2})"
+                    );
+                }
+            }
+        }
+
+        // Manipulation guard: when at least two independently-polled feeds
+        // are live this tick, require at least two of them to agree within
+        // `consensus_max_deviation_pct` of `price_source::validate_consensus`'s
+        // median; a feed outside that band alongside an agreeing majority is
+        // only logged. Unlike the Kraken sanity check above (warn-only), a
+        // failed consensus (no two feeds agreeing) skips the whole tick —
+        // 0.0 disables the guard (e.g. the Data Farm preset, which wants
+        // every tick logged even while feeds disagree).
+        if strat_config.consensus_max_deviation_pct > 0.0 {
+            let mut sources: Vec<(&'static str, f64)> = Vec::new();
+            if let Some(p) = rtds_price {
+                sources.push(("RTDS", p));
+            }
+            if let Some(p) = ws_price {
+                sources.push(("WS", p));
+            }
+            if let Some(p) = kraken_btc {
+                sources.push(("Kraken", p));
+            }
+            if sources.len() >= 2 {
+                match price_source::validate_consensus(&sources, strat_config.consensus_max_deviation_pct) {
+                    Some(report) if !report.outliers.is_empty() => {
+                        tracing::warn!(
+                            "Price consensus: {:?} diverged >{:.2}% from the {}-source median (${:.2})",
+                            report.outliers, strat_config.consensus_max_deviation_pct,
+                            report.agreeing_sources, report.consensus_price,
+                        );
+                    }
+                    Some(_) => {}
+                    None => {
+                        tracing::warn!(
+                            "Price consensus check failed across {} sources (max deviation {:.2}%), skipping tick",
+                            sources.len(), strat_config.consensus_max_deviation_pct,
+                        );
+                        continue;
+                    }
+                }
+            }
+        }
+
         let current_btc = if let Some(p) = rtds_price {
             p
         } else if let Some(p) = ws_price {
@@ -523,9 +984,137 @@ async fn main() -> Result<()> {
 
         window_ticks.tick(current_btc, now * 1000);
 
+        // Sortie anticipée ATR (stop-loss / take-profit) sur la position en cours.
+        // Pas encore de sell order early — on loggue le signal pour l'instant.
+        if let Some(ref bet) = pending_bet {
+            if !exited_this_window && strat_config.exit_stop_atr_mult > 0.0 {
+                let atr = vol_tracker.atr(strat_config.atr_window);
+                let position = exit::OpenPosition {
+                    side: bet.side,
+                    entry_price: bet.start_price,
+                    entry_ts_ms: bet.entry_ts_ms,
+                };
+                let tp_mult = adaptive_tp.tp_multiplier(strat_config.exit_tp_atr_mult);
+                if let Some(exit_signal) = exit::evaluate_exit(
+                    &position, &window_ticks, atr, strat_config.exit_stop_atr_mult, tp_mult,
+                ) {
+                    tracing::info!(
+                        "EARLY EXIT [{exit_signal:?}]: entry=${:.2} current=${:.2} ATR=${:.2}",
+                        bet.start_price, current_btc, atr,
+                    );
+                    exited_this_window = true;
+                }
+            }
+        }
+
+        // Mark-price exits on the token's own best_bid (not the BTC underlying):
+        // the microstructure protective exit, the ROI-table / single-rate
+        // trailing stop, and the staged trailing stop share one book fetch
+        // per tick and, unlike the ATR check above, actually bank the gain
+        // via a closing FOK sell rather than just logging. Checked in order
+        // of increasing hold time (protective stop first, ROI/staged
+        // take-profit after) so a single signal per tick drives the exit.
+        if !exited_this_window && pending_bet.is_some() {
+            let token_id = pending_bet.as_ref().unwrap().token_id.clone();
+            let sell_price = match poly {
+                Some(ref poly) => poly.get_book(&token_id).await.ok().map(|b| b.best_bid),
+                None => None,
+            };
+            if let Some(sell_price) = sell_price.filter(|p| *p > 0.0) {
+                let bet = pending_bet.as_mut().unwrap();
+                // High-water mark on the held token's own price, ratcheting up only —
+                // feeds the microstructure trailing take-profit, the ROI-table's
+                // trailing_stop_pct, and the staged trailing stop below alike.
+                if bet.peak_favorable == 0.0 {
+                    bet.peak_favorable = bet.entry_price;
+                }
+                bet.peak_favorable = sell_price.max(bet.peak_favorable);
+
+                let mut signal = None;
+                let mut label = "";
+
+                if signal.is_none() {
+                    let mut position = strategy::Position::new(polymarket::Side::Buy, 1.0, bet.entry_price, 0.0);
+                    position.update_high_water(bet.peak_favorable);
+                    let ctx = strategy::TradeContext {
+                        start_price: bet.start_price,
+                        chainlink_price: bet.start_price,
+                        exchange_price: None,
+                        rtds_price: None,
+                        market_up_price: sell_price,
+                        seconds_remaining: remaining,
+                        fee_rate: strat_config.fee_rate,
+                        vol_5min_pct: vol_tracker.current_vol(),
+                        spread: 0.0,
+                        book_imbalance: 0.0,
+                        num_ws_sources: u32::from(num_ws),
+                        micro_vol: window_ticks.micro_vol(),
+                        momentum_ratio: window_ticks.momentum_ratio(),
+                        fisher: window_ticks.fisher(strat_config.fisher_window),
+                        fisher_prev: window_ticks.fisher_prev(),
+                        max_drawdown_bps: window_ticks.max_drawdown_bps(),
+                    };
+                    if let Some(sig) = exit::evaluate_microstructure_exit(&position, &ctx, &strat_config) {
+                        signal = Some(sig);
+                        label = "MICROSTRUCTURE EXIT";
+                    }
+                }
+
+                if signal.is_none() {
+                    if let Some(sig) = exit::evaluate_position_exit(
+                        polymarket::Side::Buy, bet.entry_price, sell_price, bet.peak_favorable,
+                        remaining, &strat_config.roi_table, strat_config.trailing_stop_pct, false,
+                    ) {
+                        signal = Some(sig);
+                        label = "ROI EXIT";
+                    }
+                }
+
+                if signal.is_none() && !strat_config.trailing_stages.is_empty() {
+                    let mut trail = exit::StagedTrailingStop {
+                        peak_favorable: bet.peak_favorable,
+                        armed_callback_rate: bet.armed_callback_rate,
+                    };
+                    let sig = trail.update(
+                        polymarket::Side::Buy, bet.entry_price, sell_price, &strat_config.trailing_stages,
+                    );
+                    bet.peak_favorable = trail.peak_favorable;
+                    bet.armed_callback_rate = trail.armed_callback_rate;
+                    if let Some(exit::ExitSignal::TakeProfit) = sig {
+                        signal = Some(exit::ExitSignal::TakeProfit);
+                        label = "TRAILING EXIT";
+                    }
+                }
+
+                if let Some(exit_signal) = signal {
+                    tracing::info!(
+                        "{label} [{exit_signal:?}]: entry=${:.4} peak=${:.4} sell=${:.4}",
+                        bet.entry_price, bet.peak_favorable, sell_price,
+                    );
+                    let bet = pending_bet.take().unwrap();
+                    let (bet, filled) = execute_closing_sell(&poly, bet, sell_price, dry_run, label).await;
+                    if filled {
+                        resolve_early_exit(
+                            bet, sell_price, now, current_window, price_source,
+                            &mut session, &mut csv, &db_logger, &notifier, &mut strat_config, &mut calibrator, &metrics,
+                        );
+                        exited_this_window = true;
+                    } else {
+                        pending_bet = Some(bet);
+                    }
+                }
+            }
+        }
+
         if let Some(ref mut tl) = tick_csv {
             tl.log_tick(now * 1000, price_source, current_btc, current_window);
         }
+        if let Some(ref dl) = db_logger {
+            dl.log_tick(
+                now * 1000, price_source, current_btc, current_window,
+                window_ticks.micro_vol(), window_ticks.momentum_ratio(), window_ticks.sign_changes(),
+            );
+        }
 
         // Nouvel intervalle 5min — résoudre le bet précédent
         if window != current_window {
@@ -534,16 +1123,23 @@ async fn main() -> Result<()> {
                 if let Some(ref mut csv) = csv {
                     csv.log_skip(now, current_window, start_price, current_btc, last_mid, num_ws, prev_price_source, vol_tracker.current_vol(), &macro_ctx, &skip_reason);
                 }
+                if let Some(ref dl) = db_logger {
+                    dl.log_skip(current_window, now, &skip_reason, prev_price_source);
+                }
             }
 
             if let Some(bet) = pending_bet.take() {
-                resolve_pending_bet(bet, current_btc, now, current_window,
-                    &mut session, &mut csv, &mut strat_config, &mut calibrator);
+                resolve_pending_bet(bet, current_btc, now, current_window, prev_price_source,
+                    &mut session, &mut csv, &db_logger, &notifier, &mut strat_config, &mut calibrator, &metrics);
             }
 
             // Enregistrer le mouvement de l'intervalle précédent pour la vol dynamique
             if current_window > 0 && start_price > 0.0 {
                 vol_tracker.record_move(start_price, current_btc);
+                if let Some((high, low)) = window_ticks.high_low() {
+                    vol_tracker.record_range(high, low);
+                }
+                adaptive_tp.record(session.profit_factor());
                 if let Some(ref mut oc) = outcome_csv {
                     oc.log_outcome(
                         current_window, start_price, current_btc,
@@ -554,11 +1150,15 @@ async fn main() -> Result<()> {
                         reversal_detected_this_window,
                     );
                 }
+                if let Some(ref dl) = db_logger {
+                    dl.log_outcome(current_window, start_price, current_btc, prev_price_source);
+                }
             }
 
             current_window = window;
             traded_this_window = false;
             reversal_detected_this_window = false;
+            exited_this_window = false;
             start_price = current_btc;
             window_ticks.clear();
             mid_history.clear();
@@ -584,11 +1184,23 @@ async fn main() -> Result<()> {
             tracing::info!("--- Nouvel intervalle 5min (window={window}) | BTC: ${:.2} ({src}, {num_ws} src) | vol: {:.3}% | 1h: {:.2}% | 24h: {:.2}% | fund: {:.6} ---",
                 start_price, vol_tracker.current_vol(), macro_ctx.btc_1h_pct, macro_ctx.btc_24h_pct, macro_ctx.funding_rate);
 
-            if session.pnl_usdc >= strat_config.session_profit_target_usdc
-                || session.pnl_usdc <= -strat_config.session_loss_limit_usdc
-            {
-                tracing::info!("Session limit atteint (PnL: ${:.2}). Arrêt.", session.pnl_usdc);
-                break;
+            if session.session_limit_hit(&strat_config) {
+                notifier.send(notify::NotifyEvent::SessionLimitReached {
+                    session_pnl_usdc: session.pnl_usdc,
+                    target_usdc: strat_config.session_profit_target_usdc,
+                    limit_usdc: strat_config.session_loss_limit_usdc,
+                });
+                if config.schedule.session_cooldown_s > 0 {
+                    tracing::info!(
+                        "Session limit atteint (PnL: ${:.2}). Cooldown {}s avant reprise.",
+                        session.pnl_usdc, config.schedule.session_cooldown_s,
+                    );
+                    session.start_session_cooldown(now, config.schedule.session_cooldown_s);
+                    save_session(&session);
+                } else {
+                    tracing::info!("Session limit atteint (PnL: ${:.2}). Arrêt.", session.pnl_usdc);
+                    break;
+                }
             }
             continue;
         }
@@ -601,6 +1213,27 @@ async fn main() -> Result<()> {
         let standard_window = remaining <= strat_config.entry_seconds_before_end;
         if !extreme_window && !standard_window { continue; }
 
+        // Scheduled trading windows — skip entries outside allowed UTC hours/days.
+        if !is_within_schedule(now, &config.schedule) {
+            if skip_reason == "no_entry" {
+                skip_reason = String::from("off_schedule");
+            }
+            continue;
+        }
+
+        // Session cooldown (auto-resume path for the session PnL limit) —
+        // once it expires in an allowed window, reset and resume trading.
+        if session.is_session_cooldown(now) {
+            if skip_reason == "no_entry" {
+                skip_reason = String::from("session_cooldown");
+            }
+            continue;
+        } else if session.session_cooldown_until > 0 {
+            tracing::info!("Cooldown de session terminé — reprise du trading.");
+            session.resume_after_cooldown();
+            save_session(&session);
+        }
+
         // Circuit breaker — skip trading during cooldown
         if session.is_circuit_broken(now) {
             if skip_reason == "no_entry" {
@@ -669,6 +1302,19 @@ async fn main() -> Result<()> {
             10_000
         };
 
+        metrics.set_live(last_tick_age_ms, num_ws, spread_book.spread, spread_book.imbalance);
+        if let Some(ef) = exchange_feed.as_ref() {
+            metrics.set_exchange_health(&ef.source_status(), ws_agg.map_or(0.0, |a| a.median_price));
+        }
+        if let Some(rf) = rtds_feed.as_ref() {
+            metrics.set_rtds_status(rf.status());
+        }
+        metrics.set_calibration(strat_config.vol_confidence_multiplier, calibrator.brier_score());
+        metrics.set_session(
+            session.pnl_usdc, session.trades, session.win_rate() * 100.0,
+            session.consecutive_wins, session.consecutive_losses, session.session_drawdown_pct(),
+        );
+
         // --- Determine which signal to use ---
         let mut signal: Option<strategy::Signal> = None;
         let mut is_extreme_trade = false;
@@ -676,9 +1322,17 @@ async fn main() -> Result<()> {
         // Standard evaluation (only during standard window)
         if standard_window {
             let is_maker = order_type == "GTC";
+            // Haircut the reference price against the move's own direction before
+            // it feeds the entry decision, widening margins in volatile regimes.
+            let ref_price = price_source::SourcePrice {
+                price_usd: cl_price.unwrap_or(current_btc),
+                updated_at_ms: now * 1000,
+            };
+            let (ref_bid, ref_ask) = ref_price.bid_ask(quote_spread);
+            let haircut_chainlink_price = if ref_price.price_usd >= start_price { ref_bid } else { ref_ask };
             let ctx = strategy::TradeContext {
                 start_price,
-                chainlink_price: cl_price.unwrap_or(current_btc),
+                chainlink_price: haircut_chainlink_price,
                 exchange_price: ws_price,
                 rtds_price,
                 market_up_price,
@@ -690,6 +1344,9 @@ async fn main() -> Result<()> {
                 num_ws_sources: u32::from(num_ws),
                 micro_vol: window_ticks.micro_vol(),
                 momentum_ratio: window_ticks.momentum_ratio(),
+                fisher: window_ticks.fisher(strat_config.fisher_window),
+                fisher_prev: window_ticks.fisher_prev(),
+                max_drawdown_bps: window_ticks.max_drawdown_bps(),
                 is_maker,
                 last_tick_age_ms,
             };
@@ -737,6 +1394,38 @@ async fn main() -> Result<()> {
             }
         };
 
+        // Run the single live signal through `portfolio::allocate` with a
+        // trivial 1x1 correlation matrix. With only one market traded per
+        // window this loop can't yet exercise the correlation-shrinkage half
+        // of `allocate` (that needs a second simultaneous candidate) — but
+        // the aggregate-ceiling half still applies for real, clamping
+        // `size_usdc` to what's left of `session_loss_limit_usdc` against
+        // the *current* bankroll, which `fractional_kelly`'s `max_bet_usdc`
+        // cap alone doesn't guarantee.
+        let signal = match portfolio::allocate(&[signal], &[vec![1.0]], &session, &strat_config) {
+            Ok(sizes) => strategy::Signal { size_usdc: sizes[0], ..signal },
+            Err(e) => {
+                tracing::warn!("portfolio::allocate failed, using unshrunk signal size: {e}");
+                signal
+            }
+        };
+
+        // Daily fee/volume budget — refuse new entries once either cap would
+        // be exceeded, independent of the per-trade sizing logic above.
+        let projected_fee_usdc = signal.size_usdc * signal.fee_pct / 100.0;
+        if strat_config.daily_fee_budget > 0.0
+            && daily_budget.accumulated_fees_usdc + projected_fee_usdc > strat_config.daily_fee_budget
+        {
+            skip_reason = String::from("daily_fee_budget");
+            continue;
+        }
+        if strat_config.daily_max_volume > 0.0
+            && daily_budget.accumulated_volume_usdc + signal.size_usdc > strat_config.daily_max_volume
+        {
+            skip_reason = String::from("daily_volume_cap");
+            continue;
+        }
+
         let (token_id, token_label) = if signal.side == polymarket::Side::Buy {
             (&market_data.market.token_id_yes, "YES")
         } else {
@@ -755,9 +1444,10 @@ async fn main() -> Result<()> {
         // Extreme trades always use FOK (time-sensitive reversal)
         let effective_order_type = if is_extreme_trade { "FOK" } else { &order_type };
 
-        // Maker pricing for GTC: bid + 25% of spread (better than best_ask)
+        // Maker pricing for GTC/HYBRID: bid + 25% of spread (better than best_ask)
         // Taker (FOK): use best_ask as usual
-        let entry_price = if effective_order_type == "GTC" && book.best_bid > 0.0 && book.best_ask > 0.0 {
+        let effective_order_type_is_maker = effective_order_type == "GTC" || effective_order_type == "HYBRID";
+        let entry_price = if effective_order_type_is_maker && book.best_bid > 0.0 && book.best_ask > 0.0 {
             let spread = book.best_ask - book.best_bid;
             if spread >= 0.02 {
                 let maker_price = book.best_bid + spread * 0.25;
@@ -782,6 +1472,8 @@ async fn main() -> Result<()> {
         // Execute order first, then log with actual latency and fill_type
         let order_start = Instant::now();
         let (order_ok, fill_type) = if dry_run {
+            daily_budget.record_fill(signal.size_usdc, signal.size_usdc * signal.fee_pct / 100.0);
+            save_daily_budget(&daily_budget);
             pending_bet = Some(PendingBet {
                 start_price,
                 side: signal.side,
@@ -790,30 +1482,44 @@ async fn main() -> Result<()> {
                 fee_pct: signal.fee_pct,
                 implied_p_up: signal.implied_p_up,
                 is_extreme: is_extreme_trade,
+                entry_ts_ms: now * 1000,
+                token_id: token_id.clone(),
+                fee_rate_bps,
+                peak_favorable: 0.0,
+                armed_callback_rate: None,
             });
             (true, "dry_run")
         } else if let Some(ref poly) = poly {
-            if let Some(bet) = execute_order(
-                poly, token_id, &signal, entry_price, start_price,
+            if let Some((bet, ft)) = execute_order(
+                poly, token_id, &signal, entry_price, book.best_ask, start_price,
                 fee_rate_bps, effective_order_type, maker_timeout_s,
-                is_extreme_trade,
+                hybrid_escalation_s, remaining, is_extreme_trade, &mut daily_budget,
             ).await {
                 pending_bet = Some(bet);
-                let ft = if effective_order_type == "GTC" { "GTC_filled" } else { "FOK_filled" };
                 (true, ft)
             } else {
-                let reason = if effective_order_type == "GTC" { "gtc_not_filled" } else { "fok_rejected" };
+                let reason = match effective_order_type {
+                    "GTC" => "gtc_not_filled",
+                    "HYBRID" => "hybrid_rejected",
+                    _ => "fok_rejected",
+                };
                 tracing::warn!("Ordre {reason} — loggé comme skip");
                 if let Some(ref mut csv) = csv {
                     csv.log_skip(now, current_window, start_price, current_btc,
                         market_up_price, num_ws, price_source, vol_tracker.current_vol(), &macro_ctx, reason);
                 }
+                if let Some(ref dl) = db_logger {
+                    dl.log_skip(current_window, now, reason, price_source);
+                }
                 (false, "rejected")
             }
         } else {
             (false, "no_client")
         };
         let order_latency_ms = order_start.elapsed().as_millis() as u64;
+        if fill_type != "dry_run" && fill_type != "no_client" {
+            metrics.record_order(order_latency_ms, fill_type);
+        }
 
         // Only log trade row when order actually succeeded (no phantom rows)
         if order_ok {
@@ -835,6 +1541,17 @@ async fn main() -> Result<()> {
                     session.consecutive_wins, session.session_drawdown_pct(),
                 );
             }
+            if let Some(ref dl) = db_logger {
+                dl.log_trade(current_window, now, side_label, signal.edge_pct, signal.size_usdc, entry_price, price_source);
+            }
+            notifier.send(notify::NotifyEvent::Fill {
+                window: current_window,
+                btc_price: current_btc,
+                side: side_label.to_string(),
+                edge_pct: signal.edge_pct,
+                size_usdc: signal.size_usdc,
+                session_pnl_usdc: session.pnl_usdc,
+            });
         }
         traded_this_window = true;
     }
@@ -846,6 +1563,10 @@ async fn main() -> Result<()> {
         }
     }
 
+    if let Some(agg) = candle_aggregator.as_mut() {
+        agg.flush();
+    }
+
     // Résumé de session
     tracing::info!("=== SESSION TERMINÉE ===");
     tracing::info!("Trades: {} | Wins: {} | WR: {:.0}% | PnL: ${:.2}",
@@ -864,6 +1585,27 @@ struct PendingBet {
     implied_p_up: f64,
     #[serde(default)]
     is_extreme: bool,
+    /// Horodatage (ms) de l'entrée, pour ancrer le trailing stop ATR.
+    #[serde(default)]
+    entry_ts_ms: u64,
+    /// Token of the held outcome, needed to fetch its own book and place the
+    /// closing sell order for the mark-price exits below.
+    #[serde(default)]
+    token_id: String,
+    /// Fee rate used at entry, reused for the closing sell order so its
+    /// signature matches the rate actually quoted for this token.
+    #[serde(default)]
+    fee_rate_bps: u32,
+    /// High-water mark on the held token's own sell price, persisted so a
+    /// restart mid-position doesn't forget the peak. Shared by
+    /// `exit::evaluate_microstructure_exit`'s trailing take-profit,
+    /// `exit::evaluate_position_exit`'s `trailing_stop_pct`, and
+    /// `exit::StagedTrailingStop` below.
+    #[serde(default)]
+    peak_favorable: f64,
+    /// `exit::StagedTrailingStop` armed-stage state.
+    #[serde(default)]
+    armed_callback_rate: Option<f64>,
 }
 
 const PENDING_BET_PATH: &str = "pending_bet.json";
@@ -893,6 +1635,117 @@ fn save_pending_bet(bet: &PendingBet) {
     }
 }
 
+const ORDER_LIFECYCLE_PATH: &str = "order_lifecycle.json";
+
+/// Where a resting GTC maker order sits between `execute_order` placing it and
+/// resolving into either a `PendingBet` (matched) or nothing (cancelled).
+/// Only `Placed` is ever actually written to disk — `Matched`/`Cancelled`
+/// are what `reconcile_order_lifecycle` turns a stale `Placed` record into
+/// on startup, `Resolved` meaning it has already flowed into the normal
+/// `PendingBet`/`pending_bet.json` path and needs no further tracking here.
+#[derive(Debug, Serialize, Deserialize)]
+enum OrderLifecycleState {
+    Placed,
+    Matched,
+    Cancelled,
+    Resolved,
+}
+
+/// Written to disk the moment `place_limit_order` returns an
+/// `order_id`, before we know whether it fills — so a crash during
+/// `execute_order`'s `maker_timeout_s` sleep leaves behind a record of the
+/// still-open order instead of silently forgetting it the way
+/// `pending_bet.json` alone would (that file is only written once a fill is
+/// already confirmed).
+#[derive(Serialize, Deserialize)]
+struct OrderLifecycle {
+    order_id: String,
+    token_id: String,
+    side: polymarket::Side,
+    size_usdc: f64,
+    entry_price: f64,
+    fee_pct: f64,
+    fee_rate_bps: u32,
+    implied_p_up: f64,
+    start_price: f64,
+    is_extreme: bool,
+    state: OrderLifecycleState,
+}
+
+fn save_order_lifecycle(rec: &OrderLifecycle) {
+    match serde_json::to_string(rec) {
+        Ok(json) => {
+            if let Err(e) = std::fs::write(ORDER_LIFECYCLE_PATH, &json) {
+                tracing::error!("Failed to save order lifecycle record: {e}");
+            }
+        }
+        Err(e) => tracing::error!("Failed to serialize order lifecycle record: {e}"),
+    }
+}
+
+fn load_order_lifecycle() -> Option<OrderLifecycle> {
+    let content = std::fs::read_to_string(ORDER_LIFECYCLE_PATH).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn clear_order_lifecycle() {
+    if let Err(e) = std::fs::remove_file(ORDER_LIFECYCLE_PATH) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            tracing::warn!("Failed to remove {ORDER_LIFECYCLE_PATH}: {e}");
+        }
+    }
+}
+
+/// Startup reconciliation: if `order_lifecycle.json` still says `Placed`, the
+/// process died between `execute_order` placing a GTC order and finding out
+/// whether it filled. Ask Polymarket directly via `get_order_status` rather
+/// than trusting the stale on-disk guess — cancel it if it's still open
+/// (guaranteeing no dangling maker order survives the restart) or promote it
+/// into a `PendingBet` if it matched while we were down. Either way the
+/// record is cleared so it's never reconciled twice.
+async fn reconcile_order_lifecycle(poly: &polymarket::PolymarketClient) -> Option<PendingBet> {
+    let rec = load_order_lifecycle()?;
+    clear_order_lifecycle();
+
+    tracing::warn!(
+        "Found orphaned order {} (state: Placed) from a previous run — reconciling",
+        rec.order_id,
+    );
+    match poly.get_order_status(&rec.order_id).await {
+        Ok(status) if status == "matched" => {
+            tracing::info!("Orphaned order {} matched while we were down — recovering as PendingBet", rec.order_id);
+            Some(PendingBet {
+                start_price: rec.start_price,
+                side: rec.side,
+                size_usdc: rec.size_usdc,
+                entry_price: rec.entry_price,
+                fee_pct: rec.fee_pct,
+                implied_p_up: rec.implied_p_up,
+                is_extreme: rec.is_extreme,
+                entry_ts_ms: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64,
+                token_id: rec.token_id,
+                fee_rate_bps: rec.fee_rate_bps,
+                peak_favorable: 0.0,
+                armed_callback_rate: None,
+            })
+        }
+        Ok(status) => {
+            tracing::info!("Orphaned order {} still {status} — cancelling", rec.order_id);
+            if let Err(e) = poly.cancel_order(&rec.order_id).await {
+                tracing::warn!("Failed to cancel orphaned order {}: {e:#}", rec.order_id);
+            }
+            None
+        }
+        Err(e) => {
+            tracing::warn!(
+                "Could not query status of orphaned order {}: {e:#} — leaving it for Polymarket's own expiry",
+                rec.order_id,
+            );
+            None
+        }
+    }
+}
+
 fn load_session(initial_bankroll: f64) -> strategy::Session {
     match std::fs::read_to_string(SESSION_PATH) {
         Ok(content) => {
@@ -925,20 +1778,206 @@ fn save_session(session: &strategy::Session) {
     }
 }
 
+const DAILY_BUDGET_PATH: &str = "daily_budget.json";
+
+/// Cumulative taker fees/notional spent today, gating new entries once
+/// `strategy::StrategyConfig::daily_fee_budget`/`daily_max_volume` is
+/// exceeded — a noisy day can quietly burn the edge via fees even when
+/// every trade is individually well-sized. Resets at UTC midnight rather
+/// than on a rolling 24h window, so it tracks the same day boundary as
+/// `logger::CsvLogger`'s `hour_utc`/`day_of_week` columns.
+#[derive(Serialize, Deserialize)]
+struct DailyBudget {
+    accumulated_fees_usdc: f64,
+    accumulated_volume_usdc: f64,
+    started_at: u64,
+}
+
+impl DailyBudget {
+    fn new(now: u64) -> Self {
+        Self { accumulated_fees_usdc: 0.0, accumulated_volume_usdc: 0.0, started_at: now }
+    }
+
+    /// True once `now` has crossed into a different UTC day than `started_at`.
+    fn is_over_24h(&self, now: u64) -> bool {
+        now / 86400 != self.started_at / 86400
+    }
+
+    /// Resets the accumulators if `now` has crossed the UTC day boundary.
+    fn reset_if_new_day(&mut self, now: u64) {
+        if self.is_over_24h(now) {
+            *self = Self::new(now);
+        }
+    }
+
+    fn record_fill(&mut self, size_usdc: f64, fee_usdc: f64) {
+        self.accumulated_volume_usdc += size_usdc;
+        self.accumulated_fees_usdc += fee_usdc;
+    }
+}
+
+fn load_daily_budget(now: u64) -> DailyBudget {
+    match std::fs::read_to_string(DAILY_BUDGET_PATH) {
+        Ok(content) => match serde_json::from_str::<DailyBudget>(&content) {
+            Ok(mut budget) => {
+                budget.reset_if_new_day(now);
+                budget
+            }
+            Err(e) => {
+                tracing::warn!("Failed to parse {DAILY_BUDGET_PATH}: {e} — starting fresh");
+                DailyBudget::new(now)
+            }
+        },
+        Err(_) => DailyBudget::new(now),
+    }
+}
+
+fn save_daily_budget(budget: &DailyBudget) {
+    match serde_json::to_string(budget) {
+        Ok(json) => {
+            if let Err(e) = std::fs::write(DAILY_BUDGET_PATH, &json) {
+                tracing::error!("Failed to save daily budget: {e}");
+            }
+        }
+        Err(e) => tracing::error!("Failed to serialize daily budget: {e}"),
+    }
+}
+
 /// Resolve whether the 5-min window outcome is UP.
 /// Polymarket rule: end_price >= start_price → UP wins (equality = UP).
+/// Compared through `fixedpoint::Money` rather than raw `f64` so this is
+/// the same decimal-exact comparison `compute_pnl` settles against, not a
+/// second, independently-rounded notion of equality.
 fn resolve_up(start_price: f64, end_price: f64) -> bool {
-    end_price >= start_price
+    fixedpoint::Money::from_f64(end_price) >= fixedpoint::Money::from_f64(start_price)
+}
+
+/// UTC (hour-of-day, weekday) for a unix timestamp, weekday 0=Sunday..6=Saturday.
+/// No `chrono` dependency needed: Jan 1 1970 was a Thursday (weekday 4).
+fn utc_hour_and_weekday(now: u64) -> (u8, u8) {
+    let days_since_epoch = now / 86_400;
+    let hour = (now % 86_400) / 3_600;
+    let weekday = (days_since_epoch + 4) % 7;
+    (hour as u8, weekday as u8)
+}
+
+/// Returns true if `now` falls within the `[schedule]` config's allowed UTC
+/// hours/weekdays. An empty allow-list means "no restriction" for that axis.
+fn is_within_schedule(now: u64, schedule: &ScheduleConfig) -> bool {
+    if !schedule.enabled {
+        return true;
+    }
+    let (hour, weekday) = utc_hour_and_weekday(now);
+    let hour_ok = schedule.allowed_hours_utc.is_empty() || schedule.allowed_hours_utc.contains(&hour);
+    let weekday_ok = schedule.allowed_weekdays_utc.is_empty() || schedule.allowed_weekdays_utc.contains(&weekday);
+    hour_ok && weekday_ok
+}
+
+/// Why `compute_pnl` refused to settle a bet. The unguarded `1.0 / price`
+/// this replaces is the same kind of footgun as an unchecked multiply that
+/// silently scales a result wrong — except here a bad input (zero, or a
+/// price outside the `(0, 1]` a CLOB share price must live in) produces
+/// inf/NaN instead of a merely-wrong number.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PnlError {
+    ZeroPrice,
+    PriceOutOfRange(f64),
+    NegativeSize(f64),
+    Overflow,
+}
+
+impl std::fmt::Display for PnlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PnlError::ZeroPrice => write!(f, "price is zero, refusing to divide"),
+            PnlError::PriceOutOfRange(price) => write!(f, "price {price} is outside (0.0, 1.0]"),
+            PnlError::NegativeSize(size) => write!(f, "size {size} is negative"),
+            PnlError::Overflow => write!(f, "pnl computation overflowed"),
+        }
+    }
+}
+
+impl std::error::Error for PnlError {}
+
+/// How the taker fee for a trade is computed from its size and price.
+/// Polymarket-style prediction markets don't actually charge a flat
+/// percentage: the real curve is symmetric around 0.5 and taxes a
+/// near-certain trade (price near 0 or 1) less than a 50/50 one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FeeModel {
+    /// Flat percentage of `size`, independent of price.
+    Flat(f64),
+    /// GTC maker fills: no taker fee.
+    ZeroMaker,
+    /// `base_rate * size * min(price, 1.0 - price)`.
+    Symmetric { base_rate: f64 },
+}
+
+impl FeeModel {
+    /// Fee cost for a trade of `size` at `price`, both already `Money` so
+    /// this composes exactly with `compute_pnl`'s own arithmetic.
+    fn fee(self, size: fixedpoint::Money, price: fixedpoint::Money) -> Option<fixedpoint::Money> {
+        use fixedpoint::Money;
+        match self {
+            FeeModel::Flat(fee_pct) => size.checked_mul(Money::from_f64(fee_pct))?
+                .checked_div(Money::from_f64(100.0)),
+            FeeModel::ZeroMaker => Some(Money::ZERO),
+            FeeModel::Symmetric { base_rate } => {
+                let complement = Money::ONE.checked_sub(price)?;
+                let taxed_price = price.min(complement);
+                size.checked_mul(Money::from_f64(base_rate))?.checked_mul(taxed_price)
+            }
+        }
+    }
 }
 
 /// Compute PnL for a resolved bet. Taker fee is paid at entry regardless of outcome.
-fn compute_pnl(won: bool, size: f64, price: f64, fee_pct: f64) -> f64 {
-    let fee_cost = size * fee_pct / 100.0;
-    if won {
-        size * (1.0 / price - 1.0) - fee_cost
-    } else {
-        -size - fee_cost
+/// Routed through `fixedpoint::Money` rather than raw `f64`: a settlement
+/// ledger needs replaying the same (won, size, price, fee_model) to always
+/// produce the exact same number, not one that drifts with f64's
+/// order-of-operations-dependent rounding. Rejects the inputs whose
+/// unguarded arithmetic would otherwise produce inf/NaN rather than a
+/// merely-wrong PnL.
+fn compute_pnl(won: bool, size: f64, price: f64, fee_model: FeeModel) -> Result<f64, PnlError> {
+    use fixedpoint::Money;
+    if price <= 0.0 {
+        return Err(PnlError::ZeroPrice);
+    }
+    if price > 1.0 {
+        return Err(PnlError::PriceOutOfRange(price));
     }
+    if size < 0.0 {
+        return Err(PnlError::NegativeSize(size));
+    }
+    let size = Money::from_f64(size);
+    let price = Money::from_f64(price);
+    let fee_cost = fee_model.fee(size, price).ok_or(PnlError::Overflow)?;
+    let pnl = if won {
+        let inv_price = Money::ONE.checked_div(price).ok_or(PnlError::ZeroPrice)?;
+        size.checked_mul(inv_price.checked_sub(Money::ONE).ok_or(PnlError::Overflow)?).ok_or(PnlError::Overflow)?
+            .checked_sub(fee_cost).ok_or(PnlError::Overflow)?
+    } else {
+        (-size).checked_sub(fee_cost).ok_or(PnlError::Overflow)?
+    };
+    Ok(pnl.to_f64())
+}
+
+/// PnL for a position closed early by one of the mark-price exits (see
+/// `resolve_early_exit`) instead of held to `resolve_up`: the outcome never resolves, so PnL is simply
+/// the token's own price change. Unlike `compute_pnl`, the taker fee is paid on
+/// both legs since closing early means an extra sell order on top of the entry.
+/// Same `Money`-routed arithmetic as `compute_pnl`, for the same reason.
+fn compute_pnl_early_exit(size: f64, entry_price: f64, exit_price: f64, fee_pct: f64) -> f64 {
+    use fixedpoint::Money;
+    let size = Money::from_f64(size);
+    let entry_price = Money::from_f64(entry_price);
+    let exit_price = Money::from_f64(exit_price);
+    let fee_pct = Money::from_f64(fee_pct);
+    let fee_cost = size.checked_mul(fee_pct).unwrap_or(Money::ZERO)
+        .checked_div(Money::from_f64(100.0)).unwrap_or(Money::ZERO)
+        .checked_mul(Money::from_f64(2.0)).unwrap_or(Money::ZERO);
+    let price_ratio = exit_price.checked_div(entry_price).unwrap_or(Money::ZERO);
+    (size.checked_mul(price_ratio - Money::ONE).unwrap_or(Money::ZERO) - fee_cost).to_f64()
 }
 
 /// Infer why evaluate() returned None (mirrors evaluate() filter order for CSV logging).
@@ -988,36 +2027,75 @@ fn infer_skip_reason(
     }
 }
 
-/// Execute a FOK or GTC order via the Polymarket API.
-/// Returns Some(PendingBet) if the order was filled, None if it failed or wasn't filled.
+/// Execute a FOK, GTC, or HYBRID order via the Polymarket API.
+///
+/// HYBRID places the passive GTC maker order first; if it's still unfilled
+/// once `seconds_remaining` drops under `hybrid_escalation_s` (or
+/// `maker_timeout_s` elapses, whichever comes first), the maker order is
+/// cancelled and immediately re-submitted as a taker FOK at `best_ask` —
+/// capturing the maker rebate when the book cooperates while still
+/// guaranteeing entry before the window closes.
+///
+/// Returns `Some((PendingBet, fill_type))` if an order was filled, `None`
+/// if every leg failed or went unfilled.
 #[allow(clippy::too_many_arguments)]
 async fn execute_order(
     poly: &polymarket::PolymarketClient,
     token_id: &str,
     signal: &strategy::Signal,
     entry_price: f64,
+    best_ask: f64,
     start_price: f64,
     fee_rate_bps: u32,
     order_type: &str,
     maker_timeout_s: u64,
+    hybrid_escalation_s: u64,
+    seconds_remaining: u64,
     is_extreme: bool,
-) -> Option<PendingBet> {
+    daily_budget: &mut DailyBudget,
+) -> Option<(PendingBet, &'static str)> {
     let order_t = Instant::now();
-    let mut gtc_immediate_fill = false;
+    let mut pays_taker_fee = false;
+    let mut fill_price = entry_price;
 
-    let order_result = if order_type == "GTC" {
+    let order_result: Option<(polymarket::OrderResult, &'static str)> = if order_type == "GTC" || order_type == "HYBRID" {
         match poly.place_limit_order(token_id, polymarket::Side::Buy, signal.size_usdc, entry_price, fee_rate_bps).await {
             Ok(result) => {
                 let order_ms = order_t.elapsed().as_millis();
                 tracing::info!("[MAKER] Ordre GTC placé: {} en {}ms", result.order_id, order_ms);
+                // Persisted before the maker-wait sleep below so a crash
+                // mid-sleep leaves a reconcilable record instead of an order
+                // Polymarket knows about but we've forgotten (see
+                // reconcile_order_lifecycle).
+                save_order_lifecycle(&OrderLifecycle {
+                    order_id: result.order_id.clone(),
+                    token_id: token_id.to_string(),
+                    side: signal.side,
+                    size_usdc: signal.size_usdc,
+                    entry_price,
+                    fee_pct: signal.fee_pct,
+                    fee_rate_bps,
+                    implied_p_up: signal.implied_p_up,
+                    start_price,
+                    is_extreme,
+                    state: OrderLifecycleState::Placed,
+                });
                 if result.status == "matched" {
-                    gtc_immediate_fill = true;
-                    Some(result)
+                    pays_taker_fee = true;
+                    clear_order_lifecycle();
+                    Some((result, "GTC_filled"))
                 } else {
-                    tokio::time::sleep(Duration::from_secs(maker_timeout_s)).await;
+                    // HYBRID caps the maker wait so escalation still leaves
+                    // enough of the window to place and settle the taker leg.
+                    let wait_s = if order_type == "HYBRID" {
+                        seconds_remaining.saturating_sub(hybrid_escalation_s).min(maker_timeout_s)
+                    } else {
+                        maker_timeout_s
+                    };
+                    tokio::time::sleep(Duration::from_secs(wait_s)).await;
                     let filled = match poly.get_order_status(&result.order_id).await {
                         Ok(status) => {
-                            tracing::info!("[MAKER] Order {} status after {}s: {}", result.order_id, maker_timeout_s, status);
+                            tracing::info!("[MAKER] Order {} status after {}s: {}", result.order_id, wait_s, status);
                             status == "matched"
                         }
                         Err(e) => {
@@ -1026,13 +2104,39 @@ async fn execute_order(
                         }
                     };
                     if filled {
-                        Some(result)
+                        clear_order_lifecycle();
+                        Some((result, "GTC_filled"))
                     } else {
                         tracing::info!("[MAKER] Not filled — cancelling {}", result.order_id);
                         if let Err(e) = poly.cancel_order(&result.order_id).await {
                             tracing::warn!("[MAKER] Cancel failed: {e:#}");
                         }
-                        None
+                        clear_order_lifecycle();
+                        if order_type == "HYBRID" {
+                            tracing::info!(
+                                "[HYBRID] Escalating to taker FOK @ {:.4} with {}s remaining",
+                                best_ask, seconds_remaining,
+                            );
+                            match poly.place_order(token_id, polymarket::Side::Buy, signal.size_usdc, best_ask, fee_rate_bps).await {
+                                Ok(fok_result) => {
+                                    let fok_ms = order_t.elapsed().as_millis();
+                                    tracing::info!("[HYBRID] Ordre FOK: {} (status: {}) en {}ms", fok_result.order_id, fok_result.status, fok_ms);
+                                    if fok_result.status == "matched" {
+                                        pays_taker_fee = true;
+                                        fill_price = best_ask;
+                                        Some((fok_result, "HYBRID_escalated_filled"))
+                                    } else {
+                                        None
+                                    }
+                                }
+                                Err(e) => {
+                                    tracing::error!("[HYBRID] Erreur ordre FOK d'escalade: {e:#}");
+                                    None
+                                }
+                            }
+                        } else {
+                            None
+                        }
                     }
                 }
             }
@@ -1046,7 +2150,12 @@ async fn execute_order(
             Ok(result) => {
                 let order_ms = order_t.elapsed().as_millis();
                 tracing::info!("Ordre FOK: {} (status: {}) en {}ms", result.order_id, result.status, order_ms);
-                if result.status == "matched" { Some(result) } else { None }
+                if result.status == "matched" {
+                    pays_taker_fee = true;
+                    Some((result, "FOK_filled"))
+                } else {
+                    None
+                }
             }
             Err(e) => {
                 tracing::error!("Erreur ordre FOK: {e:#} ({}ms)", order_t.elapsed().as_millis());
@@ -1055,17 +2164,25 @@ async fn execute_order(
         }
     };
 
-    order_result.map(|_| {
-        let pays_taker_fee = order_type != "GTC" || gtc_immediate_fill;
-        PendingBet {
+    order_result.map(|(_, fill_type)| {
+        let fee_usdc = if pays_taker_fee { signal.size_usdc * signal.fee_pct / 100.0 } else { 0.0 };
+        daily_budget.record_fill(signal.size_usdc, fee_usdc);
+        save_daily_budget(daily_budget);
+        let bet = PendingBet {
             start_price,
             side: signal.side,
             size_usdc: signal.size_usdc,
-            entry_price,
+            entry_price: fill_price,
             fee_pct: if pays_taker_fee { signal.fee_pct } else { 0.0 },
             implied_p_up: signal.implied_p_up,
             is_extreme,
-        }
+            entry_ts_ms: SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64,
+            token_id: token_id.to_string(),
+            fee_rate_bps,
+            peak_favorable: 0.0,
+            armed_callback_rate: None,
+        };
+        (bet, fill_type)
     })
 }
 
@@ -1076,45 +2193,186 @@ fn resolve_pending_bet(
     current_btc: f64,
     now: u64,
     current_window: u64,
+    price_source: &str,
     session: &mut strategy::Session,
     csv: &mut Option<logger::CsvLogger>,
+    db_logger: &Option<db::DbLogger>,
+    notifier: &notify::Notifier,
     strat_config: &mut strategy::StrategyConfig,
     calibrator: &mut strategy::Calibrator,
+    metrics: &metrics::Metrics,
 ) {
     let went_up = resolve_up(bet.start_price, current_btc);
     let won = (went_up && bet.side == polymarket::Side::Buy)
         || (!went_up && bet.side != polymarket::Side::Buy);
-    let pnl = compute_pnl(won, bet.size_usdc, bet.entry_price, bet.fee_pct);
+    // `symmetric_fee_model` switches settlement to Polymarket's actual
+    // price-dependent curve instead of the flat `fee_pct` quoted at entry.
+    let fee_model = if strat_config.symmetric_fee_model {
+        FeeModel::Symmetric { base_rate: strat_config.symmetric_fee_base_rate }
+    } else {
+        FeeModel::Flat(bet.fee_pct)
+    };
+    let pnl = match compute_pnl(won, bet.size_usdc, bet.entry_price, fee_model) {
+        Ok(pnl) => pnl,
+        Err(e) => {
+            tracing::error!("compute_pnl failed for bet entered at {}: {e}", bet.entry_price);
+            return;
+        }
+    };
+    let predicted_p = if bet.side == polymarket::Side::Buy { bet.implied_p_up } else { 1.0 - bet.implied_p_up };
+    finalize_resolution(
+        pnl, won, bet.is_extreme, predicted_p, bet.start_price, current_btc,
+        now, current_window, price_source, session, csv, db_logger, notifier, strat_config, calibrator, metrics,
+    );
+}
+
+/// Places the closing FOK sell for any of the mark-price early-exit checks
+/// in the main loop (microstructure, ROI-table/trailing_stop_pct, staged
+/// trailing stop) and reports whether it actually filled. An unfilled FOK
+/// or a failed request means the position is still held, so the caller
+/// should put `bet` back into `pending_bet` and let the next tick retry.
+async fn execute_closing_sell(
+    poly: &Option<polymarket::PolymarketClient>,
+    bet: PendingBet,
+    sell_price: f64,
+    dry_run: bool,
+    label: &str,
+) -> (PendingBet, bool) {
+    let filled = if dry_run {
+        true
+    } else if let Some(poly) = poly {
+        let tokens_held = bet.size_usdc / bet.entry_price;
+        match poly.place_order(
+            &bet.token_id, polymarket::Side::Sell, tokens_held * sell_price,
+            sell_price, bet.fee_rate_bps,
+        ).await {
+            Ok(result) => {
+                tracing::info!("{label} sell: {} (status: {})", result.order_id, result.status);
+                result.status == "matched"
+            }
+            Err(e) => {
+                tracing::error!("{label} sell failed: {e:#}");
+                false
+            }
+        }
+    } else {
+        false
+    };
+    (bet, filled)
+}
+
+/// Resolve a bet closed early by a mark-price exit (microstructure,
+/// ROI-table/trailing_stop_pct, or staged trailing stop — see the checks in
+/// the main loop): PnL comes from the realized sell price on the token's own
+/// book instead of waiting for `resolve_up` to settle the window's BTC
+/// outcome. `won` here tracks whether the exit banked a profit, not whether
+/// the window resolved the way `predicted_p` predicted, so this is always
+/// excluded from calibration (see `finalize_resolution`'s `skip_calibration`).
+#[allow(clippy::too_many_arguments)]
+fn resolve_early_exit(
+    bet: PendingBet,
+    exit_price: f64,
+    now: u64,
+    current_window: u64,
+    price_source: &str,
+    session: &mut strategy::Session,
+    csv: &mut Option<logger::CsvLogger>,
+    db_logger: &Option<db::DbLogger>,
+    notifier: &notify::Notifier,
+    strat_config: &mut strategy::StrategyConfig,
+    calibrator: &mut strategy::Calibrator,
+    metrics: &metrics::Metrics,
+) {
+    let pnl = compute_pnl_early_exit(bet.size_usdc, bet.entry_price, exit_price, bet.fee_pct);
+    let won = pnl >= 0.0;
+    let predicted_p = if bet.side == polymarket::Side::Buy { bet.implied_p_up } else { 1.0 - bet.implied_p_up };
+    finalize_resolution(
+        pnl, won, true, predicted_p, bet.entry_price, exit_price,
+        now, current_window, price_source, session, csv, db_logger, notifier, strat_config, calibrator, metrics,
+    );
+}
+
+/// Shared tail of bet resolution: session bookkeeping, CSV/DB logging,
+/// auto-calibration, and circuit-breaker checks. Used by both
+/// `resolve_pending_bet` (hold-to-expiry) and `resolve_early_exit`
+/// (mark-price exits).
+#[allow(clippy::too_many_arguments)]
+fn finalize_resolution(
+    pnl: f64,
+    won: bool,
+    skip_calibration: bool,
+    predicted_p: f64,
+    start_price: f64,
+    end_price: f64,
+    now: u64,
+    current_window: u64,
+    price_source: &str,
+    session: &mut strategy::Session,
+    csv: &mut Option<logger::CsvLogger>,
+    db_logger: &Option<db::DbLogger>,
+    notifier: &notify::Notifier,
+    strat_config: &mut strategy::StrategyConfig,
+    calibrator: &mut strategy::Calibrator,
+    metrics: &metrics::Metrics,
+) {
     session.record_trade(pnl);
     save_session(session);
+    metrics.set_session(
+        session.pnl_usdc, session.trades, session.win_rate() * 100.0,
+        session.consecutive_wins, session.consecutive_losses, session.session_drawdown_pct(),
+    );
     let result_str = if won { "WIN" } else { "LOSS" };
     tracing::info!(
         "Résolution: {} | PnL: ${:.2} | Session: ${:.2} | WR: {:.0}%",
         result_str, pnl, session.pnl_usdc, session.win_rate() * 100.0,
     );
     if let Some(ref mut csv) = csv {
-        csv.log_resolution(now, current_window, bet.start_price, current_btc,
+        csv.log_resolution(now, current_window, start_price, end_price,
             result_str, pnl, session.pnl_usdc, session.trades, session.win_rate() * 100.0,
             session.consecutive_wins, session.session_drawdown_pct());
     }
+    if let Some(ref dl) = db_logger {
+        dl.log_resolution(current_window, now, result_str, pnl, price_source);
+    }
 
-    // Auto-calibration: record prediction and check if recalibration is due
-    // Skip calibration for extreme trades (different probability model)
-    if !bet.is_extreme {
-        let predicted_p = if bet.side == polymarket::Side::Buy {
-            bet.implied_p_up
-        } else {
-            1.0 - bet.implied_p_up
-        };
+    // Auto-calibration: record prediction and check if recalibration is due.
+    // Skipped for extreme trades (different probability model) and early
+    // exits (`won` there reflects the trail's realized PnL, not whether the
+    // window resolved the way `predicted_p` predicted).
+    if !skip_calibration {
         calibrator.record(predicted_p, won);
     }
 
     if calibrator.should_recalibrate() {
+        let breakpoints = calibrator.fit_isotonic();
+        if !breakpoints.is_empty() {
+            tracing::info!("Auto-calibration: fit isotonic map over {} knots", breakpoints.len());
+            strat_config.calibration_breakpoints = breakpoints;
+        }
+        // Bootstrap the confidence intervals before `recalibrate()` below,
+        // which clears `calibrator`'s recorded entries as a side effect —
+        // computed afterward they'd always see an empty window and return
+        // `None`. This is the number the auto-calibration feature exists
+        // for: a point estimate alone hides how dangerous a recalibration is
+        // when only a handful of trades have been recorded.
+        let brier_ci = calibrator.brier_ci(500);
+        let multiplier_ci = calibrator.multiplier_ci(500);
         if let Some((new_mult, brier)) = calibrator.recalibrate() {
-            tracing::info!("Auto-calibration: vcm {:.2} → {:.2} (brier={:.4})",
-                strat_config.vol_confidence_multiplier, new_mult, brier);
+            match (brier_ci, multiplier_ci) {
+                (Some(b), Some(m)) => {
+                    tracing::info!(
+                        "Auto-calibration: vcm {:.2} → {:.2} (brier={:.4}, 95% CI brier=[{:.4}, {:.4}] vcm=[{:.2}, {:.2}])",
+                        strat_config.vol_confidence_multiplier, new_mult, brier, b.lower, b.upper, m.lower, m.upper);
+                }
+                _ => {
+                    tracing::info!("Auto-calibration: vcm {:.2} → {:.2} (brier={:.4})",
+                        strat_config.vol_confidence_multiplier, new_mult, brier);
+                }
+            }
             strat_config.vol_confidence_multiplier = new_mult;
             calibrator.set_current_vcm(new_mult);
+            metrics.set_calibration(new_mult, brier);
+            metrics.set_calibration_ci(brier_ci, multiplier_ci);
             let cal_json = serde_json::json!({
                 "vol_confidence_multiplier": new_mult,
                 "brier_score": brier,
@@ -1127,12 +2385,25 @@ fn resolve_pending_bet(
         }
     }
 
-    session.check_circuit_breaker(
+    let tripped = session.check_circuit_breaker(
         strat_config.circuit_breaker_window,
         strat_config.circuit_breaker_min_wr,
         strat_config.circuit_breaker_cooldown_s,
         now,
     );
+    if tripped {
+        notifier.send(notify::NotifyEvent::CircuitBreakerTripped {
+            rolling_wr_pct: session.rolling_wr(strat_config.circuit_breaker_window).unwrap_or(0.0) * 100.0,
+            cooldown_s: strat_config.circuit_breaker_cooldown_s,
+            session_pnl_usdc: session.pnl_usdc,
+        });
+    }
+    if strat_config.max_consecutive_losses > 0 && session.consecutive_losses >= strat_config.max_consecutive_losses {
+        notifier.send(notify::NotifyEvent::MaxConsecutiveLosses {
+            consecutive_losses: session.consecutive_losses,
+            session_pnl_usdc: session.pnl_usdc,
+        });
+    }
 }
 
 struct MarketData {
@@ -1225,39 +2496,140 @@ mod tests {
 
     #[test]
     fn pnl_win_subtracts_fee() {
-        let size = 2.0;
-        let price = 0.65;
-        let fee_pct = 0.52;
-        let pnl = compute_pnl(true, size, price, fee_pct);
-        let expected = size * (1.0 / price - 1.0) - size * 0.0052;
-        assert!((pnl - expected).abs() < 1e-10, "pnl={pnl} expected={expected}");
+        // price=0.5 so 1/price and every intermediate land exactly on
+        // Money's 1e-6 scale — now that the math is Money-routed, this
+        // asserts exact equality instead of the old epsilon tolerance.
+        let pnl = compute_pnl(true, 2.0, 0.5, FeeModel::Flat(0.5)).unwrap();
+        assert_eq!(pnl, 1.99);
     }
 
     #[test]
     fn pnl_loss_includes_fee() {
         let size = 2.0;
         let price = 0.65;
-        let fee_pct = 0.52;
-        let pnl = compute_pnl(false, size, price, fee_pct);
-        let expected = -size - size * 0.0052;
-        assert!((pnl - expected).abs() < 1e-10, "loss pnl should be -size-fee, got {pnl}");
+        let pnl = compute_pnl(false, size, price, FeeModel::Flat(0.52)).unwrap();
+        assert_eq!(pnl, -2.0104);
     }
 
     #[test]
     fn pnl_win_zero_fee_maker() {
-        // GTC maker case: fee_pct = 0.0
-        let size = 2.0;
-        let price = 0.65;
-        let pnl = compute_pnl(true, size, price, 0.0);
-        let expected = size * (1.0 / price - 1.0); // ~1.077
-        assert!((pnl - expected).abs() < 1e-10, "pnl={pnl} expected={expected}");
+        let pnl = compute_pnl(true, 2.0, 0.5, FeeModel::ZeroMaker).unwrap();
+        assert_eq!(pnl, 2.0);
     }
 
     #[test]
     fn pnl_loss_zero_fee_maker() {
-        // GTC maker case: fee_pct = 0.0
         let size = 2.0;
-        let pnl = compute_pnl(false, size, 0.65, 0.0);
-        assert!((pnl - (-size)).abs() < 1e-10, "loss pnl should be -size, got {pnl}");
+        let pnl = compute_pnl(false, size, 0.65, FeeModel::ZeroMaker).unwrap();
+        assert_eq!(pnl, -size);
+    }
+
+    #[test]
+    fn pnl_rejects_zero_price() {
+        assert_eq!(compute_pnl(true, 2.0, 0.0, FeeModel::Flat(0.5)), Err(PnlError::ZeroPrice));
+    }
+
+    #[test]
+    fn pnl_rejects_price_above_one() {
+        assert_eq!(compute_pnl(true, 2.0, 1.5, FeeModel::Flat(0.5)), Err(PnlError::PriceOutOfRange(1.5)));
+    }
+
+    #[test]
+    fn pnl_rejects_negative_size() {
+        assert_eq!(compute_pnl(true, -2.0, 0.5, FeeModel::Flat(0.5)), Err(PnlError::NegativeSize(-2.0)));
+    }
+
+    #[test]
+    fn pnl_symmetric_fee_taxes_half_price_most() {
+        // At price=0.5, min(price, 1-price) = 0.5, so the fee is
+        // base_rate * size * 0.5 — the model's maximum for this size.
+        let pnl = compute_pnl(true, 2.0, 0.5, FeeModel::Symmetric { base_rate: 0.02 }).unwrap();
+        assert_eq!(pnl, 2.0 - 0.02); // size*(1/0.5 - 1) - 0.02*2.0*0.5 = 2.0 - 0.02
+    }
+
+    #[test]
+    fn pnl_symmetric_fee_taxes_near_certain_price_least() {
+        // At price=0.9, min(price, 1-price) = 0.1, a much smaller fee than
+        // the 0.5 case above for the same size and base_rate.
+        let pnl = compute_pnl(true, 2.0, 0.9, FeeModel::Symmetric { base_rate: 0.02 }).unwrap();
+        let fee_cost = 0.02 * 2.0 * 0.1;
+        assert!((pnl - (2.0 * (1.0 / 0.9 - 1.0) - fee_cost)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn pnl_early_exit_pays_fee_on_both_legs() {
+        // price_ratio = 0.6/0.5 = 1.2 exactly; fee_cost = 2*0.5/100*2 = 0.02.
+        let pnl = compute_pnl_early_exit(2.0, 0.5, 0.6, 0.5);
+        assert_eq!(pnl, 0.38);
+    }
+
+    /// Generative coverage for the structural invariants the hand-picked
+    /// cases above can only sample a handful of points from: these hold for
+    /// *every* valid (size, price, fee_pct), not just 0.5/0.65/2.0.
+    mod pnl_properties {
+        use super::*;
+        use proptest::prelude::*;
+
+        proptest! {
+            /// A winning bet always pays out strictly more than a losing one
+            /// for the same (size, price, fee_pct), as long as there's an
+            /// actual stake at risk.
+            #[test]
+            fn win_pnl_always_exceeds_loss_pnl(
+                size in 0.0001f64..1000.0,
+                price in 0.001f64..0.999,
+                fee_pct in 0.0f64..5.0,
+            ) {
+                let fee_model = FeeModel::Flat(fee_pct);
+                let win = compute_pnl(true, size, price, fee_model).unwrap();
+                let loss = compute_pnl(false, size, price, fee_model).unwrap();
+                prop_assert!(win > loss, "win={win} loss={loss} size={size} price={price} fee_pct={fee_pct}");
+            }
+
+            /// Raising `fee_pct` must never increase PnL, win or lose.
+            #[test]
+            fn raising_fee_pct_never_increases_pnl(
+                size in 0.0f64..1000.0,
+                price in 0.001f64..0.999,
+                won in any::<bool>(),
+                fee_pct_low in 0.0f64..5.0,
+                fee_pct_delta in 0.0f64..5.0,
+            ) {
+                let pnl_low = compute_pnl(won, size, price, FeeModel::Flat(fee_pct_low)).unwrap();
+                let pnl_high = compute_pnl(won, size, price, FeeModel::Flat(fee_pct_low + fee_pct_delta)).unwrap();
+                prop_assert!(pnl_high <= pnl_low, "pnl_high={pnl_high} should be <= pnl_low={pnl_low}");
+            }
+
+            /// `resolve_up(a, b)` is the logical negation of `resolve_up(b, a)`
+            /// except at an exact tie, where the Polymarket equality rule
+            /// makes both directions resolve UP.
+            #[test]
+            fn resolve_up_is_antisymmetric_except_at_ties(
+                a in 1.0f64..200_000.0,
+                b in 1.0f64..200_000.0,
+            ) {
+                if a == b {
+                    prop_assert!(resolve_up(a, b) && resolve_up(b, a));
+                } else {
+                    prop_assert_ne!(resolve_up(a, b), resolve_up(b, a));
+                }
+            }
+
+            /// GTC maker (zero fee) oracle: the win payout must equal
+            /// `size*(1/price - 1)` on `size`/`price` as `Money` stores them,
+            /// within 1e-3: `1/price` is rounded to Money's 1e-6 step, and a
+            /// `size` below 1000 scales that to at most 5e-4.
+            #[test]
+            fn zero_fee_maker_win_payout_matches_the_oracle_formula(
+                size in 0.0f64..1000.0,
+                price in 0.001f64..0.999,
+            ) {
+                let size = crate::fixedpoint::Money::from_f64(size).to_f64();
+                let price = crate::fixedpoint::Money::from_f64(price).to_f64();
+                let pnl = compute_pnl(true, size, price, FeeModel::ZeroMaker).unwrap();
+                let oracle = size * (1.0 / price - 1.0);
+                prop_assert!((pnl - oracle).abs() < 1e-3, "pnl={pnl} oracle={oracle}");
+            }
+        }
     }
 }
@@ -0,0 +1,236 @@
+//! Synthetic `TradeContext` stream generator for `simulator::simulate`, for
+//! stress-testing the strategy against distributions the historical tests
+//! never exercise — the existing tests only ever feed fixed `chainlink_price`/
+//! `micro_vol`/`momentum_ratio` values, so a fat-tailed or jumpy regime is
+//! never actually exercised end-to-end.
+//!
+//! Walks a running synthetic BTC price forward one tick per `sample()` call
+//! and feeds every tick into a `strategy::WindowTicks`, so `micro_vol`,
+//! `momentum_ratio`, `fisher`, and `max_drawdown_bps` are derived exactly
+//! the way the live bot derives them from real ticks, not reimplemented ad
+//! hoc. `market_up_price` is left at an uninformed 0.5 — this module's job
+//! is realistic *underlying* price paths, not a market-maker pricing model
+//! — so `evaluate`'s edge is driven entirely by how the synthetic path
+//! diverges from `start_price`.
+
+use std::f64::consts::PI;
+
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::simulator::ContextSampler;
+use crate::strategy::{TradeContext, WindowTicks};
+
+/// Per-tick log-return distribution for `MarketPathGenerator`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReturnDistribution {
+    /// `location + scale * z`, `z ~ N(0, 1)` via Box-Muller — the calm baseline.
+    Gaussian,
+    /// `location + scale * tan(pi * (u - 0.5))`, `u` uniform in `(0, 1)` —
+    /// Cauchy-style fat tails for flash-move stress testing.
+    Cauchy,
+}
+
+/// Config for `MarketPathGenerator`.
+#[derive(Debug, Clone, Copy)]
+pub struct MarketPathConfig {
+    pub distribution: ReturnDistribution,
+    /// Per-tick log-return drift (location parameter). 0.0 = no bias.
+    pub drift: f64,
+    /// Per-tick log-return scale (spread parameter).
+    pub scale: f64,
+    /// Probability in `[0, 1]` that a tick instead draws its return with
+    /// `scale * jump_multiplier` — an isolated flash move inside an
+    /// otherwise calm regime. 0.0 disables jumps entirely.
+    pub jump_probability: f64,
+    pub jump_multiplier: f64,
+    /// Clamp on the magnitude of a single tick's log-return, so an extreme
+    /// Cauchy draw can't move the price by an implausible amount in one
+    /// tick. 0.0 disables the clamp.
+    pub max_abs_log_return: f64,
+    /// Window passed to `WindowTicks::fisher` (mirrors
+    /// `StrategyConfig::fisher_window`).
+    pub window: usize,
+    pub starting_price: f64,
+    /// `seconds_remaining` reported on every sampled `TradeContext` —
+    /// sweep `SimulatorConfig` runs with different values to stress
+    /// different entry timings.
+    pub seconds_remaining: u64,
+}
+
+/// Generates a correlated `TradeContext` stream for `simulator::simulate` by
+/// walking a synthetic BTC price forward one tick per `sample()` call.
+pub struct MarketPathGenerator {
+    config: MarketPathConfig,
+    price: f64,
+    ticks: WindowTicks,
+}
+
+impl MarketPathGenerator {
+    pub fn new(config: MarketPathConfig) -> Self {
+        let price = config.starting_price;
+        Self { config, price, ticks: WindowTicks::new() }
+    }
+
+    /// Draws one log-return per the configured distribution and jump
+    /// probability, clamped to `max_abs_log_return`.
+    fn draw_log_return(&self, rng: &mut StdRng) -> f64 {
+        let jumping = self.config.jump_probability > 0.0
+            && rng.random::<f64>() < self.config.jump_probability;
+        let scale = if jumping {
+            self.config.scale * self.config.jump_multiplier
+        } else {
+            self.config.scale
+        };
+        let r = match self.config.distribution {
+            ReturnDistribution::Gaussian => {
+                let u1 = rng.random::<f64>().max(f64::MIN_POSITIVE);
+                let u2: f64 = rng.random::<f64>();
+                let z = (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos();
+                self.config.drift + scale * z
+            }
+            ReturnDistribution::Cauchy => {
+                let u: f64 = rng.random::<f64>();
+                self.config.drift + scale * (PI * (u - 0.5)).tan()
+            }
+        };
+        if self.config.max_abs_log_return > 0.0 {
+            r.clamp(-self.config.max_abs_log_return, self.config.max_abs_log_return)
+        } else {
+            r
+        }
+    }
+}
+
+impl ContextSampler for MarketPathGenerator {
+    fn sample(&mut self, rng: &mut StdRng) -> TradeContext {
+        let r = self.draw_log_return(rng);
+        self.price *= r.exp();
+        self.ticks.tick(self.price, 0);
+
+        let fisher = self.ticks.fisher(self.config.window);
+        TradeContext {
+            start_price: self.config.starting_price,
+            chainlink_price: self.price,
+            exchange_price: Some(self.price),
+            rtds_price: None,
+            market_up_price: 0.5,
+            seconds_remaining: self.config.seconds_remaining,
+            fee_rate: 0.25,
+            vol_5min_pct: self.ticks.micro_vol(),
+            spread: 0.0,
+            book_imbalance: 0.0,
+            num_ws_sources: 2,
+            micro_vol: self.ticks.micro_vol(),
+            momentum_ratio: self.ticks.momentum_ratio(),
+            fisher,
+            fisher_prev: self.ticks.fisher_prev(),
+            max_drawdown_bps: self.ticks.max_drawdown_bps(),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.price = self.config.starting_price;
+        self.ticks.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn base_config() -> MarketPathConfig {
+        MarketPathConfig {
+            distribution: ReturnDistribution::Gaussian,
+            drift: 0.0,
+            scale: 0.0005,
+            jump_probability: 0.0,
+            jump_multiplier: 1.0,
+            max_abs_log_return: 0.05,
+            window: 10,
+            starting_price: 100_000.0,
+            seconds_remaining: 30,
+        }
+    }
+
+    #[test]
+    fn reset_returns_the_price_to_the_starting_point() {
+        let mut gen = MarketPathGenerator::new(base_config());
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..20 {
+            gen.sample(&mut rng);
+        }
+        assert_ne!(gen.price, gen.config.starting_price);
+        gen.reset();
+        assert_eq!(gen.price, gen.config.starting_price);
+    }
+
+    #[test]
+    fn same_seed_produces_the_same_path() {
+        let mut a = MarketPathGenerator::new(base_config());
+        let mut b = MarketPathGenerator::new(base_config());
+        let mut rng_a = StdRng::seed_from_u64(99);
+        let mut rng_b = StdRng::seed_from_u64(99);
+        for _ in 0..15 {
+            let ctx_a = a.sample(&mut rng_a);
+            let ctx_b = b.sample(&mut rng_b);
+            assert_eq!(ctx_a.chainlink_price, ctx_b.chainlink_price);
+        }
+    }
+
+    #[test]
+    fn max_abs_log_return_clamps_extreme_cauchy_draws() {
+        let mut config = base_config();
+        config.distribution = ReturnDistribution::Cauchy;
+        config.scale = 1.0;
+        config.max_abs_log_return = 0.02;
+        let mut gen = MarketPathGenerator::new(config);
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut prev = gen.price;
+        for _ in 0..200 {
+            let ctx = gen.sample(&mut rng);
+            let log_return = (ctx.chainlink_price / prev).ln();
+            assert!(log_return.abs() <= 0.02 + 1e-9);
+            prev = ctx.chainlink_price;
+        }
+    }
+
+    #[test]
+    fn jump_probability_of_one_always_uses_the_jump_scale() {
+        let mut calm = base_config();
+        calm.scale = 0.0001;
+        calm.max_abs_log_return = 0.0;
+        let mut jumpy = calm;
+        jumpy.jump_probability = 1.0;
+        jumpy.jump_multiplier = 50.0;
+
+        let mut calm_gen = MarketPathGenerator::new(calm);
+        let mut jumpy_gen = MarketPathGenerator::new(jumpy);
+        let mut rng_calm = StdRng::seed_from_u64(5);
+        let mut rng_jumpy = StdRng::seed_from_u64(5);
+
+        let mut calm_moves = 0.0;
+        let mut jumpy_moves = 0.0;
+        let mut prev_calm = calm_gen.price;
+        let mut prev_jumpy = jumpy_gen.price;
+        for _ in 0..50 {
+            let ctx_calm = calm_gen.sample(&mut rng_calm);
+            let ctx_jumpy = jumpy_gen.sample(&mut rng_jumpy);
+            calm_moves += (ctx_calm.chainlink_price / prev_calm).ln().abs();
+            jumpy_moves += (ctx_jumpy.chainlink_price / prev_jumpy).ln().abs();
+            prev_calm = ctx_calm.chainlink_price;
+            prev_jumpy = ctx_jumpy.chainlink_price;
+        }
+        assert!(jumpy_moves > calm_moves);
+    }
+
+    #[test]
+    fn market_up_price_stays_uninformed() {
+        let mut gen = MarketPathGenerator::new(base_config());
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..10 {
+            assert_eq!(gen.sample(&mut rng).market_up_price, 0.5);
+        }
+    }
+}
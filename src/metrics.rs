@@ -0,0 +1,390 @@
+//! Prometheus metrics endpoint (`GET /metrics` on a configurable bind
+//! address) — exposes the session/latency/fill-quality numbers the main
+//! loop already computes each iteration, so dashboards/alerting can watch
+//! the bot live instead of tailing logs or parsing CSV.
+//!
+//! No metrics crate in the dependency tree: gauges/counters are bare
+//! `AtomicU64` (f64 gauges stored via `to_bits`/`from_bits`) behind an
+//! `Arc`, and the exposition text is hand-assembled and served off a plain
+//! `tokio::net::TcpListener` — the same "roll it by hand" approach already
+//! used for EIP-712 signing and fixed-point math elsewhere in this crate.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use crate::exchanges::SourceStatus;
+use crate::rtds::FeedStatus;
+use crate::strategy::ConfidenceInterval;
+
+/// Snapshot of one exchange source's health, refreshed wholesale each loop
+/// iteration from `ExchangeFeed::source_status` — labels are only known at
+/// runtime (configured sources), so unlike the fixed `Inner` gauges these
+/// live behind a `Mutex<Vec<_>>` rather than one `AtomicU64` per field.
+struct ExchangeGauge {
+    label: String,
+    connected: bool,
+    reconnects: u32,
+    seconds_since_update: f64,
+    last_price: f64,
+}
+
+fn load_f64(a: &AtomicU64) -> f64 {
+    f64::from_bits(a.load(Ordering::Relaxed))
+}
+
+fn store_f64(a: &AtomicU64, v: f64) {
+    a.store(v.to_bits(), Ordering::Relaxed);
+}
+
+struct Inner {
+    pnl_usdc: AtomicU64,
+    trades_total: AtomicU64,
+    win_rate_pct: AtomicU64,
+    consecutive_wins: AtomicU64,
+    consecutive_losses: AtomicU64,
+    session_drawdown_pct: AtomicU64,
+    order_latency_ms: AtomicU64,
+    last_tick_age_ms: AtomicU64,
+    num_ws_sources: AtomicU64,
+    book_spread: AtomicU64,
+    book_imbalance: AtomicU64,
+    vol_confidence_multiplier: AtomicU64,
+    brier_score: AtomicU64,
+    gtc_filled_total: AtomicU64,
+    fok_filled_total: AtomicU64,
+    rejected_total: AtomicU64,
+    median_price: AtomicU64,
+    exchange_health: Mutex<Vec<ExchangeGauge>>,
+    /// `FeedStatus` as a numeric code: 0=Connecting, 1=Subscribed, 2=Live,
+    /// 3=Disconnected — see `set_rtds_status`.
+    rtds_status: AtomicU64,
+    rtds_reconnects_total: AtomicU64,
+    /// Age of RTDS's last update, only meaningful while `rtds_status == 2`
+    /// (Live); 0 otherwise. Lets a dashboard tell "stale because
+    /// disconnected" apart from "connected but quiet".
+    rtds_last_update_age_ms: AtomicU64,
+    /// Bootstrap confidence bounds from `Calibrator::brier_ci`/`multiplier_ci`,
+    /// refreshed only when a recalibration has enough trades recorded to
+    /// compute them (0 otherwise) — see `set_calibration_ci`.
+    brier_ci_lower: AtomicU64,
+    brier_ci_upper: AtomicU64,
+    multiplier_ci_lower: AtomicU64,
+    multiplier_ci_upper: AtomicU64,
+}
+
+/// Handle for updating the live gauges/counters. Cloning is cheap (just an
+/// `Arc` clone) so every call site in `main` can hold its own, the same way
+/// `notify::Notifier` is threaded through.
+#[derive(Clone)]
+pub struct Metrics(Arc<Inner>);
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self(Arc::new(Inner {
+            pnl_usdc: AtomicU64::new(0),
+            trades_total: AtomicU64::new(0),
+            win_rate_pct: AtomicU64::new(0),
+            consecutive_wins: AtomicU64::new(0),
+            consecutive_losses: AtomicU64::new(0),
+            session_drawdown_pct: AtomicU64::new(0),
+            order_latency_ms: AtomicU64::new(0),
+            last_tick_age_ms: AtomicU64::new(0),
+            num_ws_sources: AtomicU64::new(0),
+            book_spread: AtomicU64::new(0),
+            book_imbalance: AtomicU64::new(0),
+            vol_confidence_multiplier: AtomicU64::new(0),
+            brier_score: AtomicU64::new(0),
+            gtc_filled_total: AtomicU64::new(0),
+            fok_filled_total: AtomicU64::new(0),
+            rejected_total: AtomicU64::new(0),
+            median_price: AtomicU64::new(0),
+            exchange_health: Mutex::new(Vec::new()),
+            rtds_status: AtomicU64::new(0),
+            rtds_reconnects_total: AtomicU64::new(0),
+            rtds_last_update_age_ms: AtomicU64::new(0),
+            brier_ci_lower: AtomicU64::new(0),
+            brier_ci_upper: AtomicU64::new(0),
+            multiplier_ci_lower: AtomicU64::new(0),
+            multiplier_ci_upper: AtomicU64::new(0),
+        }))
+    }
+
+    /// Spawns the `/metrics` HTTP listener. A blank `bind_addr` spawns
+    /// nothing, so callers can unconditionally call this — matching how
+    /// `notify::Notifier::start` no-ops on an empty webhook URL.
+    pub async fn start(&self, bind_addr: String) {
+        if bind_addr.is_empty() {
+            return;
+        }
+        let listener = match TcpListener::bind(&bind_addr).await {
+            Ok(l) => l,
+            Err(e) => {
+                tracing::error!("Metrics: failed to bind {bind_addr}: {e}");
+                return;
+            }
+        };
+        tracing::info!("Metrics: serving /metrics on {bind_addr}");
+        let metrics = self.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = match listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        tracing::warn!("Metrics: accept failed: {e}");
+                        continue;
+                    }
+                };
+                let body = metrics.render();
+                tokio::spawn(async move {
+                    // Drain (and ignore) the request — we only ever serve /metrics.
+                    let mut buf = [0u8; 1024];
+                    let _ = stream.read(&mut buf).await;
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(), body,
+                    );
+                    let _ = stream.write_all(response.as_bytes()).await;
+                    let _ = stream.shutdown().await;
+                });
+            }
+        });
+    }
+
+    /// Session-level gauges, refreshed every loop iteration (and again after
+    /// each resolution in `finalize_resolution`).
+    pub fn set_session(&self, pnl_usdc: f64, trades: u32, win_rate_pct: f64, consecutive_wins: u32, consecutive_losses: u32, session_drawdown_pct: f64) {
+        store_f64(&self.0.pnl_usdc, pnl_usdc);
+        self.0.trades_total.store(u64::from(trades), Ordering::Relaxed);
+        store_f64(&self.0.win_rate_pct, win_rate_pct);
+        self.0.consecutive_wins.store(u64::from(consecutive_wins), Ordering::Relaxed);
+        self.0.consecutive_losses.store(u64::from(consecutive_losses), Ordering::Relaxed);
+        store_f64(&self.0.session_drawdown_pct, session_drawdown_pct);
+    }
+
+    /// Feed/book gauges, refreshed every loop iteration.
+    pub fn set_live(&self, last_tick_age_ms: u64, num_ws_sources: u32, book_spread: f64, book_imbalance: f64) {
+        self.0.last_tick_age_ms.store(last_tick_age_ms, Ordering::Relaxed);
+        self.0.num_ws_sources.store(u64::from(num_ws_sources), Ordering::Relaxed);
+        store_f64(&self.0.book_spread, book_spread);
+        store_f64(&self.0.book_imbalance, book_imbalance);
+    }
+
+    /// Per-exchange feed health plus the feed-wide median price, refreshed
+    /// every loop iteration from `ExchangeFeed::source_status`/`latest` — the
+    /// per-source detail behind the `num_ws_sources` count `set_live` already
+    /// reports, so operators can see which venue dropped out and why.
+    pub fn set_exchange_health(&self, sources: &[SourceStatus], median_price: f64) {
+        store_f64(&self.0.median_price, median_price);
+        let mut guard = self.0.exchange_health.lock().unwrap();
+        *guard = sources
+            .iter()
+            .map(|s| ExchangeGauge {
+                label: s.label.to_string(),
+                connected: s.connected,
+                reconnects: s.reconnects,
+                seconds_since_update: s.seconds_since_update,
+                last_price: s.last_price,
+            })
+            .collect();
+    }
+
+    /// RTDS feed health, refreshed every loop iteration from
+    /// `RtdsFeed::status()` — mirrors `set_exchange_health`'s per-exchange
+    /// connected/reconnects/staleness detail, but for the single RTDS feed,
+    /// so a stale-because-disconnected tick is distinguishable from a
+    /// connected-but-quiet one instead of both just showing up as an aged
+    /// `last_tick_age_ms`.
+    pub fn set_rtds_status(&self, status: FeedStatus) {
+        let (code, reconnects, age_ms) = match status {
+            FeedStatus::Connecting => (0, 0, 0),
+            FeedStatus::Subscribed => (1, 0, 0),
+            FeedStatus::Live { last_update_ms } => {
+                let now_ms = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+                (2, 0, now_ms.saturating_sub(last_update_ms))
+            }
+            FeedStatus::Disconnected { reconnects } => (3, reconnects, 0),
+        };
+        self.0.rtds_status.store(code, Ordering::Relaxed);
+        self.0.rtds_reconnects_total.store(u64::from(reconnects), Ordering::Relaxed);
+        self.0.rtds_last_update_age_ms.store(age_ms, Ordering::Relaxed);
+    }
+
+    /// Calibration gauges, refreshed every loop iteration and again right
+    /// after `finalize_resolution` recalibrates.
+    pub fn set_calibration(&self, vol_confidence_multiplier: f64, brier_score: f64) {
+        store_f64(&self.0.vol_confidence_multiplier, vol_confidence_multiplier);
+        store_f64(&self.0.brier_score, brier_score);
+    }
+
+    /// Bootstrap confidence bounds for the Brier score and the new
+    /// multiplier, set right after `finalize_resolution` recalibrates —
+    /// `Calibrator::brier_ci`/`multiplier_ci` return `None` when too few
+    /// trades were recorded to bootstrap from, in which case the gauges are
+    /// left at 0 rather than showing a stale window's bounds.
+    pub fn set_calibration_ci(&self, brier_ci: Option<ConfidenceInterval>, multiplier_ci: Option<ConfidenceInterval>) {
+        let (brier_lower, brier_upper) = brier_ci.map_or((0.0, 0.0), |ci| (ci.lower, ci.upper));
+        let (mult_lower, mult_upper) = multiplier_ci.map_or((0.0, 0.0), |ci| (ci.lower, ci.upper));
+        store_f64(&self.0.brier_ci_lower, brier_lower);
+        store_f64(&self.0.brier_ci_upper, brier_upper);
+        store_f64(&self.0.multiplier_ci_lower, mult_lower);
+        store_f64(&self.0.multiplier_ci_upper, mult_upper);
+    }
+
+    /// Per-order latency gauge and `fill_type` counter, recorded right after
+    /// `execute_order`/the dry-run path resolves.
+    pub fn record_order(&self, order_latency_ms: u64, fill_type: &str) {
+        self.0.order_latency_ms.store(order_latency_ms, Ordering::Relaxed);
+        match fill_type {
+            "GTC_filled" => { self.0.gtc_filled_total.fetch_add(1, Ordering::Relaxed); }
+            "FOK_filled" | "HYBRID_escalated_filled" => { self.0.fok_filled_total.fetch_add(1, Ordering::Relaxed); }
+            "rejected" => { self.0.rejected_total.fetch_add(1, Ordering::Relaxed); }
+            _ => {}
+        }
+    }
+
+    fn render(&self) -> String {
+        let i = &self.0;
+        let mut body = format!(
+            "# HELP poly5m_session_pnl_usdc Current session PnL in USDC.\n\
+             # TYPE poly5m_session_pnl_usdc gauge\n\
+             poly5m_session_pnl_usdc {}\n\
+             # HELP poly5m_session_trades_total Trades closed this session.\n\
+             # TYPE poly5m_session_trades_total counter\n\
+             poly5m_session_trades_total {}\n\
+             # HELP poly5m_session_win_rate_pct Rolling session win rate, in percent.\n\
+             # TYPE poly5m_session_win_rate_pct gauge\n\
+             poly5m_session_win_rate_pct {}\n\
+             # HELP poly5m_session_consecutive_wins Current winning streak.\n\
+             # TYPE poly5m_session_consecutive_wins gauge\n\
+             poly5m_session_consecutive_wins {}\n\
+             # HELP poly5m_session_consecutive_losses Current losing streak.\n\
+             # TYPE poly5m_session_consecutive_losses gauge\n\
+             poly5m_session_consecutive_losses {}\n\
+             # HELP poly5m_session_drawdown_pct Drawdown from the session's PnL high-water mark, in percent.\n\
+             # TYPE poly5m_session_drawdown_pct gauge\n\
+             poly5m_session_drawdown_pct {}\n\
+             # HELP poly5m_order_latency_ms Latency of the most recent order placement, in milliseconds.\n\
+             # TYPE poly5m_order_latency_ms gauge\n\
+             poly5m_order_latency_ms {}\n\
+             # HELP poly5m_last_tick_age_ms Age of the freshest price tick, in milliseconds.\n\
+             # TYPE poly5m_last_tick_age_ms gauge\n\
+             poly5m_last_tick_age_ms {}\n\
+             # HELP poly5m_ws_sources Number of live exchange WebSocket sources.\n\
+             # TYPE poly5m_ws_sources gauge\n\
+             poly5m_ws_sources {}\n\
+             # HELP poly5m_median_price Current aggregated exchange median price.\n\
+             # TYPE poly5m_median_price gauge\n\
+             poly5m_median_price {}\n\
+             # HELP poly5m_book_spread Current order book spread.\n\
+             # TYPE poly5m_book_spread gauge\n\
+             poly5m_book_spread {}\n\
+             # HELP poly5m_book_imbalance Current order book imbalance.\n\
+             # TYPE poly5m_book_imbalance gauge\n\
+             poly5m_book_imbalance {}\n\
+             # HELP poly5m_vol_confidence_multiplier Current recalibrated volatility confidence multiplier.\n\
+             # TYPE poly5m_vol_confidence_multiplier gauge\n\
+             poly5m_vol_confidence_multiplier {}\n\
+             # HELP poly5m_brier_score Current calibrator Brier score.\n\
+             # TYPE poly5m_brier_score gauge\n\
+             poly5m_brier_score {}\n\
+             # HELP poly5m_brier_ci_lower Bootstrap 2.5th percentile of the Brier score at the last recalibration (0 if too few trades to bootstrap).\n\
+             # TYPE poly5m_brier_ci_lower gauge\n\
+             poly5m_brier_ci_lower {}\n\
+             # HELP poly5m_brier_ci_upper Bootstrap 97.5th percentile of the Brier score at the last recalibration (0 if too few trades to bootstrap).\n\
+             # TYPE poly5m_brier_ci_upper gauge\n\
+             poly5m_brier_ci_upper {}\n\
+             # HELP poly5m_multiplier_ci_lower Bootstrap 2.5th percentile of the recalibrated volatility confidence multiplier (0 if too few trades to bootstrap).\n\
+             # TYPE poly5m_multiplier_ci_lower gauge\n\
+             poly5m_multiplier_ci_lower {}\n\
+             # HELP poly5m_multiplier_ci_upper Bootstrap 97.5th percentile of the recalibrated volatility confidence multiplier (0 if too few trades to bootstrap).\n\
+             # TYPE poly5m_multiplier_ci_upper gauge\n\
+             poly5m_multiplier_ci_upper {}\n\
+             # HELP poly5m_fills_total Order attempts by fill type.\n\
+             # TYPE poly5m_fills_total counter\n\
+             poly5m_fills_total{{fill_type=\"gtc_filled\"}} {}\n\
+             poly5m_fills_total{{fill_type=\"fok_filled\"}} {}\n\
+             poly5m_fills_total{{fill_type=\"rejected\"}} {}\n\
+             # HELP poly5m_rtds_status RTDS feed status: 0=connecting, 1=subscribed, 2=live, 3=disconnected.\n\
+             # TYPE poly5m_rtds_status gauge\n\
+             poly5m_rtds_status {}\n\
+             # HELP poly5m_rtds_reconnects_total Cumulative RTDS WebSocket reconnects.\n\
+             # TYPE poly5m_rtds_reconnects_total counter\n\
+             poly5m_rtds_reconnects_total {}\n\
+             # HELP poly5m_rtds_last_update_age_ms Age of RTDS's last price update, in milliseconds (only meaningful while status=live).\n\
+             # TYPE poly5m_rtds_last_update_age_ms gauge\n\
+             poly5m_rtds_last_update_age_ms {}\n",
+            load_f64(&i.pnl_usdc),
+            i.trades_total.load(Ordering::Relaxed),
+            load_f64(&i.win_rate_pct),
+            i.consecutive_wins.load(Ordering::Relaxed),
+            i.consecutive_losses.load(Ordering::Relaxed),
+            load_f64(&i.session_drawdown_pct),
+            i.order_latency_ms.load(Ordering::Relaxed),
+            i.last_tick_age_ms.load(Ordering::Relaxed),
+            i.num_ws_sources.load(Ordering::Relaxed),
+            load_f64(&i.median_price),
+            load_f64(&i.book_spread),
+            load_f64(&i.book_imbalance),
+            load_f64(&i.vol_confidence_multiplier),
+            load_f64(&i.brier_score),
+            load_f64(&i.brier_ci_lower),
+            load_f64(&i.brier_ci_upper),
+            load_f64(&i.multiplier_ci_lower),
+            load_f64(&i.multiplier_ci_upper),
+            i.gtc_filled_total.load(Ordering::Relaxed),
+            i.fok_filled_total.load(Ordering::Relaxed),
+            i.rejected_total.load(Ordering::Relaxed),
+            i.rtds_status.load(Ordering::Relaxed),
+            i.rtds_reconnects_total.load(Ordering::Relaxed),
+            i.rtds_last_update_age_ms.load(Ordering::Relaxed),
+        );
+
+        // Exchange labels are only known at runtime (whichever sources are
+        // configured), so these can't be positional `format!` args like the
+        // gauges above — appended as their own exposition block instead.
+        let exchange_health = self.0.exchange_health.lock().unwrap();
+        body.push_str(
+            "# HELP poly5m_exchange_connected Whether the exchange WebSocket is currently connected.\n\
+             # TYPE poly5m_exchange_connected gauge\n",
+        );
+        for e in exchange_health.iter() {
+            body.push_str(&format!(
+                "poly5m_exchange_connected{{exchange=\"{}\"}} {}\n",
+                e.label, if e.connected { 1 } else { 0 },
+            ));
+        }
+        body.push_str(
+            "# HELP poly5m_exchange_reconnects_total Cumulative reconnects for this exchange's WebSocket.\n\
+             # TYPE poly5m_exchange_reconnects_total counter\n",
+        );
+        for e in exchange_health.iter() {
+            body.push_str(&format!("poly5m_exchange_reconnects_total{{exchange=\"{}\"}} {}\n", e.label, e.reconnects));
+        }
+        body.push_str(
+            "# HELP poly5m_exchange_seconds_since_update Seconds since this exchange last pushed a price.\n\
+             # TYPE poly5m_exchange_seconds_since_update gauge\n",
+        );
+        for e in exchange_health.iter() {
+            body.push_str(&format!("poly5m_exchange_seconds_since_update{{exchange=\"{}\"}} {}\n", e.label, e.seconds_since_update));
+        }
+        body.push_str(
+            "# HELP poly5m_exchange_last_price Last price pushed by this exchange.\n\
+             # TYPE poly5m_exchange_last_price gauge\n",
+        );
+        for e in exchange_health.iter() {
+            body.push_str(&format!("poly5m_exchange_last_price{{exchange=\"{}\"}} {}\n", e.label, e.last_price));
+        }
+        drop(exchange_health);
+
+        body
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
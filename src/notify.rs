@@ -0,0 +1,155 @@
+//! Push notifications (Telegram/Discord-style webhook) for fills,
+//! circuit-breaker trips, max-consecutive-loss stops, and session PnL
+//! limits — the `[notifications]` config section in `main.rs`.
+//!
+//! Mirrors how `exchanges`/`rtds` fan a feed out to subscribers: the main
+//! loop sends `NotifyEvent`s into a `tokio::sync::broadcast` channel and a
+//! background task drains it, retrying/timing out against the webhook so a
+//! slow endpoint never stalls trade execution.
+
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+const CHANNEL_CAPACITY: usize = 64;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_ATTEMPTS: u32 = 3;
+
+/// One notifiable event, carrying just enough state to render its message —
+/// the window, BTC price, edge, bet size, and running PnL the request asked
+/// for.
+#[derive(Debug, Clone)]
+pub enum NotifyEvent {
+    Fill { window: u64, btc_price: f64, side: String, edge_pct: f64, size_usdc: f64, session_pnl_usdc: f64 },
+    CircuitBreakerTripped { rolling_wr_pct: f64, cooldown_s: u64, session_pnl_usdc: f64 },
+    MaxConsecutiveLosses { consecutive_losses: u32, session_pnl_usdc: f64 },
+    SessionLimitReached { session_pnl_usdc: f64, target_usdc: f64, limit_usdc: f64 },
+}
+
+impl NotifyEvent {
+    /// Matches the `events` entries in `[notifications]` — the event mask.
+    fn kind(&self) -> &'static str {
+        match self {
+            NotifyEvent::Fill { .. } => "fill",
+            NotifyEvent::CircuitBreakerTripped { .. } => "circuit_breaker",
+            NotifyEvent::MaxConsecutiveLosses { .. } => "max_consecutive_losses",
+            NotifyEvent::SessionLimitReached { .. } => "session_limit",
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            NotifyEvent::Fill { window, btc_price, side, edge_pct, size_usdc, session_pnl_usdc } => format!(
+                "Fill [{window}] {side} @ ${btc_price:.2} | edge {edge_pct:.2}% | ${size_usdc:.2} | session PnL ${session_pnl_usdc:.2}"
+            ),
+            NotifyEvent::CircuitBreakerTripped { rolling_wr_pct, cooldown_s, session_pnl_usdc } => format!(
+                "Circuit breaker tripped: rolling WR {rolling_wr_pct:.0}%, cooldown {cooldown_s}s | session PnL ${session_pnl_usdc:.2}"
+            ),
+            NotifyEvent::MaxConsecutiveLosses { consecutive_losses, session_pnl_usdc } => format!(
+                "{consecutive_losses} consecutive losses | session PnL ${session_pnl_usdc:.2}"
+            ),
+            NotifyEvent::SessionLimitReached { session_pnl_usdc, target_usdc, limit_usdc } => format!(
+                "Session limit reached: PnL ${session_pnl_usdc:.2} (target ${target_usdc:.2} / limit -${limit_usdc:.2})"
+            ),
+        }
+    }
+}
+
+/// Handle for queuing `NotifyEvent`s. Cloning is cheap (just a
+/// `broadcast::Sender` clone) so every call site in `main` can hold its own.
+#[derive(Clone)]
+pub struct Notifier {
+    tx: broadcast::Sender<NotifyEvent>,
+}
+
+impl Notifier {
+    /// Spawns the background dispatcher and returns a handle to feed it.
+    /// `events` is the configured event mask — kinds not listed are dropped
+    /// before ever reaching the webhook. An empty `webhook_url` spawns a
+    /// dispatcher that immediately exits, so `send` is always safe to call
+    /// even when notifications are disabled.
+    pub async fn start(webhook_url: String, chat_id: String, events: Vec<String>) -> Self {
+        let (tx, rx) = broadcast::channel(CHANNEL_CAPACITY);
+        tokio::spawn(dispatch(rx, webhook_url, chat_id, events));
+        Self { tx }
+    }
+
+    /// Queues `event` for delivery. Never blocks or fails the caller — with
+    /// no active receiver `broadcast::Sender::send` just reports no
+    /// subscribers, which we ignore; a wedged dispatcher still holds its
+    /// receiver, so the channel keeps accepting and overwrites its oldest
+    /// queued events once `CHANNEL_CAPACITY` is reached.
+    pub fn send(&self, event: NotifyEvent) {
+        let _ = self.tx.send(event);
+    }
+}
+
+async fn dispatch(mut rx: broadcast::Receiver<NotifyEvent>, webhook_url: String, chat_id: String, events: Vec<String>) {
+    if webhook_url.is_empty() {
+        return;
+    }
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .expect("reqwest client");
+
+    loop {
+        let event = match rx.recv().await {
+            Ok(event) => event,
+            Err(broadcast::error::RecvError::Lagged(n)) => {
+                tracing::warn!("Notifier dropped {n} events (webhook falling behind)");
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => return,
+        };
+        if !events.iter().any(|e| e == event.kind()) {
+            continue;
+        }
+        let body = serde_json::json!({ "chat_id": chat_id, "text": event.message() });
+        for attempt in 1..=MAX_ATTEMPTS {
+            match client.post(&webhook_url).json(&body).send().await {
+                Ok(resp) if resp.status().is_success() => break,
+                Ok(resp) => tracing::warn!("Notification webhook returned {} (attempt {attempt}/{MAX_ATTEMPTS})", resp.status()),
+                Err(e) => tracing::warn!("Notification webhook failed (attempt {attempt}/{MAX_ATTEMPTS}): {e}"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fill_kind_matches_the_fill_event_mask_entry() {
+        let event = NotifyEvent::Fill {
+            window: 1_700_000_000, btc_price: 97_150.0, side: "BUY_UP".into(),
+            edge_pct: 2.5, size_usdc: 5.0, session_pnl_usdc: 12.3,
+        };
+        assert_eq!(event.kind(), "fill");
+        assert!(event.message().contains("BUY_UP"));
+        assert!(event.message().contains("97150.00"));
+    }
+
+    #[test]
+    fn circuit_breaker_message_reports_wr_and_cooldown() {
+        let event = NotifyEvent::CircuitBreakerTripped { rolling_wr_pct: 20.0, cooldown_s: 1800, session_pnl_usdc: -8.5 };
+        assert_eq!(event.kind(), "circuit_breaker");
+        assert!(event.message().contains("20%"));
+        assert!(event.message().contains("1800s"));
+    }
+
+    #[test]
+    fn session_limit_message_reports_target_and_limit() {
+        let event = NotifyEvent::SessionLimitReached { session_pnl_usdc: 100.5, target_usdc: 100.0, limit_usdc: 50.0 };
+        assert_eq!(event.kind(), "session_limit");
+        assert!(event.message().contains("100.50"));
+        assert!(event.message().contains("-50.00"));
+    }
+
+    #[test]
+    fn max_consecutive_losses_kind_matches_the_event_mask_entry() {
+        let event = NotifyEvent::MaxConsecutiveLosses { consecutive_losses: 5, session_pnl_usdc: -3.0 };
+        assert_eq!(event.kind(), "max_consecutive_losses");
+        assert!(event.message().contains('5'));
+    }
+}
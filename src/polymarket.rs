@@ -5,12 +5,14 @@ use alloy::{
     sol,
     sol_types::{eip712_domain, SolStruct},
 };
+use crate::amount;
 use anyhow::{Context, Result};
 use base64::{Engine, engine::general_purpose};
 use hmac::{Hmac, Mac};
 use rand::Rng;
 use serde::Deserialize;
 use sha2::Sha256;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 const CLOB_BASE: &str = "https://clob.polymarket.com";
@@ -42,6 +44,27 @@ pub enum Side {
     Sell,
 }
 
+/// Which CTF Exchange signature scheme an order is signed under. Most
+/// Polymarket accounts trade through a proxy or Gnosis Safe rather than a
+/// bare EOA, so `maker` (who funds the order) and `signer` (who signs it)
+/// are not always the same address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum SignatureType {
+    Eoa = 0,
+    PolyProxy = 1,
+    PolyGnosisSafe = 2,
+}
+
+/// Funding setup for a non-EOA account: the proxy/Safe address that holds
+/// the funds (`Order.maker`) plus which signature scheme the exchange should
+/// expect. The EOA derived from the client's signer always stays `Order.signer`.
+#[derive(Debug, Clone, Copy)]
+pub struct FunderConfig {
+    pub funder: Address,
+    pub signature_type: SignatureType,
+}
+
 #[derive(Debug, Clone)]
 pub struct Market {
     pub condition_id: String,
@@ -53,7 +76,76 @@ pub struct Market {
 #[derive(Debug)]
 pub struct OrderResult {
     pub order_id: String,
+    /// `"matched"` (fully filled), `"live"` (resting, GTC/GTD), `"delayed"`
+    /// (held back by the matching engine), or a partial-fill status for
+    /// FAK orders — passed through from the API as-is.
     pub status: String,
+    /// Amount actually filled on `maker`'s side of the trade so far, in raw
+    /// 6-decimal USDC units — `0` for an order that hasn't matched at all
+    /// yet (e.g. a fresh GTC). Use `amount::raw_to_usdc` to display it.
+    pub making_amount: u128,
+    /// Amount actually filled on `taker`'s side of the trade so far, same
+    /// raw units as `making_amount`.
+    pub taking_amount: u128,
+}
+
+/// Selects how an order rests on (or is pulled from) the book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderType {
+    /// Fill-or-kill: matches immediately in full, or not at all.
+    Fok,
+    /// Good-til-cancelled: rests on the book until matched or cancelled.
+    Gtc,
+    /// Good-til-date: rests on the book until the given unix-seconds
+    /// expiration, then is dropped by the exchange.
+    Gtd(u64),
+    /// Fill-and-kill: matches whatever is available immediately, then
+    /// cancels the unfilled remainder instead of resting it.
+    Fak,
+}
+
+impl OrderType {
+    fn as_str(self) -> &'static str {
+        match self {
+            OrderType::Fok => "FOK",
+            OrderType::Gtc => "GTC",
+            OrderType::Gtd(_) => "GTD",
+            OrderType::Fak => "FAK",
+        }
+    }
+
+    /// Unix-seconds expiration to stamp into the order: 30s out for
+    /// FOK/FAK, which must match or be killed almost immediately, the
+    /// caller-supplied date for GTD, and `0` (none) for GTC.
+    fn expiration(self, now: u64) -> u64 {
+        match self {
+            OrderType::Fok | OrderType::Fak => now + 30,
+            OrderType::Gtd(at) => at,
+            OrderType::Gtc => 0,
+        }
+    }
+}
+
+/// Tracks the maker's current order nonce, mirroring the nonce-manager
+/// middleware idea from ethers-rs: every signed order is stamped with
+/// `current()`, and `cancel_all` bumps it so the exchange rejects every
+/// order still resting at the old nonce without having to cancel them one
+/// by one. Bumping only updates what this client signs with next — the
+/// on-chain nonce the exchange contract actually checks is advanced by the
+/// cancel-all request itself.
+#[derive(Debug, Default)]
+struct NonceManager {
+    current: AtomicU64,
+}
+
+impl NonceManager {
+    fn current(&self) -> u64 {
+        self.current.load(Ordering::SeqCst)
+    }
+
+    fn bump(&self) -> u64 {
+        self.current.fetch_add(1, Ordering::SeqCst) + 1
+    }
 }
 
 pub struct PolymarketClient {
@@ -61,8 +153,10 @@ pub struct PolymarketClient {
     api_key: String,
     api_secret_bytes: Vec<u8>, // pré-décodé base64 une seule fois
     passphrase: String,
-    signer: PrivateKeySigner,
+    signer: Box<dyn Signer + Send + Sync>,
     wallet_address: Address,
+    funder: Option<FunderConfig>,
+    nonce_manager: NonceManager,
 }
 
 // --- Réponses API (serde) ---
@@ -98,6 +192,15 @@ struct OrderResponse {
     #[serde(rename = "orderID")]
     order_id: String,
     status: String,
+    #[serde(rename = "makingAmount", default, deserialize_with = "amount::deserialize_opt")]
+    making_amount: Option<U256>,
+    #[serde(rename = "takingAmount", default, deserialize_with = "amount::deserialize_opt")]
+    taking_amount: Option<U256>,
+}
+
+#[derive(Deserialize)]
+struct OrderStatusResponse {
+    status: String,
 }
 
 #[derive(Deserialize)]
@@ -109,6 +212,8 @@ struct BookResponse {
 #[derive(Deserialize)]
 struct BookLevel {
     price: String,
+    // Fractional share quantity (e.g. "123.456789"), not a raw on-chain
+    // integer — `amount::parse` doesn't apply here, unlike tokenId/*Amount.
     size: String,
 }
 
@@ -126,13 +231,17 @@ pub struct BookData {
 }
 
 impl PolymarketClient {
+    /// Builds a client around any `alloy` signer — a raw private key, a
+    /// hardware wallet (Ledger/Trezor), or a remote signing service. The
+    /// wallet address is derived from the signer itself rather than passed
+    /// in separately, so it can never drift from what actually signs orders.
     pub fn new(
         api_key: String,
         api_secret: String,
         passphrase: String,
-        private_key: &str,
+        signer: Box<dyn Signer + Send + Sync>,
+        funder: Option<FunderConfig>,
     ) -> Result<Self> {
-        let signer: PrivateKeySigner = private_key.parse().context("Invalid private key")?;
         let wallet_address = signer.address();
         let api_secret_bytes = general_purpose::URL_SAFE
             .decode(&api_secret)
@@ -145,7 +254,40 @@ impl PolymarketClient {
             .timeout(Duration::from_secs(3))
             .build()?;
 
-        Ok(Self { http, api_key, api_secret_bytes, passphrase, signer, wallet_address })
+        Ok(Self {
+            http,
+            api_key,
+            api_secret_bytes,
+            passphrase,
+            signer,
+            wallet_address,
+            funder,
+            nonce_manager: NonceManager::default(),
+        })
+    }
+
+    /// Convenience constructor for the common case of a raw private key held
+    /// in process memory (e.g. from an env var). Prefer `new` with a
+    /// hardware or remote signer in production to keep the key off this host.
+    pub fn from_private_key(
+        api_key: String,
+        api_secret: String,
+        passphrase: String,
+        private_key: &str,
+        funder: Option<FunderConfig>,
+    ) -> Result<Self> {
+        let signer: PrivateKeySigner = private_key.parse().context("Invalid private key")?;
+        Self::new(api_key, api_secret, passphrase, Box::new(signer), funder)
+    }
+
+    /// The `(maker, signatureType)` pair for a new order: the proxy/Safe
+    /// address under `funder`, or the signer's own EOA address when trading
+    /// directly.
+    fn maker_and_signature_type(&self) -> (Address, u8) {
+        match &self.funder {
+            Some(cfg) => (cfg.funder, cfg.signature_type as u8),
+            None => (self.wallet_address, SignatureType::Eoa as u8),
+        }
     }
 
     /// Trouve le marché 5min BTC actif pour le window donné.
@@ -270,43 +412,88 @@ impl PolymarketClient {
         size_usdc: f64,
         price: f64,
         fee_rate_bps: u32,
+    ) -> Result<OrderResult> {
+        self.submit_order(token_id, side, size_usdc, price, fee_rate_bps, OrderType::Fok).await
+    }
+
+    /// Place un ordre GTC (maker, Good-Til-Cancelled) — reste ouvert sur le book
+    /// jusqu'à exécution ou annulation explicite via `cancel_order`.
+    pub async fn place_limit_order(
+        &self,
+        token_id: &str,
+        side: Side,
+        size_usdc: f64,
+        price: f64,
+        fee_rate_bps: u32,
+    ) -> Result<OrderResult> {
+        self.submit_order(token_id, side, size_usdc, price, fee_rate_bps, OrderType::Gtc).await
+    }
+
+    /// Places an order of the given type — useful for GTD (rests open until
+    /// a specific date) or FAK (takes whatever is immediately available then
+    /// cancels the remainder), in addition to the FOK/GTC already covered by
+    /// `place_order`/`place_limit_order`.
+    pub async fn place_order_as(
+        &self,
+        token_id: &str,
+        side: Side,
+        size_usdc: f64,
+        price: f64,
+        fee_rate_bps: u32,
+        order_type: OrderType,
+    ) -> Result<OrderResult> {
+        self.submit_order(token_id, side, size_usdc, price, fee_rate_bps, order_type).await
+    }
+
+    async fn submit_order(
+        &self,
+        token_id: &str,
+        side: Side,
+        size_usdc: f64,
+        price: f64,
+        fee_rate_bps: u32,
+        order_type: OrderType,
     ) -> Result<OrderResult> {
         let side_u8: u8 = if side == Side::Buy { 0 } else { 1 };
 
-        // Amounts en unités raw (6 décimales USDC), .round() évite les erreurs f64
+        // Amounts en unités raw (6 décimales USDC) ; le round() f64 reste
+        // inévitable ici puisque size_usdc/price arrivent déjà en f64 depuis
+        // le sizing de stratégie, mais c'est la seule conversion du genre.
         let (maker_amount, taker_amount) = if side == Side::Buy {
-            let maker = (size_usdc * 1e6).round() as u128;
-            let taker = ((size_usdc / price) * 1e6).round() as u128;
+            let maker = amount::usdc_to_raw(size_usdc);
+            let taker = amount::usdc_to_raw(size_usdc / price);
             (maker, taker)
         } else {
-            let maker = ((size_usdc / price) * 1e6).round() as u128;
-            let taker = (size_usdc * 1e6).round() as u128;
+            let maker = amount::usdc_to_raw(size_usdc / price);
+            let taker = amount::usdc_to_raw(size_usdc);
             (maker, taker)
         };
 
         let salt: u128 = rand::rng().random();
-        let expiration = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() + 30;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let expiration = order_type.expiration(now);
+        let (maker, signature_type) = self.maker_and_signature_type();
 
         let order = Order {
             salt: U256::from(salt),
-            maker: self.wallet_address,
+            maker,
             signer: self.wallet_address,
             taker: Address::ZERO,
-            tokenId: U256::from_str_radix(token_id, 10).context("Invalid token_id")?,
+            tokenId: amount::parse(token_id).context("Invalid token_id")?,
             makerAmount: U256::from(maker_amount),
             takerAmount: U256::from(taker_amount),
             expiration: U256::from(expiration),
-            nonce: U256::ZERO,
+            nonce: U256::from(self.nonce_manager.current()),
             feeRateBps: U256::from(fee_rate_bps),
             side: side_u8,
-            signatureType: 0, // EOA
+            signatureType: signature_type,
         };
 
         let signature = self.sign_order_eip712(&order).await?;
 
         let body = serde_json::json!({
             "owner": format!("{}", self.wallet_address),
-            "orderType": "FOK",
+            "orderType": order_type.as_str(),
             "order": {
                 "salt": order.salt.to_string(),
                 "maker": format!("{}", order.maker),
@@ -316,10 +503,10 @@ impl PolymarketClient {
                 "makerAmount": maker_amount.to_string(),
                 "takerAmount": taker_amount.to_string(),
                 "expiration": expiration.to_string(),
-                "nonce": "0",
+                "nonce": order.nonce.to_string(),
                 "feeRateBps": fee_rate_bps.to_string(),
                 "side": side_u8.to_string(),
-                "signatureType": 0,
+                "signatureType": signature_type,
                 "signature": signature,
             }
         });
@@ -343,7 +530,102 @@ impl PolymarketClient {
         }
         let result: OrderResponse = resp.json().await?;
 
-        Ok(OrderResult { order_id: result.order_id, status: result.status })
+        Ok(OrderResult {
+            order_id: result.order_id,
+            status: result.status,
+            making_amount: result.making_amount.and_then(|v| u128::try_from(v).ok()).unwrap_or(0),
+            taking_amount: result.taking_amount.and_then(|v| u128::try_from(v).ok()).unwrap_or(0),
+        })
+    }
+
+    /// Récupère le statut courant d'un ordre (ouvert, matched, cancelled...).
+    pub async fn get_order_status(&self, order_id: &str) -> Result<String> {
+        let path = format!("/order/{order_id}");
+        let headers = self.sign_hmac("GET", &path, "")?;
+
+        let mut req = self.http.get(format!("{CLOB_BASE}{path}"));
+        for (k, v) in &headers {
+            req = req.header(k, v);
+        }
+
+        let resp = req.send().await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Order-status API error ({status}): {body}");
+        }
+        let data: OrderStatusResponse = resp.json().await?;
+        Ok(data.status)
+    }
+
+    /// Annule un ordre GTC encore ouvert sur le book.
+    pub async fn cancel_order(&self, order_id: &str) -> Result<()> {
+        let body = serde_json::json!({ "orderID": order_id });
+        let body_str = body.to_string();
+        let path = "/order";
+        let headers = self.sign_hmac("DELETE", path, &body_str)?;
+
+        let mut req = self.http.delete(format!("{CLOB_BASE}{path}"))
+            .header("Content-Type", "application/json")
+            .body(body_str);
+        for (k, v) in &headers {
+            req = req.header(k, v);
+        }
+
+        let resp = req.send().await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Cancel-order API error ({status}): {body}");
+        }
+        Ok(())
+    }
+
+    /// Annule un lot d'ordres en une seule requête.
+    pub async fn cancel_orders(&self, order_ids: &[String]) -> Result<()> {
+        let body = serde_json::json!({ "orderIDs": order_ids });
+        let body_str = body.to_string();
+        let path = "/orders";
+        let headers = self.sign_hmac("DELETE", path, &body_str)?;
+
+        let mut req = self.http.delete(format!("{CLOB_BASE}{path}"))
+            .header("Content-Type", "application/json")
+            .body(body_str);
+        for (k, v) in &headers {
+            req = req.header(k, v);
+        }
+
+        let resp = req.send().await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Cancel-orders API error ({status}): {body}");
+        }
+        Ok(())
+    }
+
+    /// Annule tous les ordres ouverts du maker en bumpant son nonce : toute
+    /// signature faite à l'ancien nonce est rejetée par l'exchange sans
+    /// avoir à connaître chaque order id. Utile pour vider les quotes
+    /// restantes à la fermeture de la fenêtre de 5min plutôt que de compter
+    /// uniquement sur l'`expiration` de 30s des FOK.
+    pub async fn cancel_all(&self) -> Result<()> {
+        let path = "/cancel-all";
+        let headers = self.sign_hmac("DELETE", path, "")?;
+
+        let mut req = self.http.delete(format!("{CLOB_BASE}{path}"));
+        for (k, v) in &headers {
+            req = req.header(k, v);
+        }
+
+        let resp = req.send().await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Cancel-all API error ({status}): {body}");
+        }
+        self.nonce_manager.bump();
+        Ok(())
     }
 
     // --- Helpers internes ---
@@ -408,6 +690,81 @@ mod tests {
         assert_eq!(resp.base_fee, 1000);
     }
 
+    #[test]
+    fn from_private_key_derives_wallet_address_from_signer() {
+        // Well-known Anvil/Hardhat dev key #0 — safe to use in tests, never
+        // holds real funds.
+        let client = PolymarketClient::from_private_key(
+            "key".into(),
+            general_purpose::URL_SAFE.encode("secret"),
+            "pass".into(),
+            "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            format!("{:#x}", client.wallet_address),
+            "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266"
+        );
+    }
+
+    #[test]
+    fn maker_defaults_to_signer_address_without_a_funder() {
+        let client = PolymarketClient::from_private_key(
+            "key".into(),
+            general_purpose::URL_SAFE.encode("secret"),
+            "pass".into(),
+            "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+            None,
+        )
+        .unwrap();
+        let (maker, signature_type) = client.maker_and_signature_type();
+        assert_eq!(maker, client.wallet_address);
+        assert_eq!(signature_type, SignatureType::Eoa as u8);
+    }
+
+    #[test]
+    fn maker_uses_proxy_address_and_signature_type_when_configured() {
+        let funder_addr = address!("1111111111111111111111111111111111111111");
+        let client = PolymarketClient::from_private_key(
+            "key".into(),
+            general_purpose::URL_SAFE.encode("secret"),
+            "pass".into(),
+            "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+            Some(FunderConfig { funder: funder_addr, signature_type: SignatureType::PolyProxy }),
+        )
+        .unwrap();
+        let (maker, signature_type) = client.maker_and_signature_type();
+        assert_eq!(maker, funder_addr);
+        assert_eq!(signature_type, SignatureType::PolyProxy as u8);
+    }
+
+    #[test]
+    fn nonce_manager_starts_at_zero_and_bumps_monotonically() {
+        let nm = NonceManager::default();
+        assert_eq!(nm.current(), 0);
+        assert_eq!(nm.bump(), 1);
+        assert_eq!(nm.current(), 1);
+        assert_eq!(nm.bump(), 2);
+    }
+
+    #[test]
+    fn order_type_expiration_matches_each_type() {
+        let now = 1_700_000_000u64;
+        assert_eq!(OrderType::Fok.expiration(now), now + 30);
+        assert_eq!(OrderType::Fak.expiration(now), now + 30);
+        assert_eq!(OrderType::Gtc.expiration(now), 0);
+        assert_eq!(OrderType::Gtd(now + 3600).expiration(now), now + 3600);
+    }
+
+    #[test]
+    fn order_type_as_str_matches_api_names() {
+        assert_eq!(OrderType::Fok.as_str(), "FOK");
+        assert_eq!(OrderType::Gtc.as_str(), "GTC");
+        assert_eq!(OrderType::Gtd(0).as_str(), "GTD");
+        assert_eq!(OrderType::Fak.as_str(), "FAK");
+    }
+
     #[test]
     fn order_struct_uses_correct_fee_rate_bps() {
         let fee_rate_bps: u32 = 1000;
@@ -0,0 +1,313 @@
+//! Joint sizing across simultaneous candidate trades on correlated markets
+//! (e.g. a BTC and an ETH 5min up/down window trading at once). `evaluate`
+//! and `combinatorial::evaluate_combinatorial` each size their `Signal`
+//! independently against `Session::bankroll()`, so running several at once
+//! can silently stack highly correlated exposure — the same partition/
+//! combinatorial-exposure idea Zeitgeist's combinatorial betting applies to
+//! overlapping outcome sets, just across markets instead of within one.
+//! `allocate` shrinks each candidate's independently-computed Kelly size by
+//! how much of its risk is already "spoken for" by same-direction correlated
+//! bets, then caps the shrunk total at the same aggregate-loss ceiling
+//! (`session_loss_limit_usdc`) already enforced per-session in `evaluate`.
+//!
+//! `main.rs` currently only ever trades one BTC window at a time, so the
+//! live call site passes a 1x1 correlation matrix — the shrinkage term is
+//! always inert there (nothing to shrink against) and only the aggregate
+//! ceiling does real work, clamping the single signal's size to what's left
+//! of `session_loss_limit_usdc` against the live bankroll. The
+//! cross-market correlation shrinkage this module is named for only
+//! activates once a second simultaneous market (e.g. ETH alongside BTC) is
+//! actually traded.
+
+use crate::polymarket::Side;
+use crate::strategy::{Session, Signal, CalibrationMode, StrategyConfig};
+
+/// Why a correlation matrix was rejected.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PortfolioError {
+    MismatchedCorrelationMatrix { expected: usize, rows: usize },
+}
+
+impl std::fmt::Display for PortfolioError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PortfolioError::MismatchedCorrelationMatrix { expected, rows } =>
+                write!(f, "correlation matrix must be {expected}x{expected}, got {rows} rows"),
+        }
+    }
+}
+
+impl std::error::Error for PortfolioError {}
+
+/// Pearson correlation of two return/z-score series (e.g. the per-asset
+/// z-scores already derivable from `VolTracker::current_vol` and the live
+/// price move). Compares the trailing, equal-length overlap of both series.
+/// Returns 0.0 (treat as uncorrelated) with fewer than 2 overlapping points
+/// or zero variance in either series, rather than propagating a NaN.
+pub fn pairwise_correlation(a: &[f64], b: &[f64]) -> f64 {
+    let n = a.len().min(b.len());
+    if n < 2 {
+        return 0.0;
+    }
+    let a = &a[a.len() - n..];
+    let b = &b[b.len() - n..];
+    let mean_a = a.iter().sum::<f64>() / n as f64;
+    let mean_b = b.iter().sum::<f64>() / n as f64;
+
+    let mut cov = 0.0;
+    let mut var_a = 0.0;
+    let mut var_b = 0.0;
+    for i in 0..n {
+        let da = a[i] - mean_a;
+        let db = b[i] - mean_b;
+        cov += da * db;
+        var_a += da * da;
+        var_b += db * db;
+    }
+    if var_a <= 0.0 || var_b <= 0.0 {
+        return 0.0;
+    }
+    (cov / (var_a.sqrt() * var_b.sqrt())).clamp(-1.0, 1.0)
+}
+
+/// Solves a fractional-Kelly allocation across `signals`, one candidate per
+/// simultaneous market, under `correlation[i][j]` (a symmetric `n x n`
+/// matrix, e.g. built from `pairwise_correlation` over each market's recent
+/// z-scores).
+///
+/// Each signal already carries its own single-market Kelly size
+/// (`Signal::size_usdc`, from `evaluate`/`evaluate_combinatorial`). For
+/// candidate `i`, every other candidate `j` that's correlated *and* points
+/// the same way (same `Side`, positive correlation) is treated as exposure
+/// already "spoken for" — its size shrinks by `1 / (1 + sum of those
+/// same-direction correlations)`. Bets that offset (opposite sides, or
+/// negatively correlated) don't shrink each other at all, approaching full
+/// independent sizing. The shrunk sizes are then scaled down further, if
+/// needed, so their total never exceeds `config.session_loss_limit_usdc`
+/// (capped at the current bankroll) — the same aggregate-loss ceiling
+/// `evaluate` already checks per-session, just enforced jointly here.
+pub fn allocate(
+    signals: &[Signal],
+    correlation: &[Vec<f64>],
+    session: &Session,
+    config: &StrategyConfig,
+) -> Result<Vec<f64>, PortfolioError> {
+    let n = signals.len();
+    if correlation.len() != n || correlation.iter().any(|row| row.len() != n) {
+        return Err(PortfolioError::MismatchedCorrelationMatrix { expected: n, rows: correlation.len() });
+    }
+    if n == 0 {
+        return Ok(Vec::new());
+    }
+
+    let sign = |side: Side| if side == Side::Buy { 1.0 } else { -1.0 };
+
+    let mut sized: Vec<f64> = (0..n)
+        .map(|i| {
+            let same_direction_corr: f64 = (0..n)
+                .filter(|&j| j != i)
+                .map(|j| (correlation[i][j] * sign(signals[i].side) * sign(signals[j].side)).max(0.0))
+                .sum();
+            signals[i].size_usdc / (1.0 + same_direction_corr)
+        })
+        .collect();
+
+    let ceiling = config.session_loss_limit_usdc.min(session.bankroll()).max(0.0);
+    let total: f64 = sized.iter().sum();
+    if ceiling > 0.0 && total > ceiling {
+        let scale = ceiling / total;
+        for size in sized.iter_mut() {
+            *size *= scale;
+        }
+    }
+
+    Ok(sized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> StrategyConfig {
+        StrategyConfig {
+            max_bet_usdc: 10.0,
+            min_bet_usdc: 1.0,
+            min_shares: 5,
+            min_edge_pct: 1.0,
+            entry_seconds_before_end: 10,
+            session_profit_target_usdc: 1000.0,
+            session_loss_limit_usdc: 10.0,
+            fee_rate: 0.25,
+            min_market_price: 0.05,
+            max_market_price: 0.95,
+            min_delta_pct: 0.0,
+            max_spread: 1.0,
+            kelly_fraction: 0.2,
+            initial_bankroll_usdc: 40.0,
+            always_trade: false,
+            vol_confidence_multiplier: 4.0,
+            min_payout_ratio: 0.0,
+            min_book_imbalance: 0.0,
+            max_vol_5min_pct: 0.0,
+            min_ws_sources: 0,
+            circuit_breaker_window: 0,
+            circuit_breaker_min_wr: 0.0,
+            circuit_breaker_cooldown_s: 0,
+            min_implied_prob: 0.0,
+            max_consecutive_losses: 0,
+            student_t_df: 0.0,
+            min_z_score: 0.0,
+            max_model_divergence: 0.0,
+            quote_spread_pct: 0.0,
+            atr_window: 10,
+            exit_stop_atr_mult: 0.0,
+            exit_tp_atr_mult: 0.0,
+            exit_tp_window: 10,
+            fisher_window: 20,
+            fisher_extreme_threshold: 0.0,
+            min_vol_edge: 0.0,
+            roi_table: Vec::new(),
+            trailing_stop_pct: 0.0,
+            trailing_stop_bps: 0.0,
+            hard_stop_bps: 0.0,
+            min_momentum_exit: 0.0,
+            calibration_breakpoints: Vec::new(),
+            calibration_mode: CalibrationMode::Multiplier,
+            safety_spread_pct: 0.0,
+            trailing_stages: Vec::new(),
+            daily_fee_budget: 0.0,
+            daily_max_volume: 0.0,
+            feed_spread_pct: 0.0,
+            feed_skew_pct: 0.0,
+            symmetric_fee_model: false,
+            symmetric_fee_base_rate: 0.0,
+            consensus_max_deviation_pct: 0.0,
+        }
+    }
+
+    fn signal(side: Side, size_usdc: f64) -> Signal {
+        Signal {
+            side,
+            edge_pct: 5.0,
+            edge_brut_pct: 6.0,
+            fee_pct: 1.0,
+            implied_p_up: 0.55,
+            size_usdc,
+            price: 0.50,
+            implied_vol: 0.0,
+            vol_edge: 0.0,
+        }
+    }
+
+    #[test]
+    fn pairwise_correlation_of_identical_series_is_one() {
+        let a = [1.0, 2.0, 3.0, 4.0];
+        assert!((pairwise_correlation(&a, &a) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn pairwise_correlation_of_inverted_series_is_minus_one() {
+        let a = [1.0, 2.0, 3.0, 4.0];
+        let b = [4.0, 3.0, 2.0, 1.0];
+        assert!((pairwise_correlation(&a, &b) + 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn pairwise_correlation_needs_at_least_two_points() {
+        assert_eq!(pairwise_correlation(&[1.0], &[2.0]), 0.0);
+        assert_eq!(pairwise_correlation(&[], &[]), 0.0);
+    }
+
+    #[test]
+    fn pairwise_correlation_is_zero_with_no_variance() {
+        assert_eq!(pairwise_correlation(&[1.0, 1.0, 1.0], &[1.0, 2.0, 3.0]), 0.0);
+    }
+
+    #[test]
+    fn rejects_a_mismatched_correlation_matrix() {
+        let signals = vec![signal(Side::Buy, 3.0), signal(Side::Buy, 3.0)];
+        let correlation = vec![vec![1.0, 0.0]]; // only 1 row for 2 signals
+        let session = Session::new(40.0);
+        let config = test_config();
+        let result = allocate(&signals, &correlation, &session, &config);
+        assert!(matches!(result, Err(PortfolioError::MismatchedCorrelationMatrix { .. })));
+    }
+
+    #[test]
+    fn single_candidate_is_unshrunk_below_the_ceiling() {
+        let signals = vec![signal(Side::Buy, 3.0)];
+        let correlation = vec![vec![1.0]];
+        let session = Session::new(40.0);
+        let config = test_config();
+        let sizes = allocate(&signals, &correlation, &session, &config).unwrap();
+        assert!((sizes[0] - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn same_direction_correlated_bets_shrink_each_other() {
+        // Two BTC/ETH longs, fully correlated, same direction: each should
+        // shrink to half its independent size (1 / (1 + 1.0)).
+        let signals = vec![signal(Side::Buy, 2.0), signal(Side::Buy, 2.0)];
+        let correlation = vec![vec![1.0, 1.0], vec![1.0, 1.0]];
+        let session = Session::new(400.0); // large bankroll so the ceiling doesn't bind
+        let config = StrategyConfig { session_loss_limit_usdc: 100.0, ..test_config() };
+        let sizes = allocate(&signals, &correlation, &session, &config).unwrap();
+        assert!((sizes[0] - 1.0).abs() < 1e-9, "expected 2.0 / (1+1.0) = 1.0, got {}", sizes[0]);
+        assert!((sizes[1] - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn offsetting_bets_do_not_shrink_each_other() {
+        // Correlated markets but opposite sides (one long, one short) hedge
+        // rather than stack — no shrinkage from the correlation term.
+        let signals = vec![signal(Side::Buy, 2.0), signal(Side::Sell, 2.0)];
+        let correlation = vec![vec![1.0, 1.0], vec![1.0, 1.0]];
+        let session = Session::new(400.0);
+        let config = StrategyConfig { session_loss_limit_usdc: 100.0, ..test_config() };
+        let sizes = allocate(&signals, &correlation, &session, &config).unwrap();
+        assert!((sizes[0] - 2.0).abs() < 1e-9);
+        assert!((sizes[1] - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn negative_correlation_also_does_not_shrink() {
+        let signals = vec![signal(Side::Buy, 2.0), signal(Side::Buy, 2.0)];
+        let correlation = vec![vec![1.0, -1.0], vec![-1.0, 1.0]];
+        let session = Session::new(400.0);
+        let config = StrategyConfig { session_loss_limit_usdc: 100.0, ..test_config() };
+        let sizes = allocate(&signals, &correlation, &session, &config).unwrap();
+        assert!((sizes[0] - 2.0).abs() < 1e-9);
+        assert!((sizes[1] - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn aggregate_ceiling_scales_down_the_total_exposure() {
+        // $40 bankroll, 25% loss limit = $10 ceiling (mirrors
+        // paper_session_loss_limit_40_portfolio), but three $5 bets (no
+        // correlation shrinkage) sum to $15 — should scale to $10 total.
+        let signals = vec![signal(Side::Buy, 5.0), signal(Side::Buy, 5.0), signal(Side::Buy, 5.0)];
+        let correlation = vec![
+            vec![1.0, 0.0, 0.0],
+            vec![0.0, 1.0, 0.0],
+            vec![0.0, 0.0, 1.0],
+        ];
+        let session = Session::new(40.0);
+        let config = test_config(); // session_loss_limit_usdc: 10.0
+        let sizes = allocate(&signals, &correlation, &session, &config).unwrap();
+        let total: f64 = sizes.iter().sum();
+        assert!((total - 10.0).abs() < 1e-9, "total should be capped at the $10 ceiling, got {total}");
+    }
+
+    #[test]
+    fn ceiling_also_respects_the_current_bankroll_not_just_the_configured_limit() {
+        // Bankroll has shrunk to $5 (a losing session); even though the
+        // configured loss limit is $10, we shouldn't risk more than what's left.
+        let signals = vec![signal(Side::Buy, 8.0)];
+        let correlation = vec![vec![1.0]];
+        let mut session = Session::new(40.0);
+        session.pnl_usdc = -35.0; // bankroll now $5
+        let config = test_config();
+        let sizes = allocate(&signals, &correlation, &session, &config).unwrap();
+        assert!((sizes[0] - 5.0).abs() < 1e-9, "expected the $5 bankroll to bind, got {}", sizes[0]);
+    }
+}
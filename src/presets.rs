@@ -1,6 +1,6 @@
 #![allow(dead_code)]
 
-use crate::strategy::StrategyConfig;
+use crate::strategy::{CalibrationMode, StrategyConfig};
 
 /// Returns the "Sniper Conservateur" preset.
 /// GTC maker, edge>=3%, kelly=0.10, vol<0.08%.
@@ -31,6 +31,30 @@ pub fn sniper() -> StrategyConfig {
         circuit_breaker_cooldown_s: 900,
         min_implied_prob: 0.75,
         max_consecutive_losses: 6,
+        quote_spread_pct: 0.02,
+        atr_window: 12,
+        exit_stop_atr_mult: 1.5,
+        exit_tp_atr_mult: 2.5,
+        exit_tp_window: 12,
+        fisher_window: 30,
+        fisher_extreme_threshold: 1.5,
+        min_vol_edge: 0.08,
+        roi_table: vec![(300, 0.15), (120, 0.08), (30, 0.03)],
+        trailing_stop_pct: 0.30,
+        trailing_stop_bps: 150.0,
+        hard_stop_bps: 250.0,
+        min_momentum_exit: 0.3,
+        calibration_breakpoints: Vec::new(),
+        calibration_mode: CalibrationMode::Multiplier,
+        safety_spread_pct: 0.02,
+        trailing_stages: vec![(0.008, 0.35), (0.015, 0.25), (0.025, 0.15)],
+        daily_fee_budget: 3.0,
+        daily_max_volume: 60.0,
+        feed_spread_pct: 0.001,
+        feed_skew_pct: 0.0,
+        symmetric_fee_model: false,
+        symmetric_fee_base_rate: 0.0,
+        consensus_max_deviation_pct: 0.5,
     }
 }
 
@@ -63,6 +87,30 @@ pub fn conviction() -> StrategyConfig {
         circuit_breaker_cooldown_s: 1200,
         min_implied_prob: 0.80,
         max_consecutive_losses: 5,
+        quote_spread_pct: 0.02,
+        atr_window: 15,
+        exit_stop_atr_mult: 2.0,
+        exit_tp_atr_mult: 3.0,
+        exit_tp_window: 15,
+        fisher_window: 35,
+        fisher_extreme_threshold: 1.8,
+        min_vol_edge: 0.12,
+        roi_table: vec![(300, 0.20), (120, 0.10), (30, 0.05)],
+        trailing_stop_pct: 0.25,
+        trailing_stop_bps: 200.0,
+        hard_stop_bps: 350.0,
+        min_momentum_exit: 0.2,
+        calibration_breakpoints: Vec::new(),
+        calibration_mode: CalibrationMode::Multiplier,
+        safety_spread_pct: 0.02,
+        trailing_stages: vec![(0.012, 0.35), (0.02, 0.25), (0.035, 0.15)],
+        daily_fee_budget: 3.0,
+        daily_max_volume: 75.0,
+        feed_spread_pct: 0.001,
+        feed_skew_pct: 0.0,
+        symmetric_fee_model: false,
+        symmetric_fee_base_rate: 0.0,
+        consensus_max_deviation_pct: 0.75,
     }
 }
 
@@ -95,6 +143,30 @@ pub fn scalper() -> StrategyConfig {
         circuit_breaker_cooldown_s: 600,
         min_implied_prob: 0.85,
         max_consecutive_losses: 6,
+        quote_spread_pct: 0.01,
+        atr_window: 8,
+        exit_stop_atr_mult: 1.0,
+        exit_tp_atr_mult: 1.5,
+        exit_tp_window: 8,
+        fisher_window: 15,
+        fisher_extreme_threshold: 1.2,
+        min_vol_edge: 0.05,
+        roi_table: vec![(120, 0.06), (30, 0.02)],
+        trailing_stop_pct: 0.40,
+        trailing_stop_bps: 80.0,
+        hard_stop_bps: 150.0,
+        min_momentum_exit: 0.4,
+        calibration_breakpoints: Vec::new(),
+        calibration_mode: CalibrationMode::Multiplier,
+        safety_spread_pct: 0.02,
+        trailing_stages: vec![(0.004, 0.4), (0.008, 0.25), (0.015, 0.15)],
+        daily_fee_budget: 4.0,
+        daily_max_volume: 80.0,
+        feed_spread_pct: 0.0,
+        feed_skew_pct: 0.0,
+        symmetric_fee_model: false,
+        symmetric_fee_base_rate: 0.0,
+        consensus_max_deviation_pct: 1.0,
     }
 }
 
@@ -127,6 +199,30 @@ pub fn farm() -> StrategyConfig {
         circuit_breaker_cooldown_s: 0,
         min_implied_prob: 0.0,
         max_consecutive_losses: 0,
+        quote_spread_pct: 0.0,
+        atr_window: 10,
+        exit_stop_atr_mult: 0.0,
+        exit_tp_atr_mult: 0.0,
+        exit_tp_window: 10,
+        fisher_window: 20,
+        fisher_extreme_threshold: 0.0,
+        min_vol_edge: 0.0,
+        roi_table: Vec::new(),
+        trailing_stop_pct: 0.0,
+        trailing_stop_bps: 0.0,
+        hard_stop_bps: 0.0,
+        min_momentum_exit: 0.0,
+        calibration_breakpoints: Vec::new(),
+        calibration_mode: CalibrationMode::Multiplier,
+        safety_spread_pct: 0.0,
+        trailing_stages: Vec::new(),
+        daily_fee_budget: 0.0,
+        daily_max_volume: 0.0,
+        feed_spread_pct: 0.0,
+        feed_skew_pct: 0.0,
+        symmetric_fee_model: false,
+        symmetric_fee_base_rate: 0.0,
+        consensus_max_deviation_pct: 0.0,
     }
 }
 
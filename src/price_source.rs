@@ -0,0 +1,305 @@
+use alloy::primitives::Address;
+use alloy::providers::Provider;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::chainlink;
+use crate::exchanges::{BinanceSource, KrakenFeed};
+use crate::rtds::RtdsFeed;
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64
+}
+
+/// Last known price from a source, regardless of its origin (on-chain
+/// Chainlink, RTDS, or a CEX exchange).
+#[derive(Debug, Clone, Copy)]
+pub struct SourcePrice {
+    pub price_usd: f64,
+    pub updated_at_ms: u64,
+}
+
+impl SourcePrice {
+    /// Derives a bid/ask around this mid price from a `Spread`.
+    /// `ask = price * (1 + spread/2)`, `bid = price * (1 - spread/2)`.
+    pub fn bid_ask(self, spread: Spread) -> (f64, f64) {
+        let half = spread.pct() / 2.0;
+        (self.price_usd * (1.0 - half), self.price_usd * (1.0 + half))
+    }
+}
+
+/// Spread (as a fraction, e.g. 0.02 = 2%) applied around a reference price
+/// to derive a conservative bid/ask before the trade decision. Settable at
+/// startup via `config.toml` or `--spread`.
+#[derive(Debug, Clone, Copy)]
+pub struct Spread(f64);
+
+impl Spread {
+    /// Builds a `Spread` from a percentage (e.g. 0.02 = 2%). Rejects
+    /// negative values.
+    pub fn new(pct: f64) -> Result<Self> {
+        anyhow::ensure!(pct >= 0.0, "spread must be non-negative, got {pct}");
+        Ok(Self(pct))
+    }
+
+    pub fn pct(self) -> f64 {
+        self.0
+    }
+}
+
+/// Unified abstraction over anything able to supply a BTC/USD price. Lets
+/// callers hold a `Vec<Box<dyn PriceSource>>` and plug in/mock feeds in
+/// tests without touching `main.rs`'s call sites.
+#[async_trait]
+pub trait PriceSource: Send + Sync {
+    /// Source name, for logs.
+    fn name(&self) -> &'static str;
+
+    /// Last known price, or `None` if the source is stale/disconnected.
+    async fn latest_price(&self) -> Result<Option<SourcePrice>>;
+}
+
+/// On-chain Chainlink source — wraps an RPC provider and the feed address.
+pub struct ChainlinkSource<P> {
+    provider: P,
+    feed: Address,
+}
+
+impl<P> ChainlinkSource<P> {
+    pub fn new(provider: P, feed: Address) -> Self {
+        Self { provider, feed }
+    }
+}
+
+#[async_trait]
+impl<P: Provider + Send + Sync> PriceSource for ChainlinkSource<P> {
+    fn name(&self) -> &'static str {
+        "Chainlink"
+    }
+
+    async fn latest_price(&self) -> Result<Option<SourcePrice>> {
+        let data = chainlink::fetch_price(&self.provider, self.feed).await?;
+        Ok(Some(SourcePrice {
+            price_usd: data.price_usd,
+            updated_at_ms: data.updated_at.saturating_mul(1000),
+        }))
+    }
+}
+
+#[async_trait]
+impl PriceSource for RtdsFeed {
+    fn name(&self) -> &'static str {
+        "RTDS"
+    }
+
+    async fn latest_price(&self) -> Result<Option<SourcePrice>> {
+        Ok(self.latest().map(|price_usd| SourcePrice { price_usd, updated_at_ms: now_ms() }))
+    }
+}
+
+#[async_trait]
+impl PriceSource for BinanceSource {
+    fn name(&self) -> &'static str {
+        "Binance"
+    }
+
+    async fn latest_price(&self) -> Result<Option<SourcePrice>> {
+        Ok(self.latest().map(|(price_usd, updated_at_ms)| SourcePrice { price_usd, updated_at_ms }))
+    }
+}
+
+#[async_trait]
+impl PriceSource for KrakenFeed {
+    fn name(&self) -> &'static str {
+        "Kraken"
+    }
+
+    async fn latest_price(&self) -> Result<Option<SourcePrice>> {
+        Ok(self.latest().map(|price_usd| SourcePrice { price_usd, updated_at_ms: now_ms() }))
+    }
+}
+
+/// Tries several sources in priority order, falling through to the next one
+/// as soon as the current one returns `None` (stale/disconnected) or fails.
+/// Lets the bot, for example, silently degrade from RTDS to on-chain
+/// Chainlink.
+pub struct CompositeSource {
+    sources: Vec<Box<dyn PriceSource>>,
+}
+
+impl CompositeSource {
+    pub fn new(sources: Vec<Box<dyn PriceSource>>) -> Self {
+        Self { sources }
+    }
+}
+
+#[async_trait]
+impl PriceSource for CompositeSource {
+    fn name(&self) -> &'static str {
+        "Composite"
+    }
+
+    async fn latest_price(&self) -> Result<Option<SourcePrice>> {
+        for source in &self.sources {
+            match source.latest_price().await {
+                Ok(Some(price)) => return Ok(Some(price)),
+                Ok(None) => continue,
+                Err(e) => {
+                    tracing::warn!("[{}] price source error: {e:#}", source.name());
+                    continue;
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Result of a cross-source validation: consensus price and health of the
+/// sources that participated in the computation.
+#[derive(Debug, Clone)]
+pub struct ConsensusReport {
+    pub consensus_price: f64,
+    pub agreeing_sources: usize,
+    pub outliers: Vec<&'static str>,
+}
+
+/// Computes a consensus price from each source's currently fresh price, and
+/// rejects sources that diverge too much to guard against a manipulated or
+/// malfunctioning feed. Computes the median of the supplied prices, then
+/// discards any source whose absolute deviation from that median exceeds
+/// `max_deviation_pct` (e.g. 1.0 = 1%). If fewer than two sources remain in
+/// agreement, returns `None` so the strategy halts rather than trading on a
+/// questionable price.
+pub fn validate_consensus(prices: &[(&'static str, f64)], max_deviation_pct: f64) -> Option<ConsensusReport> {
+    if prices.is_empty() {
+        return None;
+    }
+    let mut sorted: Vec<f64> = prices.iter().map(|(_, p)| *p).collect();
+    sorted.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap());
+    let mid = sorted.len() / 2;
+    let median = if sorted.len() % 2 == 1 {
+        sorted[mid]
+    } else {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    };
+
+    let mut agreeing_sources = 0usize;
+    let mut outliers = Vec::new();
+    for (name, price) in prices {
+        let deviation_pct = ((price - median) / median).abs() * 100.0;
+        if deviation_pct <= max_deviation_pct {
+            agreeing_sources += 1;
+        } else {
+            outliers.push(*name);
+        }
+    }
+
+    if agreeing_sources < 2 {
+        return None;
+    }
+
+    Some(ConsensusReport { consensus_price: median, agreeing_sources, outliers })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    struct MockSource {
+        name: &'static str,
+        price: Option<f64>,
+        fails: AtomicBool,
+    }
+
+    #[async_trait]
+    impl PriceSource for MockSource {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        async fn latest_price(&self) -> Result<Option<SourcePrice>> {
+            if self.fails.load(Ordering::Relaxed) {
+                anyhow::bail!("mock source failure");
+            }
+            Ok(self.price.map(|price_usd| SourcePrice { price_usd, updated_at_ms: now_ms() }))
+        }
+    }
+
+    fn mock(name: &'static str, price: Option<f64>) -> Box<dyn PriceSource> {
+        Box::new(MockSource { name, price, fails: AtomicBool::new(false) })
+    }
+
+    #[tokio::test]
+    async fn composite_uses_first_available_source() {
+        let composite = CompositeSource::new(vec![mock("a", Some(97_100.0)), mock("b", Some(97_200.0))]);
+        let price = composite.latest_price().await.unwrap().unwrap();
+        assert!((price.price_usd - 97_100.0).abs() < 0.01);
+    }
+
+    #[tokio::test]
+    async fn composite_falls_back_when_first_is_stale() {
+        let composite = CompositeSource::new(vec![mock("a", None), mock("b", Some(97_200.0))]);
+        let price = composite.latest_price().await.unwrap().unwrap();
+        assert!((price.price_usd - 97_200.0).abs() < 0.01);
+    }
+
+    #[tokio::test]
+    async fn composite_falls_back_on_error() {
+        let failing: Box<dyn PriceSource> =
+            Box::new(MockSource { name: "a", price: Some(1.0), fails: AtomicBool::new(true) });
+        let composite = CompositeSource::new(vec![failing, mock("b", Some(97_200.0))]);
+        let price = composite.latest_price().await.unwrap().unwrap();
+        assert!((price.price_usd - 97_200.0).abs() < 0.01);
+    }
+
+    #[tokio::test]
+    async fn composite_returns_none_when_all_sources_stale() {
+        let composite = CompositeSource::new(vec![mock("a", None), mock("b", None)]);
+        assert!(composite.latest_price().await.unwrap().is_none());
+    }
+
+    #[test]
+    fn spread_rejects_negative_pct() {
+        assert!(Spread::new(-0.01).is_err());
+    }
+
+    #[test]
+    fn zero_spread_bid_equals_ask_equals_mid() {
+        let price = SourcePrice { price_usd: 97_150.0, updated_at_ms: 0 };
+        let (bid, ask) = price.bid_ask(Spread::new(0.0).unwrap());
+        assert!((bid - price.price_usd).abs() < 1e-9);
+        assert!((ask - price.price_usd).abs() < 1e-9);
+    }
+
+    #[test]
+    fn two_pct_spread_around_known_btc_price() {
+        let price = SourcePrice { price_usd: 100_000.0, updated_at_ms: 0 };
+        let (bid, ask) = price.bid_ask(Spread::new(0.02).unwrap());
+        assert!((bid - 99_000.0).abs() < 1e-9);
+        assert!((ask - 101_000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn consensus_none_with_single_source() {
+        let prices = [("chainlink", 97_150.0)];
+        assert!(validate_consensus(&prices, 1.0).is_none());
+    }
+
+    #[test]
+    fn consensus_none_when_two_sources_disagree() {
+        // ~3% apart — both flagged as outliers relative to their own median.
+        let prices = [("chainlink", 97_000.0), ("rtds", 100_000.0)];
+        assert!(validate_consensus(&prices, 1.0).is_none());
+    }
+
+    #[test]
+    fn consensus_flags_single_outlier_of_three() {
+        let prices = [("chainlink", 97_100.0), ("rtds", 97_150.0), ("binance", 99_500.0)];
+        let report = validate_consensus(&prices, 1.0).unwrap();
+        assert_eq!(report.agreeing_sources, 2);
+        assert_eq!(report.outliers, vec!["binance"]);
+        assert!((report.consensus_price - 97_150.0).abs() < 0.01);
+    }
+}
@@ -14,20 +14,32 @@ pub struct RtdsPrice {
     pub timestamp_ms: u64,
 }
 
-/// Feed RTDS Polymarket — prix Chainlink Data Streams utilisé pour le settlement.
+/// State of the RTDS WS connection, to distinguish "stale because
+/// disconnected" from "connected but quiet" without having to parse logs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FeedStatus {
+    Connecting,
+    Subscribed,
+    Live { last_update_ms: u64 },
+    Disconnected { reconnects: u32 },
+}
+
+/// Polymarket RTDS feed — Chainlink Data Streams price used for settlement.
 pub struct RtdsFeed {
     rx: watch::Receiver<Option<RtdsPrice>>,
+    status_rx: watch::Receiver<FeedStatus>,
 }
 
 impl RtdsFeed {
-    /// Démarre la connexion WS au RTDS en background. Non-bloquant.
+    /// Starts the WS connection to RTDS in the background. Non-blocking.
     pub async fn start(ws_url: &str, symbol: &str) -> Self {
         let (tx, rx) = watch::channel(None);
-        tokio::spawn(ws_loop(ws_url.to_string(), symbol.to_string(), tx));
-        Self { rx }
+        let (status_tx, status_rx) = watch::channel(FeedStatus::Connecting);
+        tokio::spawn(ws_loop(ws_url.to_string(), symbol.to_string(), tx, status_tx));
+        Self { rx, status_rx }
     }
 
-    /// Dernier prix RTDS si frais (<5s), sinon None.
+    /// Latest RTDS price if fresh (<5s), else None.
     pub fn latest(&self) -> Option<f64> {
         let slot = (*self.rx.borrow())?;
         let now = now_ms();
@@ -37,19 +49,30 @@ impl RtdsFeed {
             None
         }
     }
+
+    /// Current connection state (connecting, subscribed, live, disconnected).
+    pub fn status(&self) -> FeedStatus {
+        *self.status_rx.borrow()
+    }
 }
 
 fn now_ms() -> u64 {
     SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64
 }
 
-/// Boucle de reconnexion automatique.
+/// Automatic reconnection loop.
 /// Exponential backoff: 2s → 4s → 8s → … → 30s max.
-async fn ws_loop(url: String, symbol: String, tx: watch::Sender<Option<RtdsPrice>>) {
+async fn ws_loop(
+    url: String,
+    symbol: String,
+    tx: watch::Sender<Option<RtdsPrice>>,
+    status_tx: watch::Sender<FeedStatus>,
+) {
     let mut backoff_s = 2u64;
     let mut reconnects = 0u32;
     loop {
-        let result = run_rtds(&url, &symbol, &tx).await;
+        let _ = status_tx.send(FeedStatus::Connecting);
+        let result = run_rtds(&url, &symbol, &tx, &status_tx).await;
         let _ = tx.send(None); // Clear on disconnect
 
         match result {
@@ -63,6 +86,7 @@ async fn ws_loop(url: String, symbol: String, tx: watch::Sender<Option<RtdsPrice
                 backoff_s = (backoff_s * 2).min(30);
             }
         }
+        let _ = status_tx.send(FeedStatus::Disconnected { reconnects });
         tokio::time::sleep(Duration::from_secs(backoff_s)).await;
     }
 }
@@ -82,11 +106,16 @@ struct RtdsPayload {
     value: f64,
 }
 
-async fn run_rtds(url: &str, symbol: &str, tx: &watch::Sender<Option<RtdsPrice>>) -> Result<()> {
+async fn run_rtds(
+    url: &str,
+    symbol: &str,
+    tx: &watch::Sender<Option<RtdsPrice>>,
+    status_tx: &watch::Sender<FeedStatus>,
+) -> Result<()> {
     let (mut ws, _) = connect_async(url).await.context("RTDS connect")?;
     tracing::info!("[RTDS] WS connected to {url}");
 
-    // Subscribe to crypto_prices_chainlink for the symbol
+    // Subscribe to crypto_prices_chainlink for the symbol.
     let sub = serde_json::json!({
         "action": "subscribe",
         "subscriptions": [{
@@ -110,16 +139,19 @@ async fn run_rtds(url: &str, symbol: &str, tx: &watch::Sender<Option<RtdsPrice>>
                             if m.msg_type.as_deref() == Some("subscribed") {
                                 tracing::info!("[RTDS] Subscription confirmed: {}",
                                     m.topic.as_deref().unwrap_or("unknown"));
+                                let _ = status_tx.send(FeedStatus::Subscribed);
                             }
                             if m.topic.as_deref() == Some("crypto_prices_chainlink")
                                 && m.msg_type.as_deref() == Some("update")
                             {
                                 if let Some(p) = m.payload {
                                     if p.symbol == symbol && p.value > 0.0 {
+                                        let last_update_ms = now_ms();
                                         let _ = tx.send(Some(RtdsPrice {
                                             price: p.value,
-                                            timestamp_ms: now_ms(),
+                                            timestamp_ms: last_update_ms,
                                         }));
+                                        let _ = status_tx.send(FeedStatus::Live { last_update_ms });
                                     }
                                 }
                             }
@@ -165,14 +197,20 @@ mod tests {
         assert!(m.payload.is_none());
     }
 
+    fn make_feed(price: Option<RtdsPrice>, status: FeedStatus) -> RtdsFeed {
+        let (tx, rx) = watch::channel(price);
+        let (status_tx, status_rx) = watch::channel(status);
+        std::mem::forget(tx);
+        std::mem::forget(status_tx);
+        RtdsFeed { rx, status_rx }
+    }
+
     #[test]
     fn rtds_feed_returns_fresh_price() {
-        let (tx, rx) = watch::channel(Some(RtdsPrice {
-            price: 97150.0,
-            timestamp_ms: now_ms(),
-        }));
-        std::mem::forget(tx);
-        let feed = RtdsFeed { rx };
+        let feed = make_feed(
+            Some(RtdsPrice { price: 97150.0, timestamp_ms: now_ms() }),
+            FeedStatus::Live { last_update_ms: now_ms() },
+        );
         let price = feed.latest();
         assert!(price.is_some());
         assert!((price.unwrap() - 97150.0).abs() < 0.01);
@@ -180,20 +218,28 @@ mod tests {
 
     #[test]
     fn rtds_feed_returns_none_when_stale() {
-        let (tx, rx) = watch::channel(Some(RtdsPrice {
-            price: 97150.0,
-            timestamp_ms: now_ms().saturating_sub(10_000), // 10s old
-        }));
-        std::mem::forget(tx);
-        let feed = RtdsFeed { rx };
+        let feed = make_feed(
+            Some(RtdsPrice { price: 97150.0, timestamp_ms: now_ms().saturating_sub(10_000) }),
+            FeedStatus::Live { last_update_ms: now_ms().saturating_sub(10_000) },
+        );
         assert!(feed.latest().is_none());
     }
 
+    #[test]
+    fn rtds_feed_status_defaults_to_connecting() {
+        let feed = make_feed(None, FeedStatus::Connecting);
+        assert_eq!(feed.status(), FeedStatus::Connecting);
+    }
+
+    #[test]
+    fn rtds_feed_status_reports_disconnected_with_reconnect_count() {
+        let feed = make_feed(None, FeedStatus::Disconnected { reconnects: 3 });
+        assert_eq!(feed.status(), FeedStatus::Disconnected { reconnects: 3 });
+    }
+
     #[test]
     fn rtds_feed_returns_none_when_empty() {
-        let (tx, rx) = watch::channel(None);
-        std::mem::forget(tx);
-        let feed = RtdsFeed { rx };
+        let feed = make_feed(None, FeedStatus::Connecting);
         assert!(feed.latest().is_none());
     }
 }
@@ -0,0 +1,362 @@
+//! Monte-Carlo forward projection of `evaluate` across many synthetic
+//! trading sessions, for offline strategy tuning — the same shape as FSRS
+//! simulating a deck forward over a horizon and then optimizing
+//! `desired_retention` within fixed bounds, just over `min_edge_pct` and
+//! the vol confidence multiplier instead of a review-scheduling knob.
+//!
+//! `simulate` draws synthetic `TradeContext`s from a caller-supplied
+//! `ContextSampler`, runs them through `evaluate` exactly as the live bot
+//! would, and resolves any signal that fires with a Bernoulli draw on its
+//! own implied probability — so the projection is honest about the
+//! strategy's actual filters, not a hand-rolled approximation of them.
+//! `optimal_params` then grid-searches over that projection.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::strategy::{evaluate, Session, CalibrationMode, StrategyConfig, TradeContext};
+
+/// Draws one synthetic `TradeContext` per trade opportunity, using `rng`
+/// for any randomness — implementors encode whatever distributional
+/// assumptions the sweep should test under (e.g. a historical bootstrap, a
+/// parametric vol model, or `market_path::MarketPathGenerator`'s running
+/// synthetic price path). `&mut self` because a realistic sampler carries
+/// per-session state (a running price, a rolling window) forward across
+/// calls rather than drawing iid contexts.
+pub trait ContextSampler {
+    fn sample(&mut self, rng: &mut StdRng) -> TradeContext;
+
+    /// Resets any per-session state before the next synthetic session
+    /// starts (e.g. rewind a running price path to its starting point).
+    /// Stateless samplers can leave this as a no-op.
+    fn reset(&mut self) {}
+}
+
+/// Parameters for one Monte-Carlo run: how many synthetic sessions to
+/// project, how many trade opportunities each session sees, the starting
+/// bankroll, and the RNG seed (fixed per run so `optimal_params` can
+/// compare grid points on the same synthetic draws).
+#[derive(Debug, Clone, Copy)]
+pub struct SimulatorConfig {
+    pub sessions: usize,
+    pub trades_per_session: usize,
+    pub starting_bankroll: f64,
+    pub seed: u64,
+}
+
+/// Aggregate result of a `simulate` run across all projected sessions.
+#[derive(Debug, Clone, Copy)]
+pub struct SimOutcome {
+    pub mean_terminal_bankroll: f64,
+    pub mean_win_rate: f64,
+    /// Worst `Session::session_drawdown_pct` seen across all sessions.
+    pub worst_session_drawdown_pct: f64,
+}
+
+/// Projects `config.sessions` independent synthetic sessions of
+/// `config.trades_per_session` trade opportunities each. For every
+/// opportunity, draws a `TradeContext` from `sampler`, runs `evaluate`, and
+/// if a signal fires resolves win/loss with a Bernoulli draw on
+/// `Signal::implied_p_up` — the payout is the digital option's standard
+/// cost-vs-$1-payout (`size_usdc * (1 - price) / price` on a win,
+/// `-size_usdc` on a loss), fed into `Session::record_trade`.
+pub fn simulate(
+    config: &SimulatorConfig,
+    strategy: &StrategyConfig,
+    sampler: &mut dyn ContextSampler,
+) -> SimOutcome {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut terminal_bankrolls = Vec::with_capacity(config.sessions);
+    let mut win_rates = Vec::with_capacity(config.sessions);
+    let mut worst_drawdown_pct = 0.0_f64;
+
+    for _ in 0..config.sessions {
+        sampler.reset();
+        let mut session = Session::new(config.starting_bankroll);
+        for _ in 0..config.trades_per_session {
+            let ctx = sampler.sample(&mut rng);
+            if let Some(signal) = evaluate(&ctx, &session, strategy) {
+                let won = rng.random::<f64>() < signal.implied_p_up;
+                let pnl = if won {
+                    signal.size_usdc * (1.0 - signal.price) / signal.price
+                } else {
+                    -signal.size_usdc
+                };
+                session.record_trade(pnl);
+            }
+        }
+        terminal_bankrolls.push(session.bankroll());
+        win_rates.push(session.win_rate());
+        worst_drawdown_pct = worst_drawdown_pct.max(session.session_drawdown_pct());
+    }
+
+    let n = config.sessions.max(1) as f64;
+    SimOutcome {
+        mean_terminal_bankroll: terminal_bankrolls.iter().sum::<f64>() / n,
+        mean_win_rate: win_rates.iter().sum::<f64>() / n,
+        worst_session_drawdown_pct: worst_drawdown_pct,
+    }
+}
+
+/// Inclusive `[min, max]` bounds for one grid-searched parameter, sampled
+/// at `steps` evenly-spaced points (`steps == 1` collapses to just `min`;
+/// `steps == 0` yields no points).
+#[derive(Debug, Clone, Copy)]
+pub struct ParamRange {
+    pub min: f64,
+    pub max: f64,
+    pub steps: usize,
+}
+
+impl ParamRange {
+    fn values(&self) -> Vec<f64> {
+        match self.steps {
+            0 => Vec::new(),
+            1 => vec![self.min],
+            steps => {
+                let step = (self.max - self.min) / (steps - 1) as f64;
+                (0..steps).map(|i| self.min + step * i as f64).collect()
+            }
+        }
+    }
+}
+
+/// Bounds for `optimal_params`'s grid search over `min_edge_pct` and the
+/// vol confidence multiplier, plus the drawdown threshold beyond which a
+/// candidate's score is penalized.
+#[derive(Debug, Clone, Copy)]
+pub struct SearchRanges {
+    pub min_edge_pct: ParamRange,
+    pub vol_confidence_multiplier: ParamRange,
+    /// Session drawdown (%) beyond which a candidate's score is penalized
+    /// — mirrors the loss-limit `evaluate` already enforces per-session,
+    /// applied here across the whole swept grid.
+    pub max_acceptable_drawdown_pct: f64,
+}
+
+/// One grid point's result: the swept parameters, its `SimOutcome`, and the
+/// drawdown-penalized score used to rank candidates.
+#[derive(Debug, Clone, Copy)]
+pub struct ParamCandidate {
+    pub min_edge_pct: f64,
+    pub vol_confidence_multiplier: f64,
+    pub outcome: SimOutcome,
+    pub score: f64,
+}
+
+/// Grid-searches `min_edge_pct` and `vol_confidence_multiplier` within
+/// `ranges`, running a full `simulate` at each grid point. Scores each
+/// candidate by mean terminal bankroll, penalized when
+/// `worst_session_drawdown_pct` exceeds `ranges.max_acceptable_drawdown_pct`
+/// — so a parameter set that wins more only by also blowing through the
+/// acceptable drawdown loses to a steadier one. Returns the best-scoring
+/// candidate, or `None` if either range has zero steps.
+pub fn optimal_params(
+    config: &SimulatorConfig,
+    base_strategy: &StrategyConfig,
+    ranges: &SearchRanges,
+    sampler: &mut dyn ContextSampler,
+) -> Option<ParamCandidate> {
+    let edges = ranges.min_edge_pct.values();
+    let multipliers = ranges.vol_confidence_multiplier.values();
+    if edges.is_empty() || multipliers.is_empty() {
+        return None;
+    }
+
+    let mut best: Option<ParamCandidate> = None;
+    for &min_edge_pct in &edges {
+        for &vol_confidence_multiplier in &multipliers {
+            let strategy = StrategyConfig {
+                min_edge_pct,
+                vol_confidence_multiplier,
+                ..base_strategy.clone()
+            };
+            // `simulate` reseeds from `config.seed` and calls
+            // `sampler.reset()` before each session, so every grid point
+            // walks the same synthetic draws (e.g. `MarketPathGenerator`
+            // restarts from the same starting price each time).
+            let outcome = simulate(config, &strategy, sampler);
+            let mut score = outcome.mean_terminal_bankroll;
+            if outcome.worst_session_drawdown_pct > ranges.max_acceptable_drawdown_pct {
+                let excess = outcome.worst_session_drawdown_pct - ranges.max_acceptable_drawdown_pct;
+                score /= 1.0 + excess / 10.0;
+            }
+            let candidate = ParamCandidate { min_edge_pct, vol_confidence_multiplier, outcome, score };
+            let is_better = match &best {
+                Some(b) => candidate.score > b.score,
+                None => true,
+            };
+            if is_better {
+                best = Some(candidate);
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> StrategyConfig {
+        StrategyConfig {
+            max_bet_usdc: 5.0,
+            min_bet_usdc: 1.0,
+            min_shares: 5,
+            min_edge_pct: 1.0,
+            entry_seconds_before_end: 10,
+            session_profit_target_usdc: 1000.0,
+            session_loss_limit_usdc: 1000.0,
+            fee_rate: 0.25,
+            min_market_price: 0.05,
+            max_market_price: 0.95,
+            min_delta_pct: 0.0,
+            max_spread: 1.0,
+            kelly_fraction: 0.2,
+            initial_bankroll_usdc: 40.0,
+            always_trade: false,
+            vol_confidence_multiplier: 4.0,
+            min_payout_ratio: 0.0,
+            min_book_imbalance: 0.0,
+            max_vol_5min_pct: 0.0,
+            min_ws_sources: 0,
+            circuit_breaker_window: 0,
+            circuit_breaker_min_wr: 0.0,
+            circuit_breaker_cooldown_s: 0,
+            min_implied_prob: 0.0,
+            max_consecutive_losses: 0,
+            student_t_df: 0.0,
+            min_z_score: 0.0,
+            max_model_divergence: 0.0,
+            quote_spread_pct: 0.0,
+            atr_window: 10,
+            exit_stop_atr_mult: 0.0,
+            exit_tp_atr_mult: 0.0,
+            exit_tp_window: 10,
+            fisher_window: 20,
+            fisher_extreme_threshold: 0.0,
+            min_vol_edge: 0.0,
+            roi_table: Vec::new(),
+            trailing_stop_pct: 0.0,
+            trailing_stop_bps: 0.0,
+            hard_stop_bps: 0.0,
+            min_momentum_exit: 0.0,
+            calibration_breakpoints: Vec::new(),
+            calibration_mode: CalibrationMode::Multiplier,
+            safety_spread_pct: 0.0,
+            trailing_stages: Vec::new(),
+            daily_fee_budget: 0.0,
+            daily_max_volume: 0.0,
+            feed_spread_pct: 0.0,
+            feed_skew_pct: 0.0,
+            symmetric_fee_model: false,
+            symmetric_fee_base_rate: 0.0,
+            consensus_max_deviation_pct: 0.0,
+        }
+    }
+
+    /// Always-fires sampler: returns the same mispriced `TradeContext` on
+    /// every call, regardless of `rng` — deterministic signal generation
+    /// so tests only need to control the Bernoulli win/loss draw.
+    struct FixedSampler(TradeContext);
+
+    impl ContextSampler for FixedSampler {
+        fn sample(&mut self, _rng: &mut StdRng) -> TradeContext {
+            self.0.clone()
+        }
+    }
+
+    fn mispriced_ctx() -> TradeContext {
+        TradeContext {
+            start_price: 100_000.0,
+            chainlink_price: 100_150.0,
+            exchange_price: Some(100_150.0),
+            rtds_price: None,
+            market_up_price: 0.50,
+            seconds_remaining: 5,
+            fee_rate: 0.25,
+            vol_5min_pct: 0.05,
+            spread: 0.0,
+            book_imbalance: 0.0,
+            num_ws_sources: 0,
+            micro_vol: 0.0,
+            momentum_ratio: 1.0,
+            fisher: 0.0,
+            fisher_prev: 0.0,
+            max_drawdown_bps: 0.0,
+        }
+    }
+
+    #[test]
+    fn simulate_with_zero_trades_leaves_bankroll_unchanged() {
+        let config = SimulatorConfig { sessions: 3, trades_per_session: 0, starting_bankroll: 40.0, seed: 1 };
+        let outcome = simulate(&config, &test_config(), &mut FixedSampler(mispriced_ctx()));
+        assert_eq!(outcome.mean_terminal_bankroll, 40.0);
+        assert_eq!(outcome.mean_win_rate, 0.0);
+        assert_eq!(outcome.worst_session_drawdown_pct, 0.0);
+    }
+
+    #[test]
+    fn simulate_is_reproducible_for_the_same_seed() {
+        let config = SimulatorConfig { sessions: 10, trades_per_session: 20, starting_bankroll: 40.0, seed: 42 };
+        let mut sampler = FixedSampler(mispriced_ctx());
+        let a = simulate(&config, &test_config(), &mut sampler);
+        let b = simulate(&config, &test_config(), &mut sampler);
+        assert_eq!(a.mean_terminal_bankroll, b.mean_terminal_bankroll);
+        assert_eq!(a.mean_win_rate, b.mean_win_rate);
+        assert_eq!(a.worst_session_drawdown_pct, b.worst_session_drawdown_pct);
+    }
+
+    #[test]
+    fn a_certain_winner_only_ever_grows_the_bankroll() {
+        // implied_p_up effectively 1.0 on every fired signal means the
+        // Bernoulli draw can't ever resolve to a loss.
+        let mut ctx = mispriced_ctx();
+        ctx.market_up_price = 0.10;
+        let config = SimulatorConfig { sessions: 5, trades_per_session: 10, starting_bankroll: 40.0, seed: 7 };
+        let outcome = simulate(&config, &test_config(), &mut FixedSampler(ctx));
+        assert!(outcome.mean_terminal_bankroll >= 40.0);
+        assert_eq!(outcome.worst_session_drawdown_pct, 0.0);
+    }
+
+    #[test]
+    fn optimal_params_returns_none_for_an_empty_range() {
+        let config = SimulatorConfig { sessions: 2, trades_per_session: 5, starting_bankroll: 40.0, seed: 1 };
+        let ranges = SearchRanges {
+            min_edge_pct: ParamRange { min: 1.0, max: 5.0, steps: 0 },
+            vol_confidence_multiplier: ParamRange { min: 2.0, max: 4.0, steps: 3 },
+            max_acceptable_drawdown_pct: 50.0,
+        };
+        let result = optimal_params(&config, &test_config(), &ranges, &mut FixedSampler(mispriced_ctx()));
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn optimal_params_sweeps_every_grid_point() {
+        let config = SimulatorConfig { sessions: 3, trades_per_session: 10, starting_bankroll: 40.0, seed: 3 };
+        let ranges = SearchRanges {
+            min_edge_pct: ParamRange { min: 1.0, max: 3.0, steps: 3 },
+            vol_confidence_multiplier: ParamRange { min: 2.0, max: 4.0, steps: 2 },
+            max_acceptable_drawdown_pct: 50.0,
+        };
+        let best = optimal_params(&config, &test_config(), &ranges, &mut FixedSampler(mispriced_ctx()))
+            .expect("expected a best candidate from a non-empty grid");
+        assert!(ranges.min_edge_pct.values().contains(&best.min_edge_pct));
+        assert!(ranges.vol_confidence_multiplier.values().contains(&best.vol_confidence_multiplier));
+    }
+
+    #[test]
+    fn param_range_collapses_to_min_with_one_step() {
+        let range = ParamRange { min: 2.0, max: 8.0, steps: 1 };
+        assert_eq!(range.values(), vec![2.0]);
+    }
+
+    #[test]
+    fn param_range_includes_both_endpoints() {
+        let range = ParamRange { min: 0.0, max: 10.0, steps: 5 };
+        let values = range.values();
+        assert_eq!(*values.first().unwrap(), 0.0);
+        assert_eq!(*values.last().unwrap(), 10.0);
+        assert_eq!(values.len(), 5);
+    }
+}
@@ -1,4 +1,7 @@
+use crate::fixedpoint::Fp;
 use crate::polymarket::Side;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use std::collections::VecDeque;
 
 /// Configuration de la stratégie (chargée depuis config.toml).
@@ -41,10 +44,129 @@ pub struct StrategyConfig {
     pub min_z_score: f64,
     /// Maximum model-vs-market divergence (0.0 = disabled). Recommended: 0.30.
     pub max_model_divergence: f64,
+    /// Spread (as a fraction, e.g. 0.02 = 2%) applied around the reference BTC
+    /// price before it feeds the entry decision, to haircut the apparent move
+    /// in volatile regimes. 0.0 disables the haircut entirely.
+    pub quote_spread_pct: f64,
+    /// Number of closed 5min intervals averaged into the ATR used for the
+    /// early-exit stop/take-profit levels. Recommended: 10-20.
+    pub atr_window: usize,
+    /// Stop-loss distance from the trailing peak/trough, in ATR multiples.
+    /// Recommended: 1.5-2.0.
+    pub exit_stop_atr_mult: f64,
+    /// Base take-profit distance from entry, in ATR multiples, before the
+    /// adaptive profit-factor scaling is applied. Recommended: 2.0-3.0.
+    pub exit_tp_atr_mult: f64,
+    /// Number of recent windows averaged into the realized profit factor
+    /// used to scale `exit_tp_atr_mult` up/down. Recommended: 10-20.
+    pub exit_tp_window: usize,
+    /// Number of recent ticks normalized into the Fisher transform.
+    /// Recommended: 20-40.
+    pub fisher_window: usize,
+    /// |Fisher| threshold above which the move is considered overextended
+    /// (dampens sizing). 0.0 disables the Fisher veto/dampening entirely.
+    /// Recommended: 1.5.
+    pub fisher_extreme_threshold: f64,
+    /// Minimum (realized − implied) volatility edge to trade, from
+    /// `digital_option::implied_vol`. 0.0 disables the gate entirely.
+    /// Recommended: 0.05-0.15.
+    pub min_vol_edge: f64,
+    /// Time-stepped minimum ROI (fraction, e.g. 0.10 = 10%) required for
+    /// `exit::evaluate_position_exit` to take an early profit exit: pairs of
+    /// `(seconds_remaining_threshold, min_roi)`, looked up by the smallest
+    /// threshold still `>= seconds_remaining` — so less profit is required
+    /// to exit as the round nears its end. Empty disables the ROI exit.
+    pub roi_table: Vec<(u64, f64)>,
+    /// Trailing-stop retrace fraction (e.g. 0.30 = exit once price gives
+    /// back 30% of the gain from the position's high-water mark). 0.0
+    /// disables the trailing stop.
+    pub trailing_stop_pct: f64,
+    /// Trailing take-profit retrace distance for `exit::evaluate_microstructure_exit`,
+    /// in basis points of the UP token price's ratchet-up high-water mark
+    /// (mirrors `trailing_stop_pct` but on the token price rather than ROI,
+    /// and in bps rather than a fraction). 0.0 disables this trailing stop.
+    pub trailing_stop_bps: f64,
+    /// Hard stop on intra-window drawdown since entry
+    /// (`WindowTicks::max_drawdown_bps`), in basis points. 0.0 disables it.
+    pub hard_stop_bps: f64,
+    /// `momentum_ratio` floor below which `exit::evaluate_microstructure_exit`
+    /// exits a live position — the move has stopped being directional.
+    /// 0.0 disables the momentum-collapse exit.
+    pub min_momentum_exit: f64,
+    /// Isotonic calibration map fit by `Calibrator::fit_isotonic`: sorted
+    /// `(raw_model_prob, calibrated_prob)` knots, applied to `true_up_prob`
+    /// via `calibrate()` before the edge/`min_implied_prob` checks when
+    /// `calibration_mode` is `CalibrationMode::Isotonic`. Empty disables
+    /// calibration (identity passthrough) even in `Isotonic` mode.
+    pub calibration_breakpoints: Vec<(f64, f64)>,
+    /// Which of `Calibrator`'s two corrections `evaluate` applies to
+    /// `true_up_prob`: the scalar `vol_confidence_multiplier` already baked
+    /// into `price_change_to_probability`, or the `calibration_breakpoints`
+    /// isotonic map on top of it. A single multiplier can't fix non-linear
+    /// miscalibration (e.g. overconfident only at the extremes), but needs
+    /// far fewer samples to fit reliably — hence it's the default.
+    pub calibration_mode: CalibrationMode,
+    /// Fraction by which `true_up_prob` is shrunk toward 0.5 before the edge
+    /// check, e.g. 0.02 means the model must clear an extra 2% margin beyond
+    /// `min_edge_pct` in every window, not just in volatile ones. 0.0
+    /// disables the haircut entirely.
+    pub safety_spread_pct: f64,
+    /// Ascending `(activation_ratio, callback_rate)` pairs for the staged
+    /// trailing stop on the held token's own sell price (see
+    /// `exit::StagedTrailingStop`), e.g. `[(0.006, 0.3), (0.012, 0.2), (0.02,
+    /// 0.1)]` arms a tighter giveback the further the trade has run in our
+    /// favor. Empty disables the staged trailing stop entirely.
+    pub trailing_stages: Vec<(f64, f64)>,
+    /// Cumulative taker fees (USDC) allowed per UTC day before new entries
+    /// are refused (`DailyBudget` in `main.rs`). 0.0 disables the cap.
+    pub daily_fee_budget: f64,
+    /// Cumulative notional (USDC) allowed per UTC day before new entries
+    /// are refused, independent of `max_bet_usdc`'s per-trade sizing. 0.0
+    /// disables the cap.
+    pub daily_max_volume: f64,
+    /// Conservative shading (fraction, e.g. 0.001 = 0.1%) applied by
+    /// `ExchangeFeed::latest()` to shrink the aggregated index price toward
+    /// zero before it reaches any call site — so `min_edge_pct` is cleared
+    /// against a haircut reference rather than the naked median. 0.0
+    /// disables the shading.
+    pub feed_spread_pct: f64,
+    /// Directional bias (fraction, e.g. 0.001 = 0.1% up) applied by
+    /// `ExchangeFeed::latest()` on top of `feed_spread_pct`, for modeling a
+    /// known feed offset rather than a symmetric haircut. 0.0 disables it.
+    pub feed_skew_pct: f64,
+    /// When `true`, settlement charges `FeeModel::Symmetric { base_rate:
+    /// symmetric_fee_base_rate }` instead of the flat per-trade `fee_pct`
+    /// quoted at entry — see `main.rs`'s `FeeModel`. Reflects Polymarket's
+    /// actual curve (taxes a near-certain price less than a 50/50 one) once
+    /// enabled; `false` keeps the flat percentage for backward compatibility.
+    pub symmetric_fee_model: bool,
+    /// `base_rate` passed to `FeeModel::Symmetric` when `symmetric_fee_model`
+    /// is set. Unused otherwise.
+    pub symmetric_fee_base_rate: f64,
+    /// Max absolute deviation (percent, e.g. 1.0 = 1%) a live feed may sit
+    /// from `price_source::validate_consensus`'s median and still count as
+    /// agreeing; the tick is skipped when fewer than two feeds agree, while a
+    /// lone outlier beside an agreeing majority is only logged.
+    /// 0.0 disables the cross-source check entirely (e.g. the Data Farm
+    /// preset, which wants every tick logged even while feeds disagree).
+    pub consensus_max_deviation_pct: f64,
+}
+
+/// Selects which `Calibrator`-fitted correction `evaluate` applies to the
+/// raw model probability. See `StrategyConfig::calibration_mode`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CalibrationMode {
+    /// Rely solely on `vol_confidence_multiplier`; `calibration_breakpoints`
+    /// is ignored even if populated.
+    #[default]
+    Multiplier,
+    /// Apply the isotonic `calibration_breakpoints` map on top of
+    /// `vol_confidence_multiplier`.
+    Isotonic,
 }
 
 /// Signal de trade émis par la stratégie.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct Signal {
     pub side: Side,
     pub edge_pct: f64,
@@ -53,6 +175,13 @@ pub struct Signal {
     pub implied_p_up: f64,
     pub size_usdc: f64,
     pub price: f64,
+    /// Market-implied volatility recovered from `market_up_price` via
+    /// `digital_option::implied_vol` (0.0 if it couldn't be recovered, e.g.
+    /// `seconds_remaining == 0`).
+    pub implied_vol: f64,
+    /// Realized vol (annualized, same units as `implied_vol`) minus implied
+    /// vol: positive means the market is underpricing the move we're seeing.
+    pub vol_edge: f64,
 }
 
 /// État de la session (P&L, nombre de trades, bankroll tracking, circuit breaker).
@@ -66,12 +195,27 @@ pub struct Session {
     recent_outcomes: VecDeque<bool>,
     /// Timestamp (unix secs) when circuit breaker was triggered. 0 = not active.
     pub circuit_breaker_until: u64,
+    /// Timestamp (unix secs) until which the session is cooling down after
+    /// hitting `session_profit_target_usdc`/`session_loss_limit_usdc`, when
+    /// `session_cooldown_s` is configured for auto-resume instead of a hard
+    /// stop. 0 = not active.
+    pub session_cooldown_until: u64,
     /// Current consecutive loss count (resets on any win).
     pub consecutive_losses: u32,
     /// Current consecutive win count (resets on any loss).
     pub consecutive_wins: u32,
     /// Minimum PnL reached during session (for drawdown calculation).
     pub min_pnl: f64,
+    /// Per-trade return series r_i = pnl_i / bankroll_at_entry_i, for Sharpe/Sortino.
+    returns: Vec<f64>,
+    /// High-water mark of the currently open position's mark price, for
+    /// `exit::evaluate_position_exit`'s trailing stop. `None` when no
+    /// position is open.
+    pub position_high_water: Option<f64>,
+    /// Sum of winning trade PnL (USDC), for profit factor/expectancy.
+    gross_win_usdc: f64,
+    /// Sum of losing trade PnL magnitude (USDC), for profit factor/expectancy.
+    gross_loss_usdc: f64,
 }
 
 impl Default for Session {
@@ -79,9 +223,14 @@ impl Default for Session {
         Self {
             pnl_usdc: 0.0, trades: 0, wins: 0, initial_bankroll: 0.0,
             recent_outcomes: VecDeque::new(), circuit_breaker_until: 0,
+            session_cooldown_until: 0,
             consecutive_losses: 0,
             consecutive_wins: 0,
             min_pnl: 0.0,
+            position_high_water: None,
+            returns: Vec::new(),
+            gross_win_usdc: 0.0,
+            gross_loss_usdc: 0.0,
         }
     }
 }
@@ -96,6 +245,10 @@ impl Session {
     }
 
     pub fn record_trade(&mut self, pnl: f64) {
+        let bankroll_at_entry = self.bankroll();
+        if bankroll_at_entry > 0.0 {
+            self.returns.push(pnl / bankroll_at_entry);
+        }
         self.pnl_usdc += pnl;
         self.trades += 1;
         // Break-even (pnl == 0.0) treated as loss: costs opportunity, resets win streak.
@@ -104,9 +257,11 @@ impl Session {
             self.wins += 1;
             self.consecutive_losses = 0;
             self.consecutive_wins += 1;
+            self.gross_win_usdc += pnl;
         } else {
             self.consecutive_losses += 1;
             self.consecutive_wins = 0;
+            self.gross_loss_usdc += -pnl;
         }
         if self.pnl_usdc < self.min_pnl {
             self.min_pnl = self.pnl_usdc;
@@ -129,9 +284,11 @@ impl Session {
     }
 
     /// Check if circuit breaker should trigger. If rolling WR is below threshold, set cooldown.
-    pub fn check_circuit_breaker(&mut self, window: usize, min_wr: f64, cooldown_secs: u64, now: u64) {
+    /// Returns true the moment it trips, so callers can fire a one-shot alert
+    /// instead of re-notifying on every tick the cooldown stays active.
+    pub fn check_circuit_breaker(&mut self, window: usize, min_wr: f64, cooldown_secs: u64, now: u64) -> bool {
         if window == 0 || min_wr <= 0.0 {
-            return;
+            return false;
         }
         if let Some(wr) = self.rolling_wr(window) {
             if wr < min_wr {
@@ -140,8 +297,10 @@ impl Session {
                     "Circuit breaker triggered: rolling WR {:.0}% < {:.0}% over {} trades. Pausing until +{}s",
                     wr * 100.0, min_wr * 100.0, window, cooldown_secs
                 );
+                return true;
             }
         }
+        false
     }
 
     /// Returns true if circuit breaker is active (should not trade).
@@ -149,6 +308,33 @@ impl Session {
         self.circuit_breaker_until > now
     }
 
+    /// Returns true once `pnl_usdc` crosses the configured session profit
+    /// target or loss limit.
+    pub fn session_limit_hit(&self, config: &StrategyConfig) -> bool {
+        self.pnl_usdc >= config.session_profit_target_usdc
+            || self.pnl_usdc <= -config.session_loss_limit_usdc
+    }
+
+    /// Begin a session cooldown: the caller keeps the process alive but
+    /// should skip trading until `is_session_cooldown` returns false again.
+    pub fn start_session_cooldown(&mut self, now: u64, cooldown_secs: u64) {
+        self.session_cooldown_until = now + cooldown_secs;
+    }
+
+    /// Returns true while a session cooldown is active.
+    pub fn is_session_cooldown(&self, now: u64) -> bool {
+        self.session_cooldown_until > now
+    }
+
+    /// Reset PnL/drawdown tracking and clear the cooldown so the session
+    /// resumes as if fresh. Lifetime counters (`trades`, `wins`, returns
+    /// series) are left untouched.
+    pub fn resume_after_cooldown(&mut self) {
+        self.pnl_usdc = 0.0;
+        self.min_pnl = 0.0;
+        self.session_cooldown_until = 0;
+    }
+
     /// Returns session drawdown as a percentage of initial bankroll.
     /// Drawdown = how far below zero PnL has gone, expressed as % of bankroll.
     pub fn session_drawdown_pct(&self) -> f64 {
@@ -157,19 +343,204 @@ impl Session {
         }
         (-self.min_pnl / self.initial_bankroll * 100.0).max(0.0)
     }
+
+    /// Ratchets `position_high_water` toward the best mark price seen so far
+    /// for an open position (highest for a long, lowest for a short).
+    /// Starts tracking from `mark_price` itself on the first call after a
+    /// position opens.
+    pub fn update_position_high_water(&mut self, side: Side, mark_price: f64) {
+        self.position_high_water = Some(match (self.position_high_water, side) {
+            (None, _) => mark_price,
+            (Some(hw), Side::Buy) => hw.max(mark_price),
+            (Some(hw), Side::Sell) => hw.min(mark_price),
+        });
+    }
+
+    /// Clears the high-water mark once a position is closed.
+    pub fn reset_position_high_water(&mut self) {
+        self.position_high_water = None;
+    }
+
+    /// Sharpe ratio of the per-trade return series: mean(r) / stddev(r).
+    /// Returns 0.0 with fewer than 2 trades or zero variance.
+    pub fn sharpe(&self) -> f64 {
+        if self.returns.len() < 2 {
+            return 0.0;
+        }
+        let n = self.returns.len() as f64;
+        let mean = self.returns.iter().sum::<f64>() / n;
+        let variance = self.returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        let stddev = variance.sqrt();
+        if stddev <= 0.0 { 0.0 } else { mean / stddev }
+    }
+
+    /// Sortino ratio: mean(r) / downside_deviation(r), where downside deviation
+    /// only penalizes negative returns (sqrt of mean of min(r_i, 0)^2).
+    pub fn sortino(&self) -> f64 {
+        if self.returns.is_empty() {
+            return 0.0;
+        }
+        let n = self.returns.len() as f64;
+        let mean = self.returns.iter().sum::<f64>() / n;
+        let downside_variance = self.returns.iter().map(|r| r.min(0.0).powi(2)).sum::<f64>() / n;
+        let downside_dev = downside_variance.sqrt();
+        if downside_dev <= 0.0 { 0.0 } else { mean / downside_dev }
+    }
+
+    /// Profit factor: Σ(winning PnL) / |Σ(losing PnL)|. Infinite if no losses yet.
+    pub fn profit_factor(&self) -> f64 {
+        if self.gross_loss_usdc <= 0.0 {
+            return if self.gross_win_usdc > 0.0 { f64::INFINITY } else { 0.0 };
+        }
+        self.gross_win_usdc / self.gross_loss_usdc
+    }
+
+    /// Expectancy per trade: avg_win·WR − avg_loss·(1−WR).
+    pub fn expectancy(&self) -> f64 {
+        if self.trades == 0 {
+            return 0.0;
+        }
+        let wr = self.win_rate();
+        let avg_win = if self.wins > 0 { self.gross_win_usdc / self.wins as f64 } else { 0.0 };
+        let losses = self.trades - self.wins;
+        let avg_loss = if losses > 0 { self.gross_loss_usdc / losses as f64 } else { 0.0 };
+        avg_win * wr - avg_loss * (1.0 - wr)
+    }
+
+    /// Calmar ratio: cumulative return / max drawdown fraction, reusing
+    /// `min_pnl`/`session_drawdown_pct` for drawdown. 0.0 if no drawdown yet.
+    pub fn calmar(&self) -> f64 {
+        let drawdown_fraction = self.session_drawdown_pct() / 100.0;
+        if drawdown_fraction <= 0.0 || self.initial_bankroll <= 0.0 {
+            return 0.0;
+        }
+        let cumulative_return = self.pnl_usdc / self.initial_bankroll;
+        cumulative_return / drawdown_fraction
+    }
+}
+
+/// Tracks an open position's accumulated shares, volume-weighted average
+/// entry price, and a fee-aware break-even price as `evaluate()` scales
+/// into the same side across successive ticks within one market window.
+/// Mirrors mango-v4's position accounting: average entry and break-even
+/// are recomputed incrementally on each add, and reset (along with the
+/// side) by any fill on the opposite side, whatever its size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    pub side: Side,
+    pub shares: f64,
+    pub avg_entry_price: f64,
+    break_even_price: f64,
+    /// Best price (in the held token's own terms — UP for `Side::Buy`,
+    /// DOWN for `Side::Sell`) reached since entry; ratchets up only.
+    /// Feeds `exit::evaluate_microstructure_exit`'s trailing take-profit.
+    high_water_price: f64,
+}
+
+impl Position {
+    /// Opens a position from a single fill of `shares` at `price`, having
+    /// paid `fee_usdc` in taker fees.
+    pub fn new(side: Side, shares: f64, price: f64, fee_usdc: f64) -> Self {
+        let mut pos = Self { side, shares: 0.0, avg_entry_price: price, break_even_price: price, high_water_price: price };
+        pos.add_fill(side, shares, price, fee_usdc);
+        pos
+    }
+
+    /// Folds a new fill into the position. If `side` matches the current
+    /// position (or the position is flat), this is a scale-in: avg entry
+    /// and break-even are recomputed as the shares-weighted average of the
+    /// old and new cost bases. If `side` is the opposite direction, the
+    /// position flips: the old shares/avg-entry/break-even are discarded
+    /// and replaced by the new fill alone. The caller is responsible for
+    /// realizing any PnL on the discarded side (via `unrealized_pnl`)
+    /// before calling this.
+    pub fn add_fill(&mut self, side: Side, shares: f64, price: f64, fee_usdc: f64) {
+        if shares <= 0.0 {
+            return;
+        }
+        if self.shares <= 0.0 || side == self.side {
+            let total_shares = self.shares + shares;
+            let old_cost = self.shares * self.avg_entry_price;
+            let old_break_even_cost = self.shares * self.break_even_price;
+            let new_break_even_cost = shares * price + fee_usdc;
+            self.avg_entry_price = (old_cost + shares * price) / total_shares;
+            self.break_even_price = (old_break_even_cost + new_break_even_cost) / total_shares;
+            self.shares = total_shares;
+            self.side = side;
+        } else {
+            self.side = side;
+            self.shares = shares;
+            self.avg_entry_price = price;
+            self.break_even_price = price + fee_usdc / shares;
+            self.high_water_price = price;
+        }
+    }
+
+    /// Ratchets the high-water mark toward the best price (in the held
+    /// token's own terms) seen since entry. Call once per tick while the
+    /// position is open, before `exit::evaluate_microstructure_exit`.
+    pub fn update_high_water(&mut self, current_price: f64) {
+        self.high_water_price = self.high_water_price.max(current_price);
+    }
+
+    /// Best price reached since entry — see `update_high_water`.
+    pub fn high_water_price(&self) -> f64 {
+        self.high_water_price
+    }
+
+    /// Unrealized PnL in USDC at `current_price`, net of the fees already
+    /// paid on entry — zero exactly at `break_even_price`.
+    pub fn unrealized_pnl(&self, current_price: f64) -> f64 {
+        self.shares * (current_price - self.break_even_price)
+    }
+
+    /// The price at which this position's unrealized PnL is exactly zero,
+    /// once entry fees are accounted for.
+    pub fn break_even_price(&self) -> f64 {
+        self.break_even_price
+    }
 }
 
 /// Suit la volatilité réalisée sur les derniers intervalles 5min.
 #[derive(Debug)]
 pub struct VolTracker {
     recent_moves: VecDeque<f64>,
+    /// True range (high − low) de chaque intervalle 5min terminé, pour l'ATR.
+    recent_ranges: VecDeque<f64>,
     max_samples: usize,
     default_vol: f64,
 }
 
 impl VolTracker {
     pub fn new(max_samples: usize, default_vol: f64) -> Self {
-        Self { recent_moves: VecDeque::with_capacity(max_samples), max_samples, default_vol }
+        Self {
+            recent_moves: VecDeque::with_capacity(max_samples),
+            recent_ranges: VecDeque::with_capacity(max_samples),
+            max_samples,
+            default_vol,
+        }
+    }
+
+    /// Enregistre le true range (high − low) d'un intervalle 5min terminé.
+    pub fn record_range(&mut self, high: f64, low: f64) {
+        if high < low {
+            return;
+        }
+        self.recent_ranges.push_back(high - low);
+        if self.recent_ranges.len() > self.max_samples {
+            self.recent_ranges.pop_front();
+        }
+    }
+
+    /// ATR (Average True Range) — moyenne mobile des `window` derniers true
+    /// ranges enregistrés. Retourne 0.0 si aucune donnée.
+    pub fn atr(&self, window: usize) -> f64 {
+        if self.recent_ranges.is_empty() || window == 0 {
+            return 0.0;
+        }
+        let n = window.min(self.recent_ranges.len());
+        let sum: f64 = self.recent_ranges.iter().rev().take(n).sum();
+        sum / n as f64
     }
 
     /// Enregistre le mouvement de prix d'un intervalle terminé (% signé).
@@ -188,12 +559,17 @@ impl VolTracker {
         if self.recent_moves.len() < 3 {
             return self.default_vol;
         }
-        let mut sorted: Vec<f64> = self.recent_moves.iter().copied().collect();
-        sorted.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        // Fp has a total order, so this sorts directly — no
+        // `partial_cmp(...).unwrap_or(Equal)` hack needed for the NaN case
+        // `f64::partial_cmp` can't rule out.
+        let mut sorted: Vec<Fp> = self.recent_moves.iter().map(|&x| Fp::from_f64(x)).collect();
+        sorted.sort_unstable();
         let median = sorted[sorted.len() / 2];
-        let mut deviations: Vec<f64> = sorted.iter().map(|x| (x - median).abs()).collect();
-        deviations.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
-        let mad = deviations[deviations.len() / 2];
+        let mut deviations: Vec<Fp> = sorted.iter()
+            .map(|&x| x.checked_sub(median).unwrap_or(Fp::ZERO).abs())
+            .collect();
+        deviations.sort_unstable();
+        let mad = deviations[deviations.len() / 2].to_f64();
         // MAD → std dev: σ ≈ 1.4826 × MAD (for normal distribution)
         (1.4826 * mad).clamp(0.01, 1.0)
     }
@@ -205,6 +581,12 @@ impl VolTracker {
 pub struct WindowTicks {
     prices: Vec<f64>,
     timestamps_ms: Vec<u64>,
+    /// EMA-smoothed normalized value feeding the Fisher transform.
+    fisher_ema: f64,
+    /// Fisher value returned by the most recent `fisher()` call.
+    last_fisher: f64,
+    /// Fisher value from the call before that, for zero-crossing detection.
+    prev_fisher: f64,
 }
 
 impl WindowTicks {
@@ -212,6 +594,9 @@ impl WindowTicks {
         Self {
             prices: Vec::with_capacity(3200),
             timestamps_ms: Vec::with_capacity(3200),
+            fisher_ema: 0.0,
+            last_fisher: 0.0,
+            prev_fisher: 0.0,
         }
     }
 
@@ -220,7 +605,41 @@ impl WindowTicks {
         self.timestamps_ms.push(timestamp_ms);
     }
 
+    /// Fisher transform over the last `window` ticks — sharper at turning
+    /// points than `micro_vol`/`momentum_ratio`. Normalizes the latest price
+    /// into the window's range, EMA-smooths it, then applies the Fisher
+    /// transform. Tracks the previous call's result so callers can detect a
+    /// zero crossing or an extreme reading; call once per tick.
+    pub fn fisher(&mut self, window: usize) -> f64 {
+        if self.prices.len() < 2 || window < 2 {
+            return self.last_fisher;
+        }
+        let n = window.min(self.prices.len());
+        let slice = &self.prices[self.prices.len() - n..];
+        let min = slice.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = slice.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        if max <= min {
+            return self.last_fisher;
+        }
+        let last = *slice.last().unwrap();
+        let v = 2.0 * (last - min) / (max - min) - 1.0;
+        const SMOOTHING: f64 = 0.33;
+        self.fisher_ema = SMOOTHING * v + (1.0 - SMOOTHING) * self.fisher_ema;
+        let clamped = self.fisher_ema.clamp(-0.999, 0.999);
+        self.prev_fisher = self.last_fisher;
+        self.last_fisher = 0.5 * ((1.0 + clamped) / (1.0 - clamped)).ln();
+        self.last_fisher
+    }
+
+    /// Fisher value from the call before the most recent one.
+    pub fn fisher_prev(&self) -> f64 {
+        self.prev_fisher
+    }
+
     pub fn clear(&mut self) {
+        self.fisher_ema = 0.0;
+        self.last_fisher = 0.0;
+        self.prev_fisher = 0.0;
         self.prices.clear();
         self.timestamps_ms.clear();
     }
@@ -289,6 +708,39 @@ impl WindowTicks {
         max_dd
     }
 
+    /// Dernier prix enregistré dans la fenêtre, si disponible.
+    pub fn last_price(&self) -> Option<f64> {
+        self.prices.last().copied()
+    }
+
+    /// Plus haut / plus bas de la fenêtre entière, pour l'ATR (true range).
+    pub fn high_low(&self) -> Option<(f64, f64)> {
+        let (&first, rest) = self.prices.split_first()?;
+        let mut high = first;
+        let mut low = first;
+        for &p in rest {
+            if p > high { high = p; }
+            if p < low { low = p; }
+        }
+        Some((high, low))
+    }
+
+    /// Plus haut prix enregistré depuis `since_ts_ms`, pour le trailing stop long.
+    pub fn peak_since(&self, since_ts_ms: u64) -> Option<f64> {
+        self.timestamps_ms.iter().zip(&self.prices)
+            .filter(|(&ts, _)| ts >= since_ts_ms)
+            .map(|(_, &p)| p)
+            .fold(None, |acc: Option<f64>, p| Some(acc.map_or(p, |a| a.max(p))))
+    }
+
+    /// Plus bas prix enregistré depuis `since_ts_ms`, pour le trailing stop short.
+    pub fn trough_since(&self, since_ts_ms: u64) -> Option<f64> {
+        self.timestamps_ms.iter().zip(&self.prices)
+            .filter(|(&ts, _)| ts >= since_ts_ms)
+            .map(|(_, &p)| p)
+            .fold(None, |acc: Option<f64>, p| Some(acc.map_or(p, |a| a.min(p))))
+    }
+
     /// Seconds the price spent at or above start_price.
     pub fn time_above_start_s(&self, start_price: f64) -> u64 {
         if self.timestamps_ms.len() < 2 { return 0; }
@@ -302,6 +754,81 @@ impl WindowTicks {
     }
 }
 
+/// Seed for `Calibrator::bootstrap_ci`'s resampling RNG — fixed (rather
+/// than time-based) so repeated calls over an unchanged `entries` window
+/// return the same interval.
+const BOOTSTRAP_SEED: u64 = 0x6272_6965_7200_0001;
+
+/// Brier Score of a `(predicted_prob, outcome)` sample.
+fn brier_score_of(entries: &[(f64, bool)]) -> f64 {
+    if entries.is_empty() {
+        return 1.0;
+    }
+    let sum: f64 = entries.iter()
+        .map(|(p, won)| {
+            let outcome = if *won { 1.0 } else { 0.0 };
+            (p - outcome).powi(2)
+        })
+        .sum();
+    sum / entries.len() as f64
+}
+
+/// Grid-searches the vol_confidence_multiplier that minimizes Brier Score
+/// over `entries`, rescaling each predicted probability's distance from 0.5
+/// by `ref_vcm / candidate_mult`. Returns `(best_multiplier, best_brier)`;
+/// `ref_vcm` (used as the fallback best multiplier) if `entries` is empty.
+fn best_multiplier_of(entries: &[(f64, bool)], ref_vcm: f64) -> (f64, f64) {
+    if entries.is_empty() {
+        return (ref_vcm, f64::MAX);
+    }
+    let multipliers: Vec<f64> = (2..=16).map(|i| i as f64 * 0.5).collect();
+    let mut best_mult = ref_vcm;
+    let mut best_brier = f64::MAX;
+
+    for &mult in &multipliers {
+        let brier: f64 = entries.iter()
+            .map(|(p, won)| {
+                let adjusted_p = 0.5 + (*p - 0.5) * (ref_vcm / mult);
+                let adjusted_p = adjusted_p.clamp(0.001, 0.999);
+                let outcome = if *won { 1.0 } else { 0.0 };
+                (adjusted_p - outcome).powi(2)
+            })
+            .sum::<f64>() / entries.len() as f64;
+
+        if brier < best_brier {
+            best_brier = brier;
+            best_mult = mult;
+        }
+    }
+
+    (best_mult, best_brier)
+}
+
+/// Linearly-interpolated percentile (0-100) of an already-sorted slice.
+fn percentile(sorted: &[f64], pct: f64) -> f64 {
+    if sorted.len() == 1 {
+        return sorted[0];
+    }
+    let rank = (pct / 100.0) * (sorted.len() - 1) as f64;
+    let lo = rank.floor() as usize;
+    let hi = rank.ceil() as usize;
+    if lo == hi {
+        return sorted[lo];
+    }
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (rank - lo as f64)
+}
+
+/// Bootstrap-resampled confidence interval: 2.5th/97.5th percentiles
+/// of a statistic recomputed over many resamples-with-replacement of the
+/// same size as the original data, alongside the statistic's own
+/// point estimate on the unresampled data.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConfidenceInterval {
+    pub lower: f64,
+    pub point: f64,
+    pub upper: f64,
+}
+
 /// Auto-calibration: tracks (predicted_prob, actual_outcome) pairs and
 /// recalibrates vol_confidence_multiplier by minimizing Brier Score.
 #[derive(Debug)]
@@ -309,6 +836,10 @@ pub struct Calibrator {
     entries: Vec<(f64, bool)>,
     recalibrate_every: usize,
     current_vcm: f64,
+    /// Curve returned by the most recent `fit_isotonic` call, cached so
+    /// `reliability_curve` can be logged (e.g. as a reliability diagram)
+    /// without refitting.
+    last_isotonic: Vec<(f64, f64)>,
 }
 
 impl Calibrator {
@@ -317,9 +848,18 @@ impl Calibrator {
             entries: Vec::with_capacity(recalibrate_every + 10),
             recalibrate_every,
             current_vcm: 1.0,
+            last_isotonic: Vec::new(),
         }
     }
 
+    /// The isotonic curve fitted by the most recent `fit_isotonic` call
+    /// (empty if it was never called or had no entries to fit), for
+    /// logging a reliability diagram.
+    #[allow(dead_code)]
+    pub fn reliability_curve(&self) -> &[(f64, f64)] {
+        &self.last_isotonic
+    }
+
     pub fn set_current_vcm(&mut self, vcm: f64) {
         self.current_vcm = vcm;
     }
@@ -338,18 +878,8 @@ impl Calibrator {
     }
 
     /// Brier Score on current entries.
-    #[allow(dead_code)]
     pub fn brier_score(&self) -> f64 {
-        if self.entries.is_empty() {
-            return 1.0;
-        }
-        let sum: f64 = self.entries.iter()
-            .map(|(p, won)| {
-                let outcome = if *won { 1.0 } else { 0.0 };
-                (p - outcome).powi(2)
-            })
-            .sum();
-        sum / self.entries.len() as f64
+        brier_score_of(&self.entries)
     }
 
     /// Grid-search the optimal vol_confidence_multiplier that minimizes Brier Score.
@@ -359,30 +889,136 @@ impl Calibrator {
         if self.entries.is_empty() {
             return None;
         }
+        let best = best_multiplier_of(&self.entries, self.current_vcm);
+        self.entries.clear();
+        Some(best)
+    }
 
-        let multipliers: Vec<f64> = (2..=16).map(|i| i as f64 * 0.5).collect();
-        let mut best_mult = self.current_vcm;
-        let mut best_brier = f64::MAX;
+    /// Bootstrap confidence interval on `brier_score`: resamples `entries`
+    /// with replacement `b` times (seeded, so repeated calls over an
+    /// unchanged window are reproducible), recomputes the Brier score on
+    /// each resample, and returns the 2.5th/97.5th percentiles around the
+    /// unresampled Brier score as `point` — the
+    /// same resampling idea Criterion uses for its benchmark statistics.
+    /// Lets callers tell a five-sample window's point estimate from a
+    /// thousand-sample window's before trusting it. `None` with no entries
+    /// or `b == 0`.
+    pub fn brier_ci(&self, b: usize) -> Option<ConfidenceInterval> {
+        self.bootstrap_ci(b, brier_score_of)
+    }
+
+    /// Bootstrap confidence interval on the `recalibrate`-chosen optimal
+    /// vol_confidence_multiplier, resampled the same way as `brier_ci`.
+    pub fn multiplier_ci(&self, b: usize) -> Option<ConfidenceInterval> {
         let ref_vcm = self.current_vcm;
+        self.bootstrap_ci(b, |sample| best_multiplier_of(sample, ref_vcm).0)
+    }
+
+    fn bootstrap_ci(&self, b: usize, score_of: impl Fn(&[(f64, bool)]) -> f64) -> Option<ConfidenceInterval> {
+        if self.entries.is_empty() || b == 0 {
+            return None;
+        }
+        let point = score_of(&self.entries);
+        let n = self.entries.len();
+        let mut rng = StdRng::seed_from_u64(BOOTSTRAP_SEED);
+        let mut scores: Vec<f64> = (0..b)
+            .map(|_| {
+                let resample: Vec<(f64, bool)> = (0..n)
+                    .map(|_| self.entries[rng.random_range(0..n)])
+                    .collect();
+                score_of(&resample)
+            })
+            .collect();
+        scores.sort_by(|a, b| a.total_cmp(b));
+        Some(ConfidenceInterval {
+            lower: percentile(&scores, 2.5),
+            point,
+            upper: percentile(&scores, 97.5),
+        })
+    }
+
+    /// Fits a monotonic probability calibration map over the recorded
+    /// entries with the Pool-Adjacent-Violators Algorithm: sort by predicted
+    /// probability, start each entry as its own block (weight 1, value =
+    /// outcome), then repeatedly merge adjacent blocks whose weighted means
+    /// violate monotonicity until the sequence of block means is
+    /// non-decreasing. Returns the resulting step function as
+    /// `(mean_predicted_prob, calibrated_prob)` knots, clamped to `(EPS, 1 -
+    /// EPS)` so an all-win/all-loss window can't collapse the map to an
+    /// absolute 0 or 1. Leaves `entries` intact like `brier_score` (unlike
+    /// `recalibrate`, doesn't clear them; it only caches the curve for
+    /// `reliability_curve`) so both can run over the same recalibration
+    /// window; empty with no recorded entries.
+    pub fn fit_isotonic(&mut self) -> Vec<(f64, f64)> {
+        const EPS: f64 = 1e-4;
 
-        for &mult in &multipliers {
-            let brier: f64 = self.entries.iter()
-                .map(|(p, won)| {
-                    let adjusted_p = 0.5 + (*p - 0.5) * (ref_vcm / mult);
-                    let adjusted_p = adjusted_p.clamp(0.001, 0.999);
-                    let outcome = if *won { 1.0 } else { 0.0 };
-                    (adjusted_p - outcome).powi(2)
-                })
-                .sum::<f64>() / self.entries.len() as f64;
-
-            if brier < best_brier {
-                best_brier = brier;
-                best_mult = mult;
+        if self.entries.is_empty() {
+            self.last_isotonic = Vec::new();
+            return Vec::new();
+        }
+
+        let mut sorted = self.entries.clone();
+        sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        struct Block { weight: f64, sum_x: f64, sum_y: f64 }
+
+        let mut blocks: Vec<Block> = Vec::with_capacity(sorted.len());
+        for (p, won) in sorted {
+            blocks.push(Block { weight: 1.0, sum_x: p, sum_y: if won { 1.0 } else { 0.0 } });
+            while blocks.len() >= 2 {
+                let n = blocks.len();
+                let mean_prev = blocks[n - 2].sum_y / blocks[n - 2].weight;
+                let mean_last = blocks[n - 1].sum_y / blocks[n - 1].weight;
+                if mean_last >= mean_prev {
+                    break;
+                }
+                let last = blocks.pop().unwrap();
+                let prev = blocks.pop().unwrap();
+                blocks.push(Block {
+                    weight: prev.weight + last.weight,
+                    sum_x: prev.sum_x + last.sum_x,
+                    sum_y: prev.sum_y + last.sum_y,
+                });
             }
         }
 
-        self.entries.clear();
-        Some((best_mult, best_brier))
+        let curve: Vec<(f64, f64)> = blocks.iter()
+            .map(|b| (b.sum_x / b.weight, (b.sum_y / b.weight).clamp(EPS, 1.0 - EPS)))
+            .collect();
+        self.last_isotonic = curve.clone();
+        curve
+    }
+}
+
+/// Interpolates `raw_prob` through the isotonic calibration map fit by
+/// `Calibrator::fit_isotonic` (sorted `(raw_prob, calibrated_prob)` knots),
+/// falling back to identity with an empty map. Flat-extrapolates beyond the
+/// map's first/last knot; binary-searches for the bracketing pair of knots
+/// otherwise and linearly interpolates between them.
+pub fn calibrate(raw_prob: f64, breakpoints: &[(f64, f64)]) -> f64 {
+    match breakpoints {
+        [] => raw_prob,
+        [(_, only)] => *only,
+        _ => {
+            if raw_prob <= breakpoints[0].0 {
+                return breakpoints[0].1;
+            }
+            let last = breakpoints.len() - 1;
+            if raw_prob >= breakpoints[last].0 {
+                return breakpoints[last].1;
+            }
+            // partition_point finds the first knot whose x is > raw_prob —
+            // i.e. the right edge of the bracketing pair (guaranteed to be
+            // in 1..=last by the two early-outs above).
+            let hi = breakpoints.partition_point(|&(x, _)| x <= raw_prob);
+            let (x0, y0) = breakpoints[hi - 1];
+            let (x1, y1) = breakpoints[hi];
+            if x1 <= x0 {
+                return y1;
+            }
+            let t = (raw_prob - x0) / (x1 - x0);
+            y0 + t * (y1 - y0)
+        }
     }
 }
 
@@ -402,6 +1038,13 @@ pub struct TradeContext {
     pub num_ws_sources: u32,
     pub micro_vol: f64,
     pub momentum_ratio: f64,
+    /// Current Fisher-transform turning-point value (from `WindowTicks::fisher`).
+    pub fisher: f64,
+    /// Fisher value from the prior call, for zero-crossing detection.
+    pub fisher_prev: f64,
+    /// Worst intra-window drawdown from peak, in basis points (from
+    /// `WindowTicks::max_drawdown_bps`).
+    pub max_drawdown_bps: f64,
 }
 
 /// Évalue si on doit trader sur cet intervalle.
@@ -449,6 +1092,18 @@ pub fn evaluate(
     let price_change_pct = (current_price - ctx.start_price) / ctx.start_price * 100.0;
 
     let true_up_prob = price_change_to_probability(price_change_pct, ctx.seconds_remaining, ctx.vol_5min_pct, config.vol_confidence_multiplier, config.student_t_df);
+    // In Isotonic mode, correct systematic over/under-confidence (measured
+    // by the Calibrator's Brier score) with the isotonic map, before it
+    // feeds the edge/min_implied_prob checks. Multiplier mode relies solely
+    // on vol_confidence_multiplier above and leaves true_up_prob as-is.
+    let true_up_prob = match config.calibration_mode {
+        CalibrationMode::Isotonic => calibrate(true_up_prob, &config.calibration_breakpoints),
+        CalibrationMode::Multiplier => true_up_prob,
+    };
+    // Safety spread: shrink the fair probability toward 0.5 so every entry
+    // requires genuine edge beyond a deliberate margin, independent of
+    // min_edge_pct.
+    let true_up_prob = 0.5 + (true_up_prob - 0.5) * (1.0 - config.safety_spread_pct);
     let true_down_prob = 1.0 - true_up_prob;
     let market_down_price = 1.0 - ctx.market_up_price;
 
@@ -456,7 +1111,11 @@ pub fn evaluate(
     if config.min_z_score > 0.0 {
         let remaining_vol = ctx.vol_5min_pct * config.vol_confidence_multiplier * ((ctx.seconds_remaining as f64) / 300.0).sqrt();
         if remaining_vol > 1e-9 {
-            let z_abs = (price_change_pct / remaining_vol).abs();
+            let z_abs = Fp::from_f64(price_change_pct)
+                .checked_div(Fp::from_f64(remaining_vol))
+                .unwrap_or(Fp::ZERO)
+                .abs()
+                .to_f64();
             if z_abs < config.min_z_score {
                 tracing::debug!("Skip: |z| {:.3} < {:.1} (noise)", z_abs, config.min_z_score);
                 return None;
@@ -464,6 +1123,21 @@ pub fn evaluate(
         }
     }
 
+    // 4b2. Vol edge: recover the market's implied vol from market_up_price
+    // (cash-or-nothing digital call) and compare to the realized vol this
+    // round is actually seeing. Falls back to 0.0 (gate disabled for this
+    // round) when seconds_remaining == 0, matching price_change_to_probability's
+    // own T=0 direction-lock fallback.
+    let t_years = crate::digital_option::seconds_to_years(ctx.seconds_remaining);
+    let implied_vol = crate::digital_option::implied_vol(current_price, ctx.start_price, ctx.market_up_price, t_years)
+        .unwrap_or(0.0);
+    let realized_vol = crate::digital_option::annualize_vol_5min_pct(ctx.vol_5min_pct);
+    let vol_edge = realized_vol - implied_vol;
+    if config.min_vol_edge > 0.0 && ctx.seconds_remaining > 0 && vol_edge < config.min_vol_edge {
+        tracing::debug!("Skip: vol edge {:.3} < min {:.3} (realized {:.3}, implied {:.3})", vol_edge, config.min_vol_edge, realized_vol, implied_vol);
+        return None;
+    }
+
     // 4c. Model-vs-market divergence sanity check
     if config.max_model_divergence > 0.0 {
         let model_market_divergence = (true_up_prob - ctx.market_up_price).abs();
@@ -506,6 +1180,8 @@ pub fn evaluate(
             implied_p_up: true_up_prob,
             size_usdc: size,
             price: market_price,
+            implied_vol,
+            vol_edge,
         });
     }
 
@@ -534,6 +1210,20 @@ pub fn evaluate(
         return None;
     }
 
+    // 7b2. Fisher transform reversal veto — skip if the turning-point
+    // oscillator just crossed zero against this trade's direction (a buy
+    // wants fisher > 0, a sell wants fisher < 0).
+    if config.fisher_extreme_threshold > 0.0 && ctx.fisher_prev != 0.0 && ctx.fisher.signum() != ctx.fisher_prev.signum() {
+        let against_direction = match side {
+            Side::Buy => ctx.fisher < 0.0,
+            Side::Sell => ctx.fisher > 0.0,
+        };
+        if against_direction {
+            tracing::debug!("Skip: fisher crossover against direction ({:.3} <- {:.3})", ctx.fisher, ctx.fisher_prev);
+            return None;
+        }
+    }
+
     // Cohérence Chainlink / exchanges — skip si divergence directionnelle
     if let Some(ex_price) = ctx.exchange_price {
         let cl_move_pct = ((ctx.chainlink_price - ctx.start_price) / ctx.start_price).abs();
@@ -589,6 +1279,9 @@ pub fn evaluate(
     if ctx.vol_5min_pct > 0.0 && ctx.micro_vol > ctx.vol_5min_pct * 2.0 {
         regime_factor *= 0.6;
     }
+    if config.fisher_extreme_threshold > 0.0 && ctx.fisher.abs() > config.fisher_extreme_threshold {
+        regime_factor *= 0.5;
+    }
     let kelly_size = (kelly_size * regime_factor).min(config.max_bet_usdc);
 
     let min_usdc = (config.min_shares as f64 * market_price).max(config.min_bet_usdc);
@@ -622,6 +1315,8 @@ pub fn evaluate(
         implied_p_up: true_up_prob,
         size_usdc: size,
         price: market_price,
+        implied_vol,
+        vol_edge,
     })
 }
 
@@ -631,22 +1326,51 @@ pub fn evaluate(
 /// Returns fee as fraction of cost (per dollar invested = fee / (C×p) = feeRate × [p(1-p)]^2).
 /// Max fee: 1.56% at p=0.50, drops to ~0% at extremes.
 pub fn dynamic_fee(price: f64, fee_rate: f64) -> f64 {
-    let p_q = price * (1.0 - price);
-    fee_rate * p_q.powi(2)
+    let price_fp = Fp::from_f64(price);
+    let fee_rate_fp = Fp::from_f64(fee_rate);
+    let one_minus_price = Fp::ONE.checked_sub(price_fp).unwrap_or(Fp::ZERO);
+    let p_q = price_fp.checked_mul(one_minus_price).unwrap_or(Fp::ZERO);
+    let p_q_sq = p_q.checked_mul(p_q).unwrap_or(Fp::ZERO);
+    fee_rate_fp.checked_mul(p_q_sq).unwrap_or(Fp::ZERO).to_f64()
 }
 
-/// Probabilité UP time-aware — modèle hybride prix + imbalance.
-/// Calcule P(UP) à partir du mouvement de prix et de la vol résiduelle.
-/// Utilise le z-score pur (sans book imbalance — le book Polymarket est un signal
-/// de liquidité, pas un signal directionnel sur BTC).
-fn price_change_to_probability(pct_change: f64, seconds_remaining: u64, vol_5min_pct: f64, confidence_multiplier: f64, student_t_df: f64) -> f64 {
+/// Probabilité UP time-aware — digital option sous GBM (drift de Itô inclus).
+/// Calcule P(S_T > S_0) via le d2 de l'option digitale cash-or-nothing
+/// (`digital_option::endpoint_z`), plutôt qu'un z-score brut pct_change/vol —
+/// ça inclut la correction de drift -½σ²τ que le z-score pur omet. N'utilise
+/// pas le book imbalance — le book Polymarket est un signal de liquidité,
+/// pas un signal directionnel sur BTC.
+pub(crate) fn price_change_to_probability(pct_change: f64, seconds_remaining: u64, vol_5min_pct: f64, confidence_multiplier: f64, student_t_df: f64) -> f64 {
     let remaining_vol = vol_5min_pct * confidence_multiplier * ((seconds_remaining as f64) / 300.0).sqrt();
 
     if remaining_vol < 1e-9 {
         return if pct_change > 0.0 { 1.0 } else if pct_change < 0.0 { 0.0 } else { 0.5 };
     }
 
-    let z = pct_change / remaining_vol;
+    // A pct_change of -100% or worse has no log-return (price at or below
+    // zero) — that's a certain DOWN move, not something endpoint_z can take.
+    if 1.0 + pct_change / 100.0 <= 0.0 {
+        return 0.0;
+    }
+
+    let z = crate::digital_option::endpoint_z(pct_change, seconds_remaining, vol_5min_pct, confidence_multiplier);
+    if !z.is_finite() {
+        return 0.5;
+    }
+
+    // Mirrors `fixedpoint::normal_cdf`'s own saturation threshold: beyond
+    // |z| = 37 the limiting probability is returned directly, so the
+    // Student-t branch (which has no such guard inside `statrs`) can't be
+    // handed a z-score extreme enough to underflow or misbehave there —
+    // this is the common case when `seconds_remaining` is tiny and
+    // `remaining_vol` shrinks toward zero.
+    const Z_SATURATION: f64 = 37.0;
+    if z >= Z_SATURATION {
+        return 1.0;
+    }
+    if z <= -Z_SATURATION {
+        return 0.0;
+    }
 
     if student_t_df > 0.0 {
         use statrs::distribution::{StudentsT, ContinuousCDF};
@@ -658,31 +1382,56 @@ fn price_change_to_probability(pct_change: f64, seconds_remaining: u64, vol_5min
 }
 
 /// Approximation de la CDF normale (Abramowitz & Stegun, erreur max 1.5e-7).
-fn normal_cdf(x: f64) -> f64 {
-    let t = 1.0 / (1.0 + 0.2316419 * x.abs());
-    let d = 0.3989422804014327; // 1/sqrt(2*pi)
-    let p = d * (-x * x / 2.0).exp()
-        * (t * (0.319381530
-            + t * (-0.356563782
-                + t * (1.781477937 + t * (-1.821255978 + t * 1.330274429)))));
-    if x >= 0.0 { 1.0 - p } else { p }
+/// Routed through `fixedpoint::normal_cdf` so repeated calls with the same
+/// `x` are bit-identical across platforms/optimization levels — the same
+/// determinism guarantee the rest of this decision path gets from `Fp`.
+pub(crate) fn normal_cdf(x: f64) -> f64 {
+    crate::fixedpoint::normal_cdf(Fp::from_f64(x)).to_f64()
 }
 
 /// Fractional Kelly Criterion with fee-adjusted payout.
 /// Uses b_net = (1-price)/price - fee to account for taker fees in the Kelly formula.
 /// Sizes based on current bankroll, clamped to max_bet.
-fn fractional_kelly(p: f64, price: f64, fee_rate: f64, kelly_fraction: f64, bankroll: f64, max_bet: f64) -> f64 {
+pub(crate) fn fractional_kelly(p: f64, price: f64, fee_rate: f64, kelly_fraction: f64, bankroll: f64, max_bet: f64) -> f64 {
     if price <= 0.0 || price >= 1.0 || p <= 0.0 || p >= 1.0 || bankroll <= 0.0 {
         return 0.0;
     }
     let fee = dynamic_fee(price, fee_rate);
-    let b_net = (1.0 - price) / price - fee;
-    if b_net <= 0.0 {
+    let price_fp = Fp::from_f64(price);
+    let fee_fp = Fp::from_f64(fee);
+
+    let payout = match Fp::ONE.checked_sub(price_fp).and_then(|v| v.checked_div(price_fp)) {
+        Some(v) => v,
+        None => return 0.0,
+    };
+    let b_net = match payout.checked_sub(fee_fp) {
+        Some(v) => v,
+        None => return 0.0,
+    };
+    if b_net.is_zero() || b_net.is_negative() {
         return 0.0;
     }
-    let q = 1.0 - p;
-    let kelly = (b_net * p - q) / b_net;
-    (kelly * kelly_fraction * bankroll).clamp(0.0, max_bet)
+
+    let p_fp = Fp::from_f64(p);
+    let q_fp = match Fp::ONE.checked_sub(p_fp) {
+        Some(v) => v,
+        None => return 0.0,
+    };
+    let numerator = match b_net.checked_mul(p_fp).and_then(|v| v.checked_sub(q_fp)) {
+        Some(v) => v,
+        None => return 0.0,
+    };
+    let kelly = match numerator.checked_div(b_net) {
+        Some(v) => v,
+        None => return 0.0,
+    };
+
+    let sized = kelly
+        .checked_mul(Fp::from_f64(kelly_fraction))
+        .and_then(|v| v.checked_mul(Fp::from_f64(bankroll)))
+        .unwrap_or(Fp::ZERO)
+        .to_f64();
+    sized.clamp(0.0, max_bet)
 }
 
 #[cfg(test)]
@@ -719,6 +1468,30 @@ mod tests {
             student_t_df: 0.0,
             min_z_score: 0.0,
             max_model_divergence: 0.0,
+            quote_spread_pct: 0.0,
+            atr_window: 10,
+            exit_stop_atr_mult: 0.0,
+            exit_tp_atr_mult: 0.0,
+            exit_tp_window: 10,
+            fisher_window: 20,
+            fisher_extreme_threshold: 0.0,
+            min_vol_edge: 0.0,
+            roi_table: Vec::new(),
+            trailing_stop_pct: 0.0,
+            trailing_stop_bps: 0.0,
+            hard_stop_bps: 0.0,
+            min_momentum_exit: 0.0,
+            calibration_breakpoints: Vec::new(),
+            calibration_mode: CalibrationMode::Multiplier,
+            safety_spread_pct: 0.0,
+            trailing_stages: Vec::new(),
+            daily_fee_budget: 0.0,
+            daily_max_volume: 0.0,
+            feed_spread_pct: 0.0,
+            feed_skew_pct: 0.0,
+            symmetric_fee_model: false,
+            symmetric_fee_base_rate: 0.0,
+            consensus_max_deviation_pct: 0.0,
         }
     }
 
@@ -739,6 +1512,9 @@ mod tests {
             num_ws_sources: 3,
             micro_vol: 0.0,
             momentum_ratio: 1.0,
+            fisher: 0.0,
+            fisher_prev: 0.0,
+            max_drawdown_bps: 0.0,
         }
     }
 
@@ -931,6 +1707,26 @@ mod tests {
         assert!((fee - 0.000564).abs() < 0.0001, "got {fee}");
     }
 
+    #[test]
+    fn dynamic_fee_is_deterministic_across_repeated_calls() {
+        // Same inputs must always produce the exact same bits, not just an
+        // approximately-equal float — that's the whole point of routing
+        // this through Fp instead of raw f64 multiplication.
+        let a = dynamic_fee(0.63, 0.25);
+        let b = dynamic_fee(0.63, 0.25);
+        assert_eq!(a.to_bits(), b.to_bits());
+    }
+
+    #[test]
+    fn fractional_kelly_never_returns_nan_or_infinite() {
+        for price in [0.001, 0.5, 0.999] {
+            for p in [0.001, 0.5, 0.999] {
+                let size = fractional_kelly(p, price, 0.25, 0.2, 40.0, 5.0);
+                assert!(size.is_finite(), "price={price} p={p} -> {size}");
+            }
+        }
+    }
+
     #[test]
     fn evaluate_rejects_when_fee_exceeds_edge() {
         let config = test_config();
@@ -1321,6 +2117,30 @@ mod tests {
             student_t_df: 4.0,
             min_z_score: 0.5,
             max_model_divergence: 0.30,
+            quote_spread_pct: 0.02,
+            atr_window: 12,
+            exit_stop_atr_mult: 1.5,
+            exit_tp_atr_mult: 2.5,
+            exit_tp_window: 12,
+            fisher_window: 30,
+            fisher_extreme_threshold: 1.5,
+            min_vol_edge: 0.0,
+            roi_table: vec![(300, 0.15), (120, 0.08), (30, 0.03)],
+            trailing_stop_pct: 0.30,
+            trailing_stop_bps: 150.0,
+            hard_stop_bps: 250.0,
+            min_momentum_exit: 0.3,
+            calibration_breakpoints: Vec::new(),
+            calibration_mode: CalibrationMode::Multiplier,
+            safety_spread_pct: 0.0,
+            trailing_stages: Vec::new(),
+            daily_fee_budget: 0.0,
+            daily_max_volume: 0.0,
+            feed_spread_pct: 0.0,
+            feed_skew_pct: 0.0,
+            symmetric_fee_model: false,
+            symmetric_fee_base_rate: 0.0,
+            consensus_max_deviation_pct: 0.0,
         }
     }
 
@@ -1345,6 +2165,9 @@ mod tests {
             num_ws_sources: 3,
             micro_vol: 0.0,
             momentum_ratio: 1.0,
+            fisher: 0.0,
+            fisher_prev: 0.0,
+            max_drawdown_bps: 0.0,
         };
         let signal = evaluate(&ctx, &session, &config);
         assert!(signal.is_some(), "should trade with +0.05% at 8s");
@@ -1374,6 +2197,9 @@ mod tests {
             num_ws_sources: 3,
             micro_vol: 0.0,
             momentum_ratio: 1.0,
+            fisher: 0.0,
+            fisher_prev: 0.0,
+            max_drawdown_bps: 0.0,
         };
         let signal = evaluate(&ctx, &session, &config);
         assert!(signal.is_none(), "weak +0.005% should not pass 3% min edge");
@@ -1398,6 +2224,9 @@ mod tests {
             num_ws_sources: 3,
             micro_vol: 0.0,
             momentum_ratio: 1.0,
+            fisher: 0.0,
+            fisher_prev: 0.0,
+            max_drawdown_bps: 0.0,
         };
         let signal = evaluate(&ctx, &session, &config);
         if let Some(s) = signal {
@@ -1426,6 +2255,9 @@ mod tests {
             num_ws_sources: 3,
             micro_vol: 0.0,
             momentum_ratio: 1.0,
+            fisher: 0.0,
+            fisher_prev: 0.0,
+            max_drawdown_bps: 0.0,
         };
         let signal = evaluate(&ctx, &session, &config);
         assert!(signal.is_none(), "should stop after -$10 (25% of $40 portfolio)");
@@ -1451,6 +2283,9 @@ mod tests {
             num_ws_sources: 3,
             micro_vol: 0.0,
             momentum_ratio: 1.0,
+            fisher: 0.0,
+            fisher_prev: 0.0,
+            max_drawdown_bps: 0.0,
         };
         let signal = evaluate(&ctx, &session, &config);
         assert!(signal.is_none(), "wide spread should kill the edge below 3% min");
@@ -1867,6 +2702,33 @@ mod tests {
             "should be symmetric: p_up={p_up} p_down={p_down}");
     }
 
+    #[test]
+    fn extreme_z_saturates_to_one_and_zero_for_normal() {
+        // pct_change/remaining_vol ratio far beyond the |z| = 37 threshold.
+        let p_up = price_change_to_probability(1000.0, 10, 0.0001, 1.0, 0.0);
+        let p_down = price_change_to_probability(-1000.0, 10, 0.0001, 1.0, 0.0);
+        assert_eq!(p_up, 1.0);
+        assert_eq!(p_down, 0.0);
+    }
+
+    #[test]
+    fn extreme_z_saturates_to_one_and_zero_for_student_t() {
+        let p_up = price_change_to_probability(1000.0, 10, 0.0001, 1.0, 4.0);
+        let p_down = price_change_to_probability(-1000.0, 10, 0.0001, 1.0, 4.0);
+        assert_eq!(p_up, 1.0);
+        assert_eq!(p_down, 0.0);
+    }
+
+    #[test]
+    fn tiny_seconds_remaining_with_extreme_z_does_not_produce_nan() {
+        // 1 second remaining shrinks `remaining_vol` to ~5.8e-4 (still above
+        // the 1e-9 guard), producing a huge |z| through the normal arithmetic
+        // path rather than the seconds_remaining == 0 short-circuit.
+        let p = price_change_to_probability(0.1, 1, 0.01, 1.0, 0.0);
+        assert_eq!(p, 1.0);
+        assert!(!p.is_nan());
+    }
+
     #[test]
     fn window_ticks_micro_vol_directional() {
         let mut wt = WindowTicks::new();
@@ -1964,6 +2826,134 @@ mod tests {
         assert_eq!(wt.ticks_count(), 3);
     }
 
+    #[test]
+    fn window_ticks_high_low() {
+        let mut wt = WindowTicks::new();
+        wt.tick(100.0, 0);
+        wt.tick(101.5, 100);
+        wt.tick(99.5, 200);
+        assert_eq!(wt.high_low(), Some((101.5, 99.5)));
+    }
+
+    #[test]
+    fn window_ticks_high_low_empty() {
+        let wt = WindowTicks::new();
+        assert_eq!(wt.high_low(), None);
+    }
+
+    #[test]
+    fn window_ticks_peak_since_ignores_earlier_ticks() {
+        let mut wt = WindowTicks::new();
+        wt.tick(100.0, 0);
+        wt.tick(105.0, 100); // before `since` — should be ignored
+        wt.tick(101.0, 200);
+        wt.tick(103.0, 300);
+        assert_eq!(wt.peak_since(200), Some(103.0));
+    }
+
+    #[test]
+    fn window_ticks_trough_since_ignores_earlier_ticks() {
+        let mut wt = WindowTicks::new();
+        wt.tick(100.0, 0);
+        wt.tick(95.0, 100); // before `since` — should be ignored
+        wt.tick(99.0, 200);
+        wt.tick(97.0, 300);
+        assert_eq!(wt.trough_since(200), Some(97.0));
+    }
+
+    #[test]
+    fn window_ticks_last_price() {
+        let mut wt = WindowTicks::new();
+        wt.tick(100.0, 0);
+        wt.tick(101.0, 100);
+        assert_eq!(wt.last_price(), Some(101.0));
+    }
+
+    // --- WindowTicks Fisher transform ---
+
+    #[test]
+    fn fisher_zero_with_too_few_ticks() {
+        let mut wt = WindowTicks::new();
+        wt.tick(100.0, 0);
+        assert_eq!(wt.fisher(20), 0.0);
+    }
+
+    #[test]
+    fn fisher_positive_near_window_high() {
+        let mut wt = WindowTicks::new();
+        for (i, &p) in [100.0, 100.5, 101.0, 102.0, 103.0].iter().enumerate() {
+            wt.tick(p, i as u64 * 100);
+        }
+        // Extra ticks at the high keep the latest price at the window's top
+        // for the single `fisher` call below.
+        for _ in 0..5 {
+            wt.tick(103.0, 0);
+        }
+        assert!(wt.fisher(20) > 0.0);
+    }
+
+    #[test]
+    fn fisher_negative_near_window_low() {
+        let mut wt = WindowTicks::new();
+        for (i, &p) in [103.0, 102.0, 101.0, 100.5, 100.0].iter().enumerate() {
+            wt.tick(p, i as u64 * 100);
+        }
+        for _ in 0..5 {
+            wt.tick(100.0, 0);
+        }
+        assert!(wt.fisher(20) < 0.0);
+    }
+
+    #[test]
+    fn fisher_prev_tracks_previous_call() {
+        let mut wt = WindowTicks::new();
+        for (i, &p) in [100.0, 101.0, 102.0].iter().enumerate() {
+            wt.tick(p, i as u64 * 100);
+        }
+        let first = wt.fisher(20);
+        wt.tick(103.0, 300);
+        let second = wt.fisher(20);
+        assert_eq!(wt.fisher_prev(), first);
+        assert_ne!(second, first);
+    }
+
+    #[test]
+    fn fisher_resets_on_clear() {
+        let mut wt = WindowTicks::new();
+        for (i, &p) in [100.0, 101.0, 102.0, 103.0].iter().enumerate() {
+            wt.tick(p, i as u64 * 100);
+        }
+        wt.fisher(20);
+        wt.clear();
+        assert_eq!(wt.fisher_prev(), 0.0);
+    }
+
+    // --- VolTracker ATR ---
+
+    #[test]
+    fn vol_tracker_atr_averages_recent_ranges() {
+        let mut vt = VolTracker::new(20, DEFAULT_VOL);
+        vt.record_range(102.0, 100.0); // range 2.0
+        vt.record_range(104.0, 100.0); // range 4.0
+        vt.record_range(103.0, 100.0); // range 3.0
+        assert!((vt.atr(3) - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn vol_tracker_atr_uses_only_last_window() {
+        let mut vt = VolTracker::new(20, DEFAULT_VOL);
+        vt.record_range(110.0, 100.0); // range 10.0, should fall out of the window
+        vt.record_range(102.0, 100.0); // range 2.0
+        vt.record_range(104.0, 100.0); // range 4.0
+        assert!((vt.atr(2) - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn vol_tracker_atr_zero_with_no_data() {
+        let vt = VolTracker::new(20, DEFAULT_VOL);
+        assert_eq!(vt.atr(10), 0.0);
+    }
+
     // --- Calibrator ---
 
     #[test]
@@ -2031,6 +3021,206 @@ mod tests {
         assert!(!cal.should_recalibrate());
     }
 
+    #[test]
+    fn fit_isotonic_is_empty_with_no_entries() {
+        let mut cal = Calibrator::new(5);
+        assert!(cal.fit_isotonic().is_empty());
+    }
+
+    #[test]
+    fn fit_isotonic_pools_a_monotonicity_violation() {
+        let mut cal = Calibrator::new(5);
+        // 0.5 loses while the lower-predicted 0.4 wins: a violation that PAVA
+        // should pool into a single block with the blended mean (0.5).
+        cal.record(0.4, true);
+        cal.record(0.5, false);
+        let breakpoints = cal.fit_isotonic();
+        let means: Vec<f64> = breakpoints.iter().map(|&(_, y)| y).collect();
+        for pair in means.windows(2) {
+            assert!(pair[0] <= pair[1], "calibration map must be monotonic: {means:?}");
+        }
+    }
+
+    #[test]
+    fn fit_isotonic_clamps_an_all_win_window() {
+        let mut cal = Calibrator::new(5);
+        cal.record(0.6, true);
+        cal.record(0.7, true);
+        cal.record(0.8, true);
+        let breakpoints = cal.fit_isotonic();
+        for &(_, y) in &breakpoints {
+            assert!(y < 1.0, "all-win window must not saturate to exactly 1.0: {y}");
+        }
+    }
+
+    #[test]
+    fn fit_isotonic_does_not_clear_entries() {
+        let mut cal = Calibrator::new(5);
+        cal.record(0.6, true);
+        cal.record(0.7, false);
+        cal.fit_isotonic();
+        assert_eq!(cal.count(), 2);
+    }
+
+    #[test]
+    fn calibrate_is_identity_with_an_empty_map() {
+        assert_eq!(calibrate(0.73, &[]), 0.73);
+    }
+
+    #[test]
+    fn calibrate_interpolates_between_knots() {
+        let breakpoints = [(0.3, 0.2), (0.7, 0.6)];
+        let mid = calibrate(0.5, &breakpoints);
+        assert!((mid - 0.4).abs() < 1e-9, "midpoint should interpolate linearly: {mid}");
+    }
+
+    #[test]
+    fn calibrate_flat_extrapolates_beyond_the_knots() {
+        let breakpoints = [(0.3, 0.2), (0.7, 0.6)];
+        assert_eq!(calibrate(0.1, &breakpoints), 0.2);
+        assert_eq!(calibrate(0.9, &breakpoints), 0.6);
+    }
+
+    #[test]
+    fn multiplier_mode_ignores_calibration_breakpoints() {
+        let config = test_config();
+        let session = Session::new(40.0);
+        let ctx = TradeContext { chainlink_price: 100_050.0, ..test_ctx() };
+        // A deliberately hostile map that would crush any calibrated prob
+        // toward 0.5, wiping out the edge — Multiplier mode must not apply it.
+        let breakpoints = vec![(0.0, 0.5), (1.0, 0.5)];
+        let baseline = evaluate(&ctx, &session, &config).unwrap();
+        let with_map = evaluate(
+            &ctx,
+            &session,
+            &StrategyConfig { calibration_breakpoints: breakpoints, ..config },
+        )
+        .unwrap();
+        assert!((with_map.edge_pct - baseline.edge_pct).abs() < 1e-9);
+    }
+
+    #[test]
+    fn isotonic_mode_applies_calibration_breakpoints() {
+        let config = StrategyConfig { calibration_mode: CalibrationMode::Isotonic, ..test_config() };
+        let session = Session::new(40.0);
+        let ctx = TradeContext { chainlink_price: 100_050.0, ..test_ctx() };
+        let uncalibrated = evaluate(&ctx, &session, &config).unwrap();
+        // Flatten every raw prob to 0.5: the edge collapses and no signal fires.
+        let flattened = StrategyConfig {
+            calibration_breakpoints: vec![(0.0, 0.5), (1.0, 0.5)],
+            ..config
+        };
+        assert!(uncalibrated.edge_pct > 0.0);
+        assert!(evaluate(&ctx, &session, &flattened).is_none());
+    }
+
+    #[test]
+    fn safety_spread_shrinks_edge_toward_zero() {
+        let session = Session::new(40.0);
+        let ctx = TradeContext { chainlink_price: 100_050.0, ..test_ctx() };
+        let baseline = evaluate(&ctx, &session, &test_config()).unwrap();
+        let haircut = StrategyConfig { safety_spread_pct: 0.5, ..test_config() };
+        let shrunk = evaluate(&ctx, &session, &haircut).unwrap();
+        assert!(shrunk.edge_pct < baseline.edge_pct);
+    }
+
+    #[test]
+    fn safety_spread_can_reject_an_otherwise_tradeable_edge() {
+        let session = Session::new(40.0);
+        let ctx = TradeContext { chainlink_price: 100_050.0, ..test_ctx() };
+        assert!(evaluate(&ctx, &session, &test_config()).is_some());
+        // Near-total haircut flattens true_up_prob to ~0.5, so the edge over
+        // the market price collapses below min_edge_pct even though the
+        // un-hairecut signal traded fine.
+        let config = StrategyConfig { safety_spread_pct: 0.99, ..test_config() };
+        assert!(evaluate(&ctx, &session, &config).is_none());
+    }
+
+    #[test]
+    fn brier_ci_is_none_with_no_entries() {
+        let cal = Calibrator::new(5);
+        assert!(cal.brier_ci(1000).is_none());
+    }
+
+    #[test]
+    fn brier_ci_is_none_with_zero_resamples() {
+        let mut cal = Calibrator::new(5);
+        cal.record(0.7, true);
+        assert!(cal.brier_ci(0).is_none());
+    }
+
+    #[test]
+    fn brier_ci_point_matches_brier_score() {
+        let mut cal = Calibrator::new(5);
+        cal.record(0.7, true);
+        cal.record(0.6, false);
+        cal.record(0.8, true);
+        let ci = cal.brier_ci(200).unwrap();
+        assert_eq!(ci.point, cal.brier_score());
+        assert!(ci.lower <= ci.point + 1e-9);
+        assert!(ci.upper + 1e-9 >= ci.point);
+    }
+
+    #[test]
+    fn brier_ci_is_reproducible_across_calls() {
+        let mut cal = Calibrator::new(5);
+        for i in 0..10 {
+            cal.record(0.5 + i as f64 * 0.02, i % 3 == 0);
+        }
+        let a = cal.brier_ci(500).unwrap();
+        let b = cal.brier_ci(500).unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn brier_ci_narrows_with_a_larger_sample_of_the_same_mix() {
+        // A 50/50 mix of a very-right prediction and a very-wrong one: each
+        // resample's mean swings a lot when there are only 2 entries to draw
+        // from, but converges to the same ~0.41 blend once there are 100.
+        let mut small = Calibrator::new(5);
+        small.record(0.9, true);
+        small.record(0.9, false);
+        let small_ci = small.brier_ci(2000).unwrap();
+
+        let mut large = Calibrator::new(5);
+        for _ in 0..50 {
+            large.record(0.9, true);
+            large.record(0.9, false);
+        }
+        let large_ci = large.brier_ci(2000).unwrap();
+
+        assert!(
+            (large_ci.upper - large_ci.lower) < (small_ci.upper - small_ci.lower),
+            "more data from the same mix should bootstrap a tighter interval"
+        );
+    }
+
+    #[test]
+    fn multiplier_ci_does_not_clear_entries() {
+        let mut cal = Calibrator::new(5);
+        cal.record(0.7, true);
+        cal.record(0.3, false);
+        cal.multiplier_ci(100);
+        assert_eq!(cal.count(), 2);
+    }
+
+    #[test]
+    fn multiplier_ci_point_matches_a_fresh_recalibrate() {
+        let mut cal = Calibrator::new(5);
+        cal.set_current_vcm(3.0);
+        cal.record(0.75, true);
+        cal.record(0.25, false);
+        cal.record(0.8, true);
+        let ci = cal.multiplier_ci(300).unwrap();
+        let mut clone = Calibrator::new(5);
+        clone.set_current_vcm(3.0);
+        clone.record(0.75, true);
+        clone.record(0.25, false);
+        clone.record(0.8, true);
+        let (expected_mult, _) = clone.recalibrate().unwrap();
+        assert_eq!(ci.point, expected_mult);
+    }
+
     #[test]
     fn regime_choppy_reduces_sizing() {
         let config = StrategyConfig { min_edge_pct: 1.0, ..test_config() };
@@ -2039,6 +3229,8 @@ mod tests {
             chainlink_price: 100_050.0,
             micro_vol: 0.001,
             momentum_ratio: 0.9,
+            fisher: 0.0,
+            fisher_prev: 0.0,
             ..test_ctx()
         };
         let sig_good = evaluate(&ctx_good, &session, &config);
@@ -2047,6 +3239,8 @@ mod tests {
             chainlink_price: 100_050.0,
             micro_vol: 0.001,
             momentum_ratio: 0.45,
+            fisher: 0.0,
+            fisher_prev: 0.0,
             ..test_ctx()
         };
         let sig_choppy = evaluate(&ctx_choppy, &session, &config);
@@ -2080,6 +3274,146 @@ mod tests {
         assert!((s.session_drawdown_pct() - 12.5).abs() < 0.01);
     }
 
+    #[test]
+    fn profit_factor_ratio_of_gross_win_to_loss() {
+        let mut s = Session::new(40.0);
+        s.record_trade(10.0);
+        s.record_trade(-5.0);
+        assert!((s.profit_factor() - 2.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn profit_factor_infinite_with_no_losses() {
+        let mut s = Session::new(40.0);
+        s.record_trade(10.0);
+        assert_eq!(s.profit_factor(), f64::INFINITY);
+    }
+
+    #[test]
+    fn expectancy_combines_avg_win_loss_and_win_rate() {
+        let mut s = Session::new(40.0);
+        s.record_trade(10.0); // win
+        s.record_trade(-5.0); // loss
+        // WR=0.5, avg_win=10, avg_loss=5 -> 10*0.5 - 5*0.5 = 2.5
+        assert!((s.expectancy() - 2.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn sharpe_zero_with_fewer_than_two_trades() {
+        let mut s = Session::new(40.0);
+        s.record_trade(10.0);
+        assert_eq!(s.sharpe(), 0.0);
+    }
+
+    #[test]
+    fn sharpe_positive_for_consistently_winning_trades() {
+        let mut s = Session::new(40.0);
+        s.record_trade(4.0);
+        s.record_trade(2.0);
+        s.record_trade(4.0);
+        assert!(s.sharpe() > 0.0);
+    }
+
+    #[test]
+    fn sortino_ignores_positive_returns_in_downside_deviation() {
+        let mut s = Session::new(40.0);
+        s.record_trade(10.0);
+        s.record_trade(10.0);
+        // No negative returns at all -> downside deviation is 0 -> defined as 0.0
+        assert_eq!(s.sortino(), 0.0);
+    }
+
+    #[test]
+    fn sortino_penalizes_losing_trades() {
+        let mut s = Session::new(40.0);
+        s.record_trade(10.0);
+        s.record_trade(-10.0);
+        assert!(s.sortino() != 0.0);
+    }
+
+    #[test]
+    fn calmar_zero_with_no_drawdown() {
+        let mut s = Session::new(40.0);
+        s.record_trade(10.0);
+        assert_eq!(s.calmar(), 0.0);
+    }
+
+    #[test]
+    fn calmar_relates_cumulative_return_to_drawdown() {
+        let mut s = Session::new(40.0);
+        s.record_trade(-5.0);
+        s.record_trade(10.0);
+        // drawdown = 5/40 = 12.5%, cumulative return = 5/40 = 12.5% -> calmar = 1.0
+        assert!((s.calmar() - 1.0).abs() < 0.01);
+    }
+
+    // --- Position ---
+
+    #[test]
+    fn position_break_even_is_above_entry_price_for_a_buy() {
+        let pos = Position::new(Side::Buy, 10.0, 0.5, 0.05);
+        assert_eq!(pos.avg_entry_price, 0.5);
+        assert!(pos.break_even_price() > 0.5, "fees should push break-even above entry");
+        assert_eq!(pos.break_even_price(), 0.5 + 0.05 / 10.0);
+    }
+
+    #[test]
+    fn position_unrealized_pnl_is_zero_at_break_even() {
+        let pos = Position::new(Side::Buy, 10.0, 0.5, 0.05);
+        let be = pos.break_even_price();
+        assert!(pos.unrealized_pnl(be).abs() < 1e-12);
+    }
+
+    #[test]
+    fn position_unrealized_pnl_is_positive_above_break_even_and_negative_below() {
+        let pos = Position::new(Side::Buy, 10.0, 0.5, 0.05);
+        assert!(pos.unrealized_pnl(0.9) > 0.0);
+        assert!(pos.unrealized_pnl(0.1) < 0.0);
+    }
+
+    #[test]
+    fn position_scale_in_recomputes_weighted_avg_entry() {
+        let mut pos = Position::new(Side::Buy, 10.0, 0.40, 0.0);
+        pos.add_fill(Side::Buy, 10.0, 0.60, 0.0);
+        // Equal-sized fills at 0.40 and 0.60 -> weighted avg 0.50.
+        assert!((pos.avg_entry_price - 0.50).abs() < 1e-9, "got {}", pos.avg_entry_price);
+        assert_eq!(pos.shares, 20.0);
+    }
+
+    #[test]
+    fn position_scale_in_accumulates_fee_aware_break_even() {
+        let mut pos = Position::new(Side::Buy, 10.0, 0.40, 0.10);
+        pos.add_fill(Side::Buy, 10.0, 0.40, 0.10);
+        // Same price both fills -> break-even sits at price + total fee / total shares.
+        let expected = 0.40 + 0.20 / 20.0;
+        assert!((pos.break_even_price() - expected).abs() < 1e-9, "got {}", pos.break_even_price());
+    }
+
+    #[test]
+    fn position_flip_resets_side_and_avg_entry_to_the_new_fill() {
+        let mut pos = Position::new(Side::Buy, 10.0, 0.40, 0.0);
+        pos.add_fill(Side::Sell, 5.0, 0.70, 0.0);
+        assert_eq!(pos.side, Side::Sell);
+        assert_eq!(pos.shares, 5.0);
+        assert_eq!(pos.avg_entry_price, 0.70);
+    }
+
+    #[test]
+    fn position_high_water_ratchets_up_only() {
+        let mut pos = Position::new(Side::Buy, 10.0, 0.40, 0.0);
+        pos.update_high_water(0.60);
+        pos.update_high_water(0.50);
+        assert_eq!(pos.high_water_price(), 0.60, "should stay at the peak, not fall back");
+    }
+
+    #[test]
+    fn position_flip_resets_high_water_to_the_new_fill() {
+        let mut pos = Position::new(Side::Buy, 10.0, 0.40, 0.0);
+        pos.update_high_water(0.80);
+        pos.add_fill(Side::Sell, 5.0, 0.30, 0.0);
+        assert_eq!(pos.high_water_price(), 0.30);
+    }
+
     #[test]
     fn regime_high_microvol_reduces_sizing() {
         let config = StrategyConfig { min_edge_pct: 1.0, ..test_config() };
@@ -2089,6 +3423,8 @@ mod tests {
             vol_5min_pct: 0.10,
             micro_vol: 0.05,
             momentum_ratio: 0.9,
+            fisher: 0.0,
+            fisher_prev: 0.0,
             ..test_ctx()
         };
         let sig_normal = evaluate(&ctx_normal, &session, &config);
@@ -2098,6 +3434,8 @@ mod tests {
             vol_5min_pct: 0.10,
             micro_vol: 0.25,
             momentum_ratio: 0.9,
+            fisher: 0.0,
+            fisher_prev: 0.0,
             ..test_ctx()
         };
         let sig_high = evaluate(&ctx_high, &session, &config);
@@ -2108,4 +3446,113 @@ mod tests {
                 "high micro_vol should reduce sizing");
         }
     }
+
+    #[test]
+    fn fisher_crossover_against_direction_vetoes_signal() {
+        let config = StrategyConfig { min_edge_pct: 1.0, fisher_extreme_threshold: 1.5, ..test_config() };
+        let session = Session::new(40.0);
+        // Price moved up (buy-side edge), but the fisher oscillator just
+        // flipped from positive to negative — a bearish reversal crossing
+        // against the long direction.
+        let ctx = TradeContext {
+            chainlink_price: 100_050.0,
+            fisher: -0.3,
+            fisher_prev: 0.2,
+            ..test_ctx()
+        };
+        assert!(evaluate(&ctx, &session, &config).is_none());
+    }
+
+    #[test]
+    fn fisher_crossover_with_direction_does_not_veto() {
+        let config = StrategyConfig { min_edge_pct: 1.0, fisher_extreme_threshold: 1.5, ..test_config() };
+        let session = Session::new(40.0);
+        // Crossover flips into positive territory, which agrees with a long.
+        let ctx = TradeContext {
+            chainlink_price: 100_050.0,
+            fisher: 0.3,
+            fisher_prev: -0.2,
+            ..test_ctx()
+        };
+        assert!(evaluate(&ctx, &session, &config).is_some());
+    }
+
+    #[test]
+    fn fisher_disabled_at_zero_threshold_ignores_crossover() {
+        let config = StrategyConfig { min_edge_pct: 1.0, fisher_extreme_threshold: 0.0, ..test_config() };
+        let session = Session::new(40.0);
+        let ctx = TradeContext {
+            chainlink_price: 100_050.0,
+            fisher: -0.3,
+            fisher_prev: 0.2,
+            ..test_ctx()
+        };
+        assert!(evaluate(&ctx, &session, &config).is_some());
+    }
+
+    #[test]
+    fn fisher_extreme_reduces_sizing() {
+        let config = StrategyConfig { min_edge_pct: 1.0, fisher_extreme_threshold: 1.5, ..test_config() };
+        let session = Session::new(40.0);
+        let ctx_normal = TradeContext {
+            chainlink_price: 100_050.0,
+            fisher: 0.5,
+            fisher_prev: 0.4,
+            ..test_ctx()
+        };
+        let sig_normal = evaluate(&ctx_normal, &session, &config);
+
+        let ctx_extreme = TradeContext {
+            chainlink_price: 100_050.0,
+            fisher: 2.0,
+            fisher_prev: 1.9,
+            ..test_ctx()
+        };
+        let sig_extreme = evaluate(&ctx_extreme, &session, &config);
+
+        assert!(sig_normal.is_some());
+        if let Some(se) = sig_extreme {
+            assert!(se.size_usdc <= sig_normal.unwrap().size_usdc,
+                "overextended fisher reading should reduce sizing");
+        }
+    }
+
+    // --- vol edge (digital_option) ---
+
+    #[test]
+    fn signal_reports_finite_implied_vol_and_vol_edge() {
+        let config = StrategyConfig { min_edge_pct: 1.0, ..test_config() };
+        let session = Session::new(40.0);
+        let ctx = TradeContext { chainlink_price: 100_050.0, ..test_ctx() };
+        let sig = evaluate(&ctx, &session, &config).expect("expected a signal");
+        assert!(sig.implied_vol.is_finite() && sig.implied_vol >= 0.0);
+        assert!(sig.vol_edge.is_finite());
+    }
+
+    #[test]
+    fn min_vol_edge_gate_blocks_when_requirement_unreachable() {
+        let config = StrategyConfig { min_edge_pct: 1.0, min_vol_edge: 1_000.0, ..test_config() };
+        let session = Session::new(40.0);
+        let ctx = TradeContext { chainlink_price: 100_050.0, ..test_ctx() };
+        assert!(evaluate(&ctx, &session, &config).is_none());
+    }
+
+    #[test]
+    fn min_vol_edge_disabled_does_not_block() {
+        let config = StrategyConfig { min_edge_pct: 1.0, min_vol_edge: 0.0, ..test_config() };
+        let session = Session::new(40.0);
+        let ctx = TradeContext { chainlink_price: 100_050.0, ..test_ctx() };
+        assert!(evaluate(&ctx, &session, &config).is_some());
+    }
+
+    #[test]
+    fn min_vol_edge_gate_does_not_block_when_seconds_remaining_is_zero() {
+        // At seconds_remaining == 0 the direction-locked probability is
+        // exactly 1.0, which `fractional_kelly` sizes at 0 after the gate;
+        // always_trade returns the signal right after the gate instead.
+        let config = StrategyConfig { min_edge_pct: 1.0, min_vol_edge: 5.0, always_trade: true, ..test_config() };
+        let session = Session::new(40.0);
+        let ctx = TradeContext { chainlink_price: 100_050.0, seconds_remaining: 0, ..test_ctx() };
+        assert!(evaluate(&ctx, &session, &config).is_some());
+    }
 }